@@ -21,8 +21,23 @@ pub fn setup_local_client<N: Network>() -> AleoAPIClient<N> {
 }
 
 pub fn setup_client<N: Network>() -> AvailResult<AleoAPIClient<N>> {
+    let base_url = node_url()?;
+
+    println!("Base URL: {:?}", base_url);
+
+    let api_client = AleoAPIClient::<N>::new(&base_url, "testnet")?;
+
+    Ok(api_client)
+}
+
+/// Url of the node the wallet is currently configured to use, without the network path.
+pub fn node_url() -> AvailResult<String> {
+    base_url_for(&get_base_url()?)
+}
+
+fn base_url_for(base_url: &str) -> AvailResult<String> {
     let node_api_obscura = env!("TESTNET_API_OBSCURA");
-    let base_url = match get_base_url()?.as_str() {
+    let base_url = match base_url {
         "obscura" => format!(
             "https://aleo-testnetbeta.obscura.network/v1/{}",
             node_api_obscura
@@ -37,11 +52,52 @@ pub fn setup_client<N: Network>() -> AvailResult<AleoAPIClient<N>> {
         }
     };
 
-    println!("Base URL: {:?}", base_url);
+    Ok(base_url)
+}
 
-    let api_client = AleoAPIClient::<N>::new(&base_url, "testnet")?;
+/// Node urls in the order requests try them, the configured node first
+pub fn node_urls() -> AvailResult<Vec<String>> {
+    let configured = get_base_url()?;
+    let mut urls = vec![base_url_for(&configured)?];
 
-    Ok(api_client)
+    for base_url in ["obscura", "aleo"] {
+        if base_url != configured {
+            urls.push(base_url_for(base_url)?);
+        }
+    }
+
+    Ok(urls)
+}
+
+/// Sends a read request to the configured node and retries it on the other nodes when it fails.
+/// Answers like a missing value should be returned as `Ok` by `request` so they aren't retried.
+pub fn with_failover<T>(request: impl Fn(&str) -> AvailResult<T>) -> AvailResult<T> {
+    let mut errors = vec![];
+    let mut last_error = None;
+
+    for url in node_urls()? {
+        match request(&url) {
+            Ok(response) => return Ok(response),
+            Err(e) => {
+                errors.push(format!("{}: {}", url, e.internal_msg));
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let (error_type, external_msg) = match last_error {
+        Some(e) => (e.error_type, e.external_msg),
+        None => (
+            avail_common::errors::AvailErrorType::Node,
+            "The network could not be reached".to_string(),
+        ),
+    };
+
+    Err(AvailError::new(
+        error_type,
+        format!("Every node failed: {}", errors.join("; ")),
+        external_msg,
+    ))
 }
 
 pub fn setup_obscura_client<N: Network>() -> AvailResult<AleoAPIClient<N>> {
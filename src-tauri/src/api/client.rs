@@ -143,6 +143,11 @@ impl Session {
         *token_write = Some(token);
     }
 
+    pub fn clear_session_token(&self) {
+        let mut token_write = self.session.write().unwrap();
+        *token_write = None;
+    }
+
     pub fn get_session_token(&self) -> Option<String> {
         let token_read = self.session.read().unwrap();
         token_read.clone()
@@ -1,61 +1,14 @@
-use tauri_plugin_http::reqwest;
+use crate::models::token::TokenMetadata;
+use crate::services::local_storage::token_metadata::get_token_metadata_list;
 
-use crate::helpers::utils::HOST;
-
-use avail_common::{
-    errors::{AvailError, AvailErrorType, AvailResult},
-    models::tokens::Token,
-};
-//TODO - Update for V2
+use avail_common::errors::AvailResult;
 
 /* --TOKENS-- */
 
-/// Fetches list of tokens to be listed to user in ui, for example for swaps.
-#[tokio::main(flavor = "current_thread")]
-pub async fn get_token_list() -> AvailResult<Vec<Token>> {
-    let client = reqwest::Client::new();
-
-    let res = match client
-        .get(format!("http://{}:8100/token", HOST))
-        .send()
-        .await
-    {
-        Ok(res) => res,
-        Err(e) => {
-            return Err(AvailError::new(
-                AvailErrorType::External,
-                e.to_string(),
-                "Error getting token list.".to_string(),
-            ));
-        }
-    };
-
-    if res.status() == 200 {
-        let result: Vec<Token> = match res.json().await {
-            Ok(res) => res,
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::External,
-                    e.to_string(),
-                    "Error getting token list.".to_string(),
-                ));
-            }
-        };
-
-        Ok(result)
-    } else if res.status() == 401 {
-        Err(AvailError::new(
-            AvailErrorType::Unauthorized,
-            "User session has expired.".to_string(),
-            "Your session has expired, please authenticate again.".to_string(),
-        ))
-    } else {
-        Err(AvailError::new(
-            AvailErrorType::External,
-            "Error getting token list.".to_string(),
-            "Error getting token list.".to_string(),
-        ))
-    }
+/// List of tokens to be listed to user in ui, for example for swaps.
+/// Comes from the local token metadata registry, which is refreshed from the configured token list source.
+pub fn get_token_list() -> AvailResult<Vec<TokenMetadata>> {
+    get_token_metadata_list()
 }
 
 #[cfg(test)]
@@ -66,6 +19,7 @@ mod tests {
     #[test]
     fn test_get_token_list() {
         let result = get_token_list().unwrap();
-        println!("{:?}", result);
+
+        assert!(result.iter().any(|token| token.asset_id == "credits"));
     }
 }
@@ -1,6 +1,8 @@
 use super::validation;
 use chrono::{DateTime, Local, NaiveDateTime, Utc};
-use snarkvm::prelude::{Ciphertext, Network, PrivateKey};
+use snarkvm::prelude::{Ciphertext, FromStr, Network, PrivateKey};
+
+use crate::models::storage::envelope::{is_envelope, open_with_password, EnvelopeKey, KdfParams};
 
 use avail_common::{
     aleo_tools::encryptor::Encryptor,
@@ -9,23 +11,29 @@ use avail_common::{
 
 /// This should be moved to utils and will most likely be deprecated.
 /// Deprication depends on android local storage.
-pub fn encrypt_key<N: Network>(key: PrivateKey<N>, secret: &str) -> AvailResult<Ciphertext<N>> {
+/// The key is sealed in an envelope with a salt of its own.
+pub fn encrypt_key<N: Network>(key: PrivateKey<N>, secret: &str) -> AvailResult<String> {
     validation::validate_private_key(&key.to_string())?;
     validation::validate_secret_password(secret)?;
 
-    let encrypted_private_key = Encryptor::<N>::encrypt_private_key_with_secret(&key, secret)?;
+    let encrypted_private_key =
+        EnvelopeKey::derive(secret, &KdfParams::generate())?.seal(&key.to_string())?;
 
     Ok(encrypted_private_key)
 }
 
 ///This should be moved to utils and will most likely be deprecated.
 /// Deprication depends on android local storage.
-pub fn decrypt_key<N: Network>(
-    ciphertext: &Ciphertext<N>,
-    secret: &str,
-) -> AvailResult<PrivateKey<N>> {
+/// Keys encrypted before envelopes are snarkVM ciphertexts and still decrypt.
+pub fn decrypt_key<N: Network>(encrypted: &str, secret: &str) -> AvailResult<PrivateKey<N>> {
+    if is_envelope(encrypted) {
+        let private_key = open_with_password(secret, encrypted)?;
+        return Ok(PrivateKey::<N>::from_str(&private_key)?);
+    }
+
+    let ciphertext = Ciphertext::<N>::from_str(encrypted)?;
     let decrypted_private_key =
-        Encryptor::<N>::decrypt_private_key_with_secret(ciphertext, secret)?;
+        Encryptor::<N>::decrypt_private_key_with_secret(&ciphertext, secret)?;
 
     Ok(decrypted_private_key)
 }
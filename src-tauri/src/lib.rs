@@ -4,14 +4,22 @@ pub mod models;
 pub mod services;
 
 use crate::services::record_handling::utils::get_all_nft_data;
+use services::account::derivation::{derive_account, get_accounts, rename_account, switch_account};
 use services::account::generation::create_seed_phrase_wallet;
 use services::account::generation::import_wallet;
+use services::account::passphrase::unlock_passphrase;
+use services::account::password::change_password;
 use services::account::phrase_recovery::recover_wallet_from_seed_phrase;
+use services::account::shard_recovery::{
+    create_seed_backup, inspect_backup_share, recover_wallet_from_shares,
+};
+use services::account::signer::{get_remote_signer, set_remote_signer};
 use services::account::utils::{network_status_check, open_url, os_type};
 use services::authentication::session::get_session;
 use services::local_storage::persistent_storage::{
-    get_address_string, get_auth_type, get_backup_flag, get_language, get_last_sync, get_network,
-    get_username, update_language,
+    get_address_string, get_auth_type, get_backup_flag, get_language, get_last_sync,
+    get_mnemonic_language, get_network, get_supported_mnemonic_languages, get_username,
+    update_language,
 };
 
 use api::quests::{
@@ -22,8 +30,27 @@ use api::{
     aleo_client::switch_to_obscura,
     user::{update_backup_flag, update_username},
 };
+use services::payment_request::{create_payment_request, parse_payment_request, route_deep_link};
+use services::rpc_server::{
+    get_rpc_server_status, resolve_rpc_approval, start_rpc_server, start_saved_rpc_server,
+    stop_rpc_server,
+};
 use services::local_storage::{
+    dapp_sessions::{
+        extend_dapp_session, get_dapp_sessions, grant_dapp_session, revoke_dapp_session,
+    },
     encrypted_data::get_and_store_all_data,
+    profiles::{create_profile, delete_profile, list_profiles, rename_profile, switch_profile},
+    reputation::{
+        add_reputation_entry, check_reputation, get_reputation_entries, import_reputation_list,
+        remove_reputation_entry, set_reputation_publisher,
+    },
+    rpc_clients::{get_rpc_clients, revoke_rpc_client},
+    spending_policy::{
+        check_batch_spending_policy, check_spending_policy, get_spending_policies,
+        remove_spending_policy, set_spending_policy,
+    },
+    token_metadata::{get_token_metadata_list, refresh_token_metadata, set_token_list_source},
     tokens::get_stored_tokens,
     utils::{
         delete_local_for_recovery, delete_util, get_private_key_tauri, get_seed_phrase,
@@ -34,23 +61,25 @@ use services::local_storage::{
 // record handliong services
 // use crate::services::record_handling::utils::get_all_nft_data;
 use services::record_handling::{
+    deployment::{deploy_program_directory, estimate_deployment},
+    parameters::{check_parameters, download_parameters, import_parameter_bundle},
+    program_abi::{get_program_abi, validate_program_inputs},
+    prover::{get_delegated_prover, set_delegated_prover},
+    staking::{bond_public, bond_validator, claim_unbond_public, get_staking_info, unbond_public},
     sync::{blocks_sync, sync_backup, txs_sync},
     transfer::{pre_install_inclusion_prover, transfer},
+    tx_decoder::explain_create_event,
 };
-use tauri::Emitter;
 use tauri::Listener;
 use tauri::Manager;
 use tauri_plugin_deep_link::DeepLinkExt;
 // wallet connect services
 use crate::services::wallet_connect_api::{
     decrypt_records, get_avail_event, get_avail_events, get_balance, get_event, get_events,
-    get_records, get_succinct_avail_event, get_succinct_avail_events, request_create_event, sign,
-    verify,
+    get_mapping_keys, get_mapping_value, get_records, get_succinct_avail_event,
+    get_succinct_avail_events, request_create_event, request_create_events_batch, sign,
+    sign_typed_data, verify, verify_typed_data,
 };
-#[derive(Clone, serde::Deserialize, serde::Serialize)]
-struct DeepLinkPayload {
-    uri: String,
-}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -65,6 +94,7 @@ pub fn run() {
             app.listen("deep-link://new-url", move |event| {
                 deep_link_print(event, handle.clone())
             });
+            start_saved_rpc_server(app.handle().clone());
             // Remove the on_scheme method call
             println!("Deep link: {:?}", app.deep_link().get_current());
             // NOTE: Updater is only supported on desktop platforms
@@ -75,8 +105,22 @@ pub fn run() {
             /* Account Management */
             create_seed_phrase_wallet,
             recover_wallet_from_seed_phrase,
+            create_seed_backup,
+            inspect_backup_share,
+            recover_wallet_from_shares,
+            unlock_passphrase,
+            change_password,
             update_username,
             import_wallet,
+            derive_account,
+            get_accounts,
+            switch_account,
+            rename_account,
+            create_profile,
+            list_profiles,
+            switch_profile,
+            rename_profile,
+            delete_profile,
             get_username,
             delete_util,
             delete_local_for_recovery,
@@ -91,10 +135,19 @@ pub fn run() {
             get_network,
             get_language,
             update_language,
+            get_mnemonic_language,
+            get_supported_mnemonic_languages,
             get_stored_tokens,
+            get_token_metadata_list,
+            refresh_token_metadata,
+            set_token_list_source,
             open_url,
             os_type,
             network_status_check,
+            set_remote_signer,
+            get_remote_signer,
+            set_delegated_prover,
+            get_delegated_prover,
             /* Authentication */
             get_session,
             get_auth_type,
@@ -107,6 +160,15 @@ pub fn run() {
             get_avail_events,
             get_all_nft_data,
             transfer,
+            create_payment_request,
+            parse_payment_request,
+            bond_public,
+            bond_validator,
+            unbond_public,
+            claim_unbond_public,
+            get_staking_info,
+            estimate_deployment,
+            deploy_program_directory,
             get_campaigns,
             get_quests_for_campaign,
             get_points,
@@ -120,22 +182,53 @@ pub fn run() {
             get_events,
             get_records,
             request_create_event,
+            request_create_events_batch,
             sign,
+            sign_typed_data,
             decrypt_records,
             get_balance,
+            get_mapping_value,
+            get_mapping_keys,
             get_succinct_avail_event,
             get_succinct_avail_events,
             verify,
+            verify_typed_data,
+            get_program_abi,
+            validate_program_inputs,
+            explain_create_event,
+            grant_dapp_session,
+            extend_dapp_session,
+            get_dapp_sessions,
+            revoke_dapp_session,
+            check_spending_policy,
+            check_batch_spending_policy,
+            set_spending_policy,
+            get_spending_policies,
+            remove_spending_policy,
+            check_reputation,
+            get_reputation_entries,
+            add_reputation_entry,
+            remove_reputation_entry,
+            set_reputation_publisher,
+            import_reputation_list,
+            start_rpc_server,
+            stop_rpc_server,
+            get_rpc_server_status,
+            resolve_rpc_approval,
+            get_rpc_clients,
+            revoke_rpc_client,
             /* Aleo Helpers */
             pre_install_inclusion_prover,
+            check_parameters,
+            download_parameters,
+            import_parameter_bundle,
             switch_to_obscura
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 fn deep_link_print(event: tauri::Event, handle: tauri::AppHandle) {
-    let uri = event.payload().to_string();
-    handle
-        .emit("deep-link-wc", DeepLinkPayload { uri })
-        .unwrap();
+    if let Err(e) = route_deep_link(event.payload(), &handle) {
+        println!("Error routing deep link: {:?}", e.internal_msg);
+    }
 }
@@ -2,8 +2,12 @@ pub mod account;
 pub mod auth;
 pub mod event;
 pub mod event_payloads;
+pub mod payment_request;
 pub mod pointers;
+pub mod backup_share;
+pub mod reputation;
 pub mod storage;
+pub mod token;
 pub mod transfer;
 pub mod wallet;
 pub mod wallet_connect;
@@ -22,3 +22,12 @@ impl Serialize for AvailKeys<TestnetV0> {
 pub struct AddressRequest {
     pub username: String,
 }
+
+/// An account derived from the wallet's seed phrase, index 0 is the account of the seed phrase itself
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAccount {
+    pub index: u32,
+    pub name: String,
+    pub address: String,
+    pub active: bool,
+}
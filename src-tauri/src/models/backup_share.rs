@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Crockford base32, it leaves out letters that are easily confused with digits
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const PREFIX: &str = "AVL";
+const GROUP_SIZE: usize = 4;
+const VERSION: u8 = 1;
+const CHECKSUM_SIZE: usize = 4;
+
+/// One share of a seed phrase backup split with Shamir's secret sharing scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupShare {
+    /// Random id shared by every share of one backup, shares of different backups can't be combined
+    pub set_id: [u8; 4],
+    pub threshold: u8,
+    pub share_count: u8,
+    /// Position of the share in its backup, starting at 1
+    pub index: u8,
+    /// Short code of the seed phrase language
+    pub language: String,
+    pub data: Vec<u8>,
+}
+
+/// What the wallet can tell about a share before the backup is combined
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BackupShareInfo {
+    pub set_id: String,
+    pub threshold: u8,
+    pub share_count: u8,
+    pub index: u8,
+}
+
+fn invalid_share(internal: String, external: &str) -> AvailError {
+    AvailError::new(AvailErrorType::Validation, internal, external.to_string())
+}
+
+fn checksum(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes)[..CHECKSUM_SIZE].to_vec()
+}
+
+fn to_base32(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+
+    encoded
+}
+
+fn from_base32(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for c in encoded.chars() {
+        // transcription mistakes Crockford base32 forgives
+        let c = match c {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = ALPHABET.iter().position(|a| *a as char == c)? as u16;
+
+        buffer = (buffer << 5) | value;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(bytes)
+}
+
+impl BackupShare {
+    pub fn info(&self) -> BackupShareInfo {
+        BackupShareInfo {
+            set_id: hex::encode(self.set_id),
+            threshold: self.threshold,
+            share_count: self.share_count,
+            index: self.index,
+        }
+    }
+
+    /// Encodes the share as groups of base32 characters ending in a checksum, e.g. `AVL-04TG-...`
+    pub fn encode(&self) -> String {
+        let mut payload = vec![VERSION];
+        payload.extend_from_slice(&self.set_id);
+        payload.extend_from_slice(&[self.threshold, self.share_count, self.index]);
+        payload.push(self.language.len() as u8);
+        payload.extend_from_slice(self.language.as_bytes());
+        payload.extend_from_slice(&self.data);
+        payload.extend(checksum(&payload));
+
+        let encoded = to_base32(&payload);
+        let groups = encoded
+            .as_bytes()
+            .chunks(GROUP_SIZE)
+            .map(|group| String::from_utf8_lossy(group).to_string())
+            .collect::<Vec<String>>();
+
+        format!("{}-{}", PREFIX, groups.join("-"))
+    }
+
+    /// Decodes a transcribed share, spacing, dashes and case don't matter
+    pub fn decode(share: &str) -> AvailResult<Self> {
+        let normalized = share
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect::<String>()
+            .to_uppercase();
+
+        let encoded = match normalized.strip_prefix(PREFIX) {
+            Some(encoded) => encoded,
+            None => {
+                return Err(invalid_share(
+                    format!("Share does not start with {}", PREFIX),
+                    "This is not an Avail backup share",
+                ))
+            }
+        };
+
+        let payload = match from_base32(encoded) {
+            Some(payload) if payload.len() > 9 + CHECKSUM_SIZE => payload,
+            _ => {
+                return Err(invalid_share(
+                    "Share is not valid base32 or too short".to_string(),
+                    "The share contains invalid characters or is incomplete",
+                ))
+            }
+        };
+
+        let (payload, check) = payload.split_at(payload.len() - CHECKSUM_SIZE);
+        if checksum(payload) != check {
+            return Err(invalid_share(
+                "Share checksum does not match".to_string(),
+                "The share was not transcribed correctly, please check it for typos",
+            ));
+        }
+
+        if payload[0] != VERSION {
+            return Err(invalid_share(
+                format!("Unsupported share version {}", payload[0]),
+                "This share was made by a newer version of the wallet",
+            ));
+        }
+
+        let language_len = payload[8] as usize;
+        let language = payload
+            .get(9..9 + language_len)
+            .and_then(|language| String::from_utf8(language.to_vec()).ok());
+        let data = payload.get(9 + language_len..).unwrap_or(&[]);
+
+        match language {
+            Some(language) if !data.is_empty() => Ok(BackupShare {
+                set_id: [payload[1], payload[2], payload[3], payload[4]],
+                threshold: payload[5],
+                share_count: payload[6],
+                index: payload[7],
+                language,
+                data: data.to_vec(),
+            }),
+            _ => Err(invalid_share(
+                "Share payload is malformed".to_string(),
+                "The share is incomplete",
+            )),
+        }
+    }
+}
+
+/// Checks that shares belong to the same backup and are enough to combine it
+pub fn validate_share_set(shares: &[BackupShare]) -> AvailResult<()> {
+    let first = match shares.first() {
+        Some(first) => first,
+        None => {
+            return Err(invalid_share(
+                "No shares were given".to_string(),
+                "Enter the shares of your backup",
+            ))
+        }
+    };
+
+    for share in shares {
+        if share.set_id != first.set_id
+            || share.threshold != first.threshold
+            || share.share_count != first.share_count
+        {
+            return Err(invalid_share(
+                format!(
+                    "Share {} belongs to backup {}, share {} to backup {}",
+                    share.index,
+                    hex::encode(share.set_id),
+                    first.index,
+                    hex::encode(first.set_id)
+                ),
+                "The shares belong to different backups",
+            ));
+        }
+    }
+
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(invalid_share(
+                format!("Share {} was given twice", share.index),
+                &format!("Share {} was entered twice", share.index),
+            ));
+        }
+    }
+
+    if shares.len() < first.threshold as usize {
+        return Err(invalid_share(
+            format!(
+                "{} of {} required shares were given",
+                shares.len(),
+                first.threshold
+            ),
+            &format!(
+                "{} shares are needed to recover this backup, {} more to go",
+                first.threshold,
+                first.threshold as usize - shares.len()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod backup_share_tests {
+    use super::*;
+
+    fn share(index: u8) -> BackupShare {
+        BackupShare {
+            set_id: [1, 2, 3, 4],
+            threshold: 2,
+            share_count: 3,
+            index,
+            language: "en".to_string(),
+            data: format!("{}-7f3a09c4e1", index).into_bytes(),
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let encoded = share(1).encode();
+        assert!(encoded.starts_with("AVL-"));
+
+        assert_eq!(BackupShare::decode(&encoded).unwrap(), share(1));
+
+        // transcriptions are forgiving about case, spacing and look-alike characters
+        let transcribed = encoded.to_lowercase().replace('-', " ").replace('0', "o");
+        assert_eq!(BackupShare::decode(&transcribed).unwrap(), share(1));
+
+        let mut typo = encoded.into_bytes();
+        typo[5] = if typo[5] == b'A' { b'B' } else { b'A' };
+        assert!(BackupShare::decode(&String::from_utf8(typo).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_validate_share_set() {
+        assert!(validate_share_set(&[share(1), share(3)]).is_ok());
+        assert!(validate_share_set(&[share(1)]).is_err());
+        assert!(validate_share_set(&[share(2), share(2)]).is_err());
+
+        let other_backup = BackupShare {
+            set_id: [9, 9, 9, 9],
+            ..share(2)
+        };
+        assert!(validate_share_set(&[share(1), other_backup]).is_err());
+    }
+}
@@ -110,6 +110,10 @@ impl Event {
     pub fn get_created(&self) -> DateTime<Local> {
         self.created
     }
+
+    pub fn program_id(&self) -> &Option<String> {
+        &self.program_id
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -171,6 +175,8 @@ pub struct SuccinctAvailEvent {
     program_id: Option<String>,
     #[serde(rename = "functionId")]
     function_id: Option<String>,
+    #[serde(default)]
+    staking: Option<StakingEventType>,
 }
 
 impl SuccinctAvailEvent {
@@ -199,9 +205,15 @@ impl SuccinctAvailEvent {
             created,
             program_id,
             function_id,
+            staking: None,
         }
     }
 
+    pub fn with_staking(mut self, staking: Option<StakingEventType>) -> Self {
+        self.staking = staking;
+        self
+    }
+
     pub fn get_created(&self) -> DateTime<Local> {
         self.created
     }
@@ -239,6 +251,8 @@ pub struct AvailEvent {
     to: Option<String>,
     from: Option<String>,
     amount: Option<f64>,
+    #[serde(default)]
+    staking: Option<StakingEventType>,
 }
 
 impl AvailEvent {
@@ -293,14 +307,43 @@ impl AvailEvent {
             to,
             from,
             amount,
+            staking: None,
         }
     }
 
+    pub fn with_staking(mut self, staking: Option<StakingEventType>) -> Self {
+        self.staking = staking;
+        self
+    }
+
     pub fn get_created(&self) -> DateTime<Local> {
         self.created
     }
 }
 
+/// Staking action of a credits.aleo transaction. The common event types have no staking variant,
+/// so staking events are stored as executions and carry this next to their type.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingEventType {
+    Bond,
+    BondValidator,
+    Unbond,
+    ClaimUnbond,
+}
+
+impl StakingEventType {
+    pub fn from_function(function_id: &str) -> Option<Self> {
+        match function_id {
+            "bond_public" => Some(StakingEventType::Bond),
+            "bond_validator" => Some(StakingEventType::BondValidator),
+            "unbond_public" => Some(StakingEventType::Unbond),
+            "claim_unbond_public" => Some(StakingEventType::ClaimUnbond),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum Network {
     AleoTestnet,
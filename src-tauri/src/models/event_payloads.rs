@@ -1,10 +1,32 @@
 use serde::Serialize;
 
+use super::payment_request::PaymentRequest;
+use super::transfer::TransferRequest;
+
+use avail_common::models::encrypted_data::TransactionState;
+
 #[derive(Serialize, Clone)]
 pub struct ScanProgressPayload {
     pub progress: f32,
 }
 
-// TODO : Transaction execution event
-// TODO : Transaction confirmed event
-// TODO : Transaction failed event
+#[derive(Serialize, Clone)]
+pub struct PaymentRequestPayload {
+    pub uri: String,
+    pub request: PaymentRequest,
+    pub transfer: TransferRequest,
+}
+
+/// A transaction moving from one state to another, published through the transaction event bus
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TxStateChangePayload {
+    pub event_id: String,
+    /// Unknown for the first change of an event
+    pub old_state: Option<TransactionState>,
+    pub new_state: TransactionState,
+    pub transaction_id: Option<String>,
+    pub height: Option<u32>,
+    pub error: Option<String>,
+    /// Origin of the dapp that requested the event
+    pub origin: Option<String>,
+}
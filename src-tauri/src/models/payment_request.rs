@@ -0,0 +1,108 @@
+use avail_common::aleo_tools::program_manager::TransferType;
+use serde::{Deserialize, Serialize};
+
+use super::transfer::TransferRequest;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentVisibility {
+    #[default]
+    Private,
+    Public,
+}
+
+impl PaymentVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentVisibility::Private => "private",
+            PaymentVisibility::Public => "public",
+        }
+    }
+}
+
+/// A request for payment, shared as an `aleo:` uri or an `avail://pay` deep link
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    recipient: String,
+    /// Amount in the asset's smallest unit
+    amount: Option<u64>,
+    asset_id: String,
+    visibility: PaymentVisibility,
+    memo: Option<String>,
+    /// Unix timestamp after which the request should not be paid
+    expires_at: Option<i64>,
+    callback: Option<String>,
+}
+
+impl PaymentRequest {
+    pub fn new(
+        recipient: String,
+        amount: Option<u64>,
+        asset_id: String,
+        visibility: PaymentVisibility,
+        memo: Option<String>,
+        expires_at: Option<i64>,
+        callback: Option<String>,
+    ) -> Self {
+        Self {
+            recipient,
+            amount,
+            asset_id,
+            visibility,
+            memo,
+            expires_at,
+            callback,
+        }
+    }
+
+    pub fn recipient(&self) -> &String {
+        &self.recipient
+    }
+
+    pub fn amount(&self) -> Option<u64> {
+        self.amount
+    }
+
+    pub fn asset_id(&self) -> &String {
+        &self.asset_id
+    }
+
+    pub fn visibility(&self) -> PaymentVisibility {
+        self.visibility
+    }
+
+    pub fn memo(&self) -> &Option<String> {
+        &self.memo
+    }
+
+    pub fn expires_at(&self) -> Option<i64> {
+        self.expires_at
+    }
+
+    pub fn callback(&self) -> &Option<String> {
+        &self.callback
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+
+    /// Transfer pre-filled from the request, fees and password are left to the user
+    pub fn to_transfer_request(&self) -> TransferRequest {
+        let transfer_type = match self.visibility {
+            PaymentVisibility::Private => TransferType::Private,
+            PaymentVisibility::Public => TransferType::Public,
+        };
+
+        TransferRequest::new(
+            self.recipient.clone(),
+            self.amount.unwrap_or(0),
+            self.memo.clone(),
+            None,
+            transfer_type,
+            false,
+            0,
+            self.asset_id.clone(),
+        )
+    }
+}
@@ -8,7 +8,8 @@ use uuid::Uuid;
 
 use crate::api::aleo_client::setup_local_client;
 use crate::models::event::{
-    AvailEvent, Event, EventTransition, Network as EventNetwork, SuccinctAvailEvent, Visibility,
+    AvailEvent, Event, EventTransition, Network as EventNetwork, StakingEventType,
+    SuccinctAvailEvent, Visibility,
 };
 use crate::{
     api::aleo_client::setup_client,
@@ -50,6 +51,8 @@ pub struct TransactionPointer<N: Network> {
     amount: Option<f64>,
     fee: Option<f64>,
     error: Option<String>,
+    #[serde(default)]
+    staking: Option<StakingEventType>,
 }
 
 fn decrypt<N: Network>(encrypted_struct: EncryptedStruct<N>) -> AvailResult<TransactionPointer<N>> {
@@ -94,9 +97,16 @@ impl<N: Network> TransactionPointer<N> {
             amount,
             fee,
             error,
+            staking: None,
         }
     }
 
+    /// Marks a staking transaction, its event type stays `Execute`
+    pub fn with_staking(mut self, staking: StakingEventType) -> Self {
+        self.staking = Some(staking);
+        self
+    }
+
     #[allow(dead_code)]
     pub fn to(&self) -> Option<String> {
         self.to.clone()
@@ -152,6 +162,10 @@ impl<N: Network> TransactionPointer<N> {
         self.event_type.clone()
     }
 
+    pub fn staking(&self) -> Option<StakingEventType> {
+        self.staking
+    }
+
     pub fn amount(&self) -> Option<f64> {
         self.amount
     }
@@ -593,7 +607,8 @@ impl<N: Network> TransactionPointer<N> {
             self.to.clone(),
             None,
             self.amount.clone(),
-        );
+        )
+        .with_staking(self.staking);
 
         Ok(event)
     }
@@ -611,7 +626,8 @@ impl<N: Network> TransactionPointer<N> {
             self.created,
             self.executed_program_id.clone(),
             self.executed_function_id.clone(),
-        );
+        )
+        .with_staking(self.staking);
 
         Ok(event)
     }
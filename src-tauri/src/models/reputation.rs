@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use snarkvm::prelude::{Address, Network, ProgramID};
+use std::str::FromStr;
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/* Reputation Interfaces */
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationStatus {
+    /// Known to be malicious, requests involving it are refused
+    Blocked,
+    /// Known to be legitimate, shown as verified on approval screens
+    Verified,
+}
+
+impl ReputationStatus {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ReputationStatus::Blocked => "blocked",
+            ReputationStatus::Verified => "verified",
+        }
+    }
+}
+
+impl FromStr for ReputationStatus {
+    type Err = AvailError;
+
+    fn from_str(status: &str) -> AvailResult<Self> {
+        match status {
+            "blocked" => Ok(ReputationStatus::Blocked),
+            "verified" => Ok(ReputationStatus::Verified),
+            _ => Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                format!("Unknown reputation status {}", status),
+                "Unknown reputation status".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationSource {
+    /// Imported from the signed list of the configured publisher
+    List,
+    /// Added by the user, takes precedence over the list
+    User,
+}
+
+impl ReputationSource {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ReputationSource::List => "list",
+            ReputationSource::User => "user",
+        }
+    }
+}
+
+impl FromStr for ReputationSource {
+    type Err = AvailError;
+
+    fn from_str(source: &str) -> AvailResult<Self> {
+        match source {
+            "list" => Ok(ReputationSource::List),
+            "user" => Ok(ReputationSource::User),
+            _ => Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                format!("Unknown reputation source {}", source),
+                "Unknown reputation source".to_string(),
+            )),
+        }
+    }
+}
+
+/// Reputation of a program id or an address
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReputationEntry {
+    pub target: String,
+    pub status: ReputationStatus,
+    pub reason: Option<String>,
+    #[serde(default = "default_source")]
+    pub source: ReputationSource,
+}
+
+fn default_source() -> ReputationSource {
+    ReputationSource::List
+}
+
+impl ReputationEntry {
+    pub fn is_blocked(&self) -> bool {
+        self.status == ReputationStatus::Blocked
+    }
+
+    /// Message shown to the user when a request involving the target is refused
+    pub fn blocked_message(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("{} is blocked: {}", self.target, reason),
+            None => format!("{} is blocked as a known phishing target", self.target),
+        }
+    }
+}
+
+/// Checks that a target is a program id or an address and returns it in its canonical form
+pub fn parse_target<N: Network>(target: &str) -> AvailResult<String> {
+    let target = target.trim();
+
+    if let Ok(address) = Address::<N>::from_str(target) {
+        return Ok(address.to_string());
+    }
+
+    match ProgramID::<N>::from_str(target) {
+        Ok(program_id) => Ok(program_id.to_string()),
+        Err(_) => Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("{} is neither a program id nor an address", target),
+            format!("{} is neither a program id nor an address", target),
+        )),
+    }
+}
+
+/// Entries published for one network, a list replaces every entry of an older version
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReputationList {
+    pub version: u64,
+    pub network: String,
+    pub entries: Vec<ReputationEntry>,
+}
+
+/// Reputation list file, `list` is the json of a [`ReputationList`] signed as a message by `publisher`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedReputationList {
+    pub list: String,
+    pub publisher: String,
+    pub signature: String,
+}
+
+impl SignedReputationList {
+    /// Parses the signed list and puts its targets in canonical form, the signature is checked by the caller
+    pub fn parse<N: Network>(&self, network: &str) -> AvailResult<ReputationList> {
+        let list = serde_json::from_str::<ReputationList>(&self.list).map_err(|e| {
+            AvailError::new(
+                AvailErrorType::InvalidData,
+                e.to_string(),
+                "Invalid reputation list".to_string(),
+            )
+        })?;
+
+        if list.network != network {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!(
+                    "Reputation list is for {}, the wallet is on {}",
+                    list.network, network
+                ),
+                "The reputation list is for another network".to_string(),
+            ));
+        }
+
+        let entries = list
+            .entries
+            .into_iter()
+            .map(|entry| {
+                Ok(ReputationEntry {
+                    target: parse_target::<N>(&entry.target)?,
+                    source: ReputationSource::List,
+                    ..entry
+                })
+            })
+            .collect::<AvailResult<Vec<ReputationEntry>>>()?;
+
+        Ok(ReputationList { entries, ..list })
+    }
+}
+
+#[cfg(test)]
+mod reputation_tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestnetV0};
+
+    #[test]
+    fn test_parse_target() {
+        let rng = &mut rand::thread_rng();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let address = Address::<TestnetV0>::try_from(&private_key).unwrap();
+
+        assert_eq!(
+            parse_target::<TestnetV0>(&format!(" {} ", address)).unwrap(),
+            address.to_string()
+        );
+        assert_eq!(
+            parse_target::<TestnetV0>("drainer.aleo").unwrap(),
+            "drainer.aleo"
+        );
+        assert!(parse_target::<TestnetV0>("drainer").is_err());
+        assert!(parse_target::<TestnetV0>("aleo1notanaddress").is_err());
+    }
+
+    #[test]
+    fn test_parse_signed_list() {
+        let list = serde_json::json!({
+            "version": 3,
+            "network": "testnet",
+            "entries": [
+                {"target": "drainer.aleo", "status": "blocked", "reason": "Drains approvals"},
+                {"target": "credits.aleo", "status": "verified", "reason": null, "source": "user"}
+            ]
+        });
+        let signed = SignedReputationList {
+            list: list.to_string(),
+            publisher: String::new(),
+            signature: String::new(),
+        };
+
+        let parsed = signed.parse::<TestnetV0>("testnet").unwrap();
+        assert_eq!(parsed.version, 3);
+        assert!(parsed.entries[0].is_blocked());
+        assert_eq!(
+            parsed.entries[0].blocked_message(),
+            "drainer.aleo is blocked: Drains approvals"
+        );
+        // a list can't pass its entries off as the user's own
+        assert_eq!(parsed.entries[1].source, ReputationSource::List);
+
+        assert!(signed.parse::<TestnetV0>("mainnet").is_err());
+
+        let invalid = SignedReputationList {
+            list: list.to_string().replace("drainer.aleo", "not a target"),
+            ..signed
+        };
+        assert!(invalid.parse::<TestnetV0>("testnet").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use openssl::{
+    pkcs5::scrypt,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Every envelope starts with the prefix and the format version, older ciphertexts are plain snarkVM ciphertexts
+const PREFIX: &str = "avl";
+const VERSION: u8 = 1;
+const KDF: &str = "scrypt";
+
+/// scrypt cost of new envelopes, N = 2^15 with r = 8 takes 32 MiB of memory per derivation
+pub const LOG_N: u8 = 15;
+pub const R: u32 = 8;
+pub const P: u32 = 1;
+
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const KEY_SIZE: usize = 32;
+
+fn envelope_error(internal: String, external: &str) -> AvailError {
+    AvailError::new(AvailErrorType::Internal, internal, external.to_string())
+}
+
+fn invalid_envelope(internal: &str) -> AvailError {
+    AvailError::new(
+        AvailErrorType::InvalidData,
+        internal.to_string(),
+        "The stored key is corrupted".to_string(),
+    )
+}
+
+/// Key derivation parameters, shared by every envelope of a wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: [u8; SALT_SIZE],
+}
+
+impl KdfParams {
+    /// Parameters with a fresh random salt
+    pub fn new(log_n: u8, r: u32, p: u32) -> Self {
+        Self {
+            log_n,
+            r,
+            p,
+            salt: rand::random::<[u8; SALT_SIZE]>(),
+        }
+    }
+
+    /// Parameters of new wallets
+    pub fn generate() -> Self {
+        Self::new(LOG_N, R, P)
+    }
+
+    /// Envelopes sealed with a lower cost than the current one are sealed again on unlock
+    pub fn is_outdated(&self) -> bool {
+        self.log_n < LOG_N || self.r < R || self.p < P
+    }
+
+    /// Encodes the parameters as `scrypt$<log_n>$<r>$<p>$<salt>`
+    pub fn encode(&self) -> String {
+        format!(
+            "{}${}${}${}${}",
+            KDF,
+            self.log_n,
+            self.r,
+            self.p,
+            hex::encode(self.salt)
+        )
+    }
+
+    pub fn decode(encoded: &str) -> AvailResult<Self> {
+        let parts = encoded.split('$').collect::<Vec<&str>>();
+
+        let (log_n, r, p, salt) = match parts.as_slice() {
+            [KDF, log_n, r, p, salt] => (
+                log_n.parse::<u8>(),
+                r.parse::<u32>(),
+                p.parse::<u32>(),
+                hex::decode(salt),
+            ),
+            _ => return Err(invalid_envelope("Unknown key derivation function")),
+        };
+
+        match (log_n, r, p, salt) {
+            // bounds keep a corrupted entry from asking for unbounded memory
+            (Ok(log_n), Ok(r), Ok(p), Ok(salt))
+                if (10..=22).contains(&log_n)
+                    && (1..=32).contains(&r)
+                    && (1..=16).contains(&p)
+                    && salt.len() == SALT_SIZE =>
+            {
+                let mut salt_bytes = [0u8; SALT_SIZE];
+                salt_bytes.copy_from_slice(&salt);
+
+                Ok(Self {
+                    log_n,
+                    r,
+                    p,
+                    salt: salt_bytes,
+                })
+            }
+            _ => Err(invalid_envelope("Invalid key derivation parameters")),
+        }
+    }
+}
+
+/// Symmetric key derived from a password, it seals and opens envelopes of the same parameters
+pub struct EnvelopeKey {
+    key: [u8; KEY_SIZE],
+    params: KdfParams,
+}
+
+impl EnvelopeKey {
+    pub fn derive(password: &str, params: &KdfParams) -> AvailResult<Self> {
+        let n = 1u64 << params.log_n;
+        let (r, p) = (params.r as u64, params.p as u64);
+        let maxmem = 128 * r * (n + p + 2) + 1024 * 1024;
+
+        let mut key = [0u8; KEY_SIZE];
+        scrypt(password.as_bytes(), &params.salt, n, r, p, maxmem, &mut key)
+            .map_err(|e| envelope_error(format!("{:?}", e), "Error deriving the encryption key"))?;
+
+        Ok(Self {
+            key,
+            params: params.clone(),
+        })
+    }
+
+    pub fn params(&self) -> &KdfParams {
+        &self.params
+    }
+
+    fn header(params: &KdfParams) -> String {
+        format!("{}${}${}", PREFIX, VERSION, params.encode())
+    }
+
+    /// Encrypts with AES-256-GCM into `avl$1$scrypt$<log_n>$<r>$<p>$<salt>$<nonce>$<ciphertext>`.
+    /// The header is authenticated, so the parameters can't be swapped.
+    pub fn seal(&self, plaintext: &str) -> AvailResult<String> {
+        let header = Self::header(&self.params);
+        let nonce = rand::random::<[u8; NONCE_SIZE]>();
+        let mut tag = [0u8; TAG_SIZE];
+
+        let mut sealed = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            header.as_bytes(),
+            plaintext.as_bytes(),
+            &mut tag,
+        )
+        .map_err(|e| envelope_error(format!("{:?}", e), "Error encrypting the key"))?;
+        sealed.extend_from_slice(&tag);
+
+        Ok(format!(
+            "{}${}${}",
+            header,
+            hex::encode(nonce),
+            hex::encode(sealed)
+        ))
+    }
+
+    /// Decrypts an envelope sealed with the same parameters, a wrong password fails authentication
+    pub fn open(&self, envelope: &str) -> AvailResult<String> {
+        let (params, nonce, sealed) = parse(envelope)?;
+
+        if params != self.params {
+            return Err(envelope_error(
+                "Envelope was sealed with other key derivation parameters".to_string(),
+                "Error decrypting the key",
+            ));
+        }
+
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_SIZE);
+
+        let plaintext = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            Self::header(&params).as_bytes(),
+            ciphertext,
+            tag,
+        )
+        .map_err(|e| {
+            AvailError::new(
+                AvailErrorType::Validation,
+                format!("Envelope authentication failed {:?}", e),
+                "Incorrect password".to_string(),
+            )
+        })?;
+
+        String::from_utf8(plaintext).map_err(|_| invalid_envelope("Envelope is not valid utf-8"))
+    }
+}
+
+fn parse(envelope: &str) -> AvailResult<(KdfParams, Vec<u8>, Vec<u8>)> {
+    let parts = envelope.splitn(3, '$').collect::<Vec<&str>>();
+
+    let rest = match parts.as_slice() {
+        [PREFIX, version, rest] if *version == VERSION.to_string() => *rest,
+        [PREFIX, _, _] => {
+            return Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                "Unsupported envelope version".to_string(),
+                "This key was stored by a newer version of the wallet".to_string(),
+            ))
+        }
+        _ => return Err(invalid_envelope("Not an envelope")),
+    };
+
+    let (params, sealed) = match rest.rsplitn(3, '$').collect::<Vec<&str>>().as_slice() {
+        [sealed, nonce, params] => (
+            KdfParams::decode(params)?,
+            (hex::decode(nonce), hex::decode(sealed)),
+        ),
+        _ => return Err(invalid_envelope("Envelope is incomplete")),
+    };
+
+    match sealed {
+        (Ok(nonce), Ok(sealed)) if nonce.len() == NONCE_SIZE && sealed.len() >= TAG_SIZE => {
+            Ok((params, nonce, sealed))
+        }
+        _ => Err(invalid_envelope("Envelope is malformed")),
+    }
+}
+
+/// Tells envelopes apart from the snarkVM ciphertexts keys were stored as before
+pub fn is_envelope(value: &str) -> bool {
+    value.starts_with(&format!("{}$", PREFIX))
+}
+
+/// Parameters an envelope was sealed with
+pub fn envelope_params(envelope: &str) -> AvailResult<KdfParams> {
+    Ok(parse(envelope)?.0)
+}
+
+/// Opens an envelope with the parameters it carries
+pub fn open_with_password(password: &str, envelope: &str) -> AvailResult<String> {
+    let params = envelope_params(envelope)?;
+
+    EnvelopeKey::derive(password, &params)?.open(envelope)
+}
+
+#[cfg(test)]
+mod envelope_tests {
+    use super::*;
+
+    const SEED_PHRASE: &str =
+        "light soon prepare wire blade charge female stage ridge happy pony chief";
+
+    // cheap parameters so the tests stay fast
+    fn params() -> KdfParams {
+        KdfParams::new(10, 8, 1)
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = EnvelopeKey::derive("password", &params()).unwrap();
+        let envelope = key.seal(SEED_PHRASE).unwrap();
+
+        assert!(is_envelope(&envelope));
+        assert!(!is_envelope("ciphertext1qyqd0j"));
+        assert_eq!(key.open(&envelope).unwrap(), SEED_PHRASE);
+        assert_eq!(
+            open_with_password("password", &envelope).unwrap(),
+            SEED_PHRASE
+        );
+
+        // the same key seals to a different envelope every time
+        assert_ne!(key.seal(SEED_PHRASE).unwrap(), envelope);
+
+        assert!(open_with_password("passw0rd", &envelope).is_err());
+
+        let tampered = envelope.replacen("$10$", "$11$", 1);
+        assert!(open_with_password("password", &tampered).is_err());
+    }
+
+    #[test]
+    fn test_kdf_params() {
+        let params = params();
+
+        assert_eq!(KdfParams::decode(&params.encode()).unwrap(), params);
+        assert!(params.is_outdated());
+        assert!(!KdfParams::generate().is_outdated());
+        assert_ne!(KdfParams::generate().salt, KdfParams::generate().salt);
+
+        assert!(KdfParams::decode("scrypt$40$8$1$00").is_err());
+        assert!(KdfParams::decode("pbkdf2$15$8$1$00").is_err());
+    }
+}
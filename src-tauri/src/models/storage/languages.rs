@@ -1,6 +1,8 @@
-use bip39::Language;
+use bip39::{Language, Mnemonic};
 use serde::{Deserialize, Serialize};
 
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Languages {
     English,
@@ -70,15 +72,184 @@ impl Languages {
         }
     }
 
+    /// Wordlist of seed phrases created before the seed phrase language was stored.
+    /// They were written in the interface language when it had a wordlist and in English otherwise.
+    pub fn legacy_mnemonic_language(&self) -> MnemonicLanguage {
+        match self {
+            Languages::ChineseSimplified => MnemonicLanguage::ChineseSimplified,
+            Languages::ChineseTraditional => MnemonicLanguage::ChineseTraditional,
+            Languages::Spanish => MnemonicLanguage::Spanish,
+            Languages::Italian => MnemonicLanguage::Italian,
+            Languages::Japanese => MnemonicLanguage::Japanese,
+            _ => MnemonicLanguage::English,
+        }
+    }
+}
+
+/// BIP-39 wordlist a seed phrase is written in, chosen independently of the interface language
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MnemonicLanguage {
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Portuguese,
+    Spanish,
+}
+
+impl MnemonicLanguage {
+    /// Every wordlist of the BIP-39 specification
+    pub const ALL: [MnemonicLanguage; 10] = [
+        MnemonicLanguage::English,
+        MnemonicLanguage::ChineseSimplified,
+        MnemonicLanguage::ChineseTraditional,
+        MnemonicLanguage::Czech,
+        MnemonicLanguage::French,
+        MnemonicLanguage::Italian,
+        MnemonicLanguage::Japanese,
+        MnemonicLanguage::Korean,
+        MnemonicLanguage::Portuguese,
+        MnemonicLanguage::Spanish,
+    ];
+
+    pub fn to_string(&self) -> String {
+        match self {
+            MnemonicLanguage::English => "English".to_string(),
+            MnemonicLanguage::ChineseSimplified => "Chinese Simplified".to_string(),
+            MnemonicLanguage::ChineseTraditional => "Chinese Traditional".to_string(),
+            MnemonicLanguage::Czech => "Czech".to_string(),
+            MnemonicLanguage::French => "French".to_string(),
+            MnemonicLanguage::Italian => "Italian".to_string(),
+            MnemonicLanguage::Japanese => "Japanese".to_string(),
+            MnemonicLanguage::Korean => "Korean".to_string(),
+            MnemonicLanguage::Portuguese => "Portuguese".to_string(),
+            MnemonicLanguage::Spanish => "Spanish".to_string(),
+        }
+    }
+
+    pub fn to_string_short(&self) -> String {
+        match self {
+            MnemonicLanguage::English => "en".to_string(),
+            MnemonicLanguage::ChineseSimplified => "zh-cn".to_string(),
+            MnemonicLanguage::ChineseTraditional => "zh-tw".to_string(),
+            MnemonicLanguage::Czech => "cs".to_string(),
+            MnemonicLanguage::French => "fr".to_string(),
+            MnemonicLanguage::Italian => "it".to_string(),
+            MnemonicLanguage::Japanese => "ja".to_string(),
+            MnemonicLanguage::Korean => "ko".to_string(),
+            MnemonicLanguage::Portuguese => "pt".to_string(),
+            MnemonicLanguage::Spanish => "es".to_string(),
+        }
+    }
+
+    pub fn from_string_short(s: &str) -> Option<MnemonicLanguage> {
+        MnemonicLanguage::ALL
+            .into_iter()
+            .find(|language| language.to_string_short() == s)
+    }
+
     pub fn to_bip39_language(&self) -> Language {
         match self {
-            Languages::English => Language::English,
-            Languages::ChineseSimplified => Language::ChineseSimplified,
-            Languages::ChineseTraditional => Language::ChineseTraditional,
-            Languages::Spanish => Language::Spanish,
-            Languages::Italian => Language::Italian,
-            Languages::Japanese => Language::Japanese,
-            _ => Language::English,
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::ChineseSimplified => Language::SimplifiedChinese,
+            MnemonicLanguage::ChineseTraditional => Language::TraditionalChinese,
+            MnemonicLanguage::Czech => Language::Czech,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Portuguese => Language::Portuguese,
+            MnemonicLanguage::Spanish => Language::Spanish,
+        }
+    }
+
+    /// Wordlists seed phrases can be created in and recovered from
+    pub fn supported() -> Vec<MnemonicLanguage> {
+        MnemonicLanguage::ALL.to_vec()
+    }
+
+    /// Finds the wordlist of a seed phrase from its words and checksum
+    pub fn detect(seed_phrase: &str) -> AvailResult<MnemonicLanguage> {
+        let matches = MnemonicLanguage::supported()
+            .into_iter()
+            .filter(|language| {
+                Mnemonic::parse_in(language.to_bip39_language(), seed_phrase).is_ok()
+            })
+            .collect::<Vec<MnemonicLanguage>>();
+
+        match matches.as_slice() {
+            [language] => Ok(*language),
+            [] => Err(AvailError::new(
+                AvailErrorType::Validation,
+                "Seed phrase does not match any supported wordlist".to_string(),
+                "The seed phrase is not valid, please check it for typos".to_string(),
+            )),
+            // some Chinese characters are in both the simplified and the traditional wordlist
+            _ => Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Seed phrase is valid in {:?}", matches),
+                "The seed phrase is valid in more than one language, please choose its language"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+/// Error of a seed phrase the bip39 crate can't read or create
+pub fn seed_phrase_error(e: bip39::Error) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Validation,
+        format!("Invalid seed phrase: {}", e),
+        "The seed phrase is not valid, please check it for typos".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod languages_tests {
+    use super::*;
+
+    const PHRASE: &str = "light soon prepare wire blade charge female stage ridge happy pony chief";
+
+    #[test]
+    fn test_mnemonic_language_codes() {
+        for language in MnemonicLanguage::ALL {
+            assert_eq!(
+                MnemonicLanguage::from_string_short(&language.to_string_short()),
+                Some(language)
+            );
+        }
+
+        assert!(MnemonicLanguage::supported().contains(&MnemonicLanguage::Czech));
+        assert!(MnemonicLanguage::supported().contains(&MnemonicLanguage::Portuguese));
+        assert_eq!(
+            Languages::Russian.legacy_mnemonic_language(),
+            MnemonicLanguage::English
+        );
+    }
+
+    #[test]
+    fn test_detect_mnemonic_language() {
+        assert_eq!(
+            MnemonicLanguage::detect(PHRASE).unwrap(),
+            MnemonicLanguage::English
+        );
+
+        for language in MnemonicLanguage::supported() {
+            let mnemonic = Mnemonic::generate_in(language.to_bip39_language(), 12).unwrap();
+            let detected = MnemonicLanguage::detect(&mnemonic.to_string());
+
+            match language {
+                MnemonicLanguage::ChineseSimplified | MnemonicLanguage::ChineseTraditional => {
+                    assert!(detected.is_err() || detected.unwrap() == language)
+                }
+                _ => assert_eq!(detected.unwrap(), language),
+            }
         }
+
+        assert!(MnemonicLanguage::detect("light soon prepare wire").is_err());
     }
 }
@@ -1,3 +1,5 @@
 pub mod encryption;
+pub mod envelope;
 pub mod languages;
 pub mod persistent;
+pub mod profile;
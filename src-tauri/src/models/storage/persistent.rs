@@ -1,10 +1,12 @@
-use app_dirs::*;
 use rusqlite::{
     params_from_iter,
     types::{FromSql, ToSql},
     Connection,
 };
 
+use crate::models::storage::profile::{app_data_dir, profile_dir};
+use crate::services::local_storage::profiles::get_active_profile;
+
 use avail_common::errors::AvailResult;
 
 pub struct PersistentStorage {
@@ -13,19 +15,20 @@ pub struct PersistentStorage {
 }
 
 impl PersistentStorage {
-    ///Creates a new instance of PersistentStorage
+    ///Creates a new instance of PersistentStorage on the database of the active profile
     pub fn new() -> AvailResult<Self> {
-        let path = app_root(
-            AppDataType::UserData,
-            &AppInfo {
-                name: "avail_wallet",
-                author: "Avail",
-            },
-        )?;
+        let db_path = profile_dir(&get_active_profile()?)?;
+
+        Self::open(db_path, "persistent.db")
+    }
 
-        let db_path = path.into_os_string().into_string().unwrap();
+    /// Opens the profile registry, which is shared by every profile
+    pub fn new_profiles() -> AvailResult<Self> {
+        Self::open(app_data_dir()?, "profiles.db")
+    }
 
-        let conn = Connection::open(format!("{}/persistent.db", db_path))?;
+    fn open(db_path: String, file: &str) -> AvailResult<Self> {
+        let conn = Connection::open(format!("{}/{}", db_path, file))?;
 
         Ok(PersistentStorage { conn, db_path })
     }
@@ -92,6 +95,41 @@ impl PersistentStorage {
 
         Ok(key_vec)
     }
+
+    /// Fetch rows with an SQL query whose parameters are bound, allowing for different types
+    pub fn get_all_mixed<T: FromSql>(
+        &self,
+        query: &str,
+        data: Vec<&dyn ToSql>,
+        item_count: usize,
+    ) -> AvailResult<Vec<Vec<T>>> {
+        let mut statement = self.conn.prepare(query)?;
+
+        let key_iter = statement.query_map(params_from_iter(data.into_iter()), |row| {
+            let data = (0..item_count).flat_map(|i| row.get(i)).collect();
+            Ok(data)
+        })?;
+
+        let key_vec = key_iter
+            .map(|key| match key {
+                Ok(key) => key,
+                Err(_) => Vec::new(),
+            })
+            .collect::<Vec<Vec<T>>>();
+
+        Ok(key_vec)
+    }
+
+    /// Checks whether a table has a column, columns added by later versions are missing on older installations
+    pub fn has_column(&self, table: &str, column: &str) -> AvailResult<bool> {
+        let res = self.get_all_mixed::<i64>(
+            "SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2",
+            vec![&table, &column],
+            1,
+        )?;
+
+        Ok(!res.is_empty())
+    }
 }
 #[test]
 fn test_save() {
@@ -130,3 +168,15 @@ fn test_get() {
     assert_eq!(res[0], "test".to_string());
     assert_eq!(res[1], "blue".to_string());
 }
+
+#[test]
+fn test_has_column() {
+    let storage = PersistentStorage::new().unwrap();
+
+    storage
+        .execute_query("CREATE TABLE IF NOT EXISTS test (name TEXT, color TEXT)")
+        .unwrap();
+
+    assert!(storage.has_column("test", "color").unwrap());
+    assert!(!storage.has_column("test", "size").unwrap());
+}
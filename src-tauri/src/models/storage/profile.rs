@@ -0,0 +1,105 @@
+use app_dirs::*;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Profile of installations from before profiles existed, it keeps the original database and keyring entries
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A wallet installation of its own, with its own database files and keyring entries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+    pub active: bool,
+}
+
+/// Profile every database and keyring access goes to, loaded from the profile registry on first use
+pub static ACTIVE_PROFILE: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// Root of the application data, shared by every profile
+pub fn app_data_dir() -> AvailResult<String> {
+    let path = app_root(
+        AppDataType::UserData,
+        &AppInfo {
+            name: "avail_wallet",
+            author: "Avail",
+        },
+    )?;
+
+    path.into_os_string().into_string().map_err(|_| {
+        AvailError::new(
+            AvailErrorType::LocalStorage,
+            "Application data path is not valid unicode".to_string(),
+            "Could not open the wallet storage".to_string(),
+        )
+    })
+}
+
+/// Profile ids are generated by the wallet, anything else can't name a directory or a keyring entry
+pub fn validate_profile_id(id: &str) -> AvailResult<()> {
+    match !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        true => Ok(()),
+        false => Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Invalid profile id {}", id),
+            "Invalid profile".to_string(),
+        )),
+    }
+}
+
+/// Directory holding the database files of a profile
+pub fn profile_dir(id: &str) -> AvailResult<String> {
+    validate_profile_id(id)?;
+
+    let root = app_data_dir()?;
+    if id == DEFAULT_PROFILE {
+        return Ok(root);
+    }
+
+    let dir = format!("{}/profiles/{}", root, id);
+    std::fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// Keyring service of a profile, the default profile keeps the service names of older installations
+pub fn keyring_service_for(id: &str, service: &str) -> String {
+    match id {
+        DEFAULT_PROFILE => service.to_string(),
+        _ => format!("{}.{}", service, id),
+    }
+}
+
+pub fn cached_active_profile() -> Option<String> {
+    ACTIVE_PROFILE.read().unwrap().clone()
+}
+
+pub fn cache_active_profile(id: &str) {
+    let mut profile_lock = ACTIVE_PROFILE.write().unwrap();
+    *profile_lock = Some(id.to_string());
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_namespaces() {
+        assert_eq!(
+            keyring_service_for(DEFAULT_PROFILE, "com.avail.wallet.p"),
+            "com.avail.wallet.p"
+        );
+        assert_eq!(
+            keyring_service_for("qa_1", "com.avail.wallet.p"),
+            "com.avail.wallet.p.qa_1"
+        );
+
+        assert!(validate_profile_id("3f9a0c1d").is_ok());
+        assert!(validate_profile_id("../default").is_err());
+        assert!(validate_profile_id("").is_err());
+    }
+}
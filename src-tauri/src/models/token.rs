@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/* Token Metadata Interfaces */
+
+/// Display information of a token, amounts are stored on chain as integers with `decimals` places.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub asset_id: String,
+    pub program_id: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+impl TokenMetadata {
+    pub fn new(
+        asset_id: String,
+        program_id: String,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        icon: Option<String>,
+        verified: bool,
+    ) -> Self {
+        Self {
+            asset_id,
+            program_id,
+            name,
+            symbol,
+            decimals,
+            icon,
+            verified,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use avail_common::aleo_tools::program_manager::TransferType;
 use serde::{Deserialize, Serialize};
 
+use crate::models::reputation::ReputationEntry;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransferRequest {
     recipient: String,
@@ -81,3 +83,42 @@ impl TransferRequest {
         &self.asset_id
     }
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransferResponse {
+    message: Option<String>,
+    error: Option<String>,
+    /// Set when the transfer was refused because the recipient or the token is blocked
+    #[serde(default)]
+    blocked: Option<ReputationEntry>,
+}
+
+impl TransferResponse {
+    pub fn new(message: String) -> Self {
+        Self {
+            message: Some(message),
+            error: None,
+            blocked: None,
+        }
+    }
+
+    pub fn blocked(entry: ReputationEntry) -> Self {
+        Self {
+            message: None,
+            error: Some(format!("Transfer refused, {}", entry.blocked_message())),
+            blocked: Some(entry),
+        }
+    }
+
+    pub fn message(&self) -> Option<&String> {
+        self.message.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    pub fn blocked_entry(&self) -> Option<&ReputationEntry> {
+        self.blocked.as_ref()
+    }
+}
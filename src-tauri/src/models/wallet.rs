@@ -1,4 +1,5 @@
-use bip39::{Mnemonic, MnemonicType, Seed};
+use bip39::Mnemonic;
+use sha2::{Digest, Sha512};
 use snarkvm::{
     console::prelude::*,
     prelude::{Address, PrivateKey, ViewKey},
@@ -7,7 +8,10 @@ use snarkvm::{
 use avail_common::errors::{AvailError, AvailResult};
 use zeroize::Zeroize;
 
-use crate::models::storage::languages::Languages;
+use crate::models::storage::languages::{seed_phrase_error, MnemonicLanguage};
+
+/// Domain separator of the hash accounts after the first one are derived with
+const ACCOUNT_DERIVATION_DOMAIN: &[u8] = b"AvailAccountDerivation";
 
 #[derive(Debug)]
 pub struct BetterAvailWallet<N: Network> {
@@ -30,30 +34,33 @@ impl<N: Network> Eq for BetterAvailWallet<N> {}
 impl<N: Network> BetterAvailWallet<N> {
     /// Generates a new [`BetterAvailWallet`], whilst throwing an error if the seed phrase length is not 12, 15, 18, 21, or 24.
     ///
+    /// The BIP-39 `passphrase` is mixed into the seed, an empty passphrase is the same as none.
+    ///
     /// ```
     /// use availx_lib::models::wallet::BetterAvailWallet;
-    /// # use availx_lib::models::storage::languages::Languages;
+    /// # use availx_lib::models::storage::languages::MnemonicLanguage;
     /// # use snarkvm::prelude::TestnetV0;
     ///
-    /// let wallet = BetterAvailWallet::<TestnetV0>::new(24, &Languages::English);
+    /// let wallet = BetterAvailWallet::<TestnetV0>::new(24, &MnemonicLanguage::English, "");
     ///
     /// assert!(wallet.is_ok());
     /// ```
-    pub fn new(seed_phrase_len: usize, seed_lang: &Languages) -> AvailResult<Self> {
-        let mnemonic = Mnemonic::new(
-            MnemonicType::for_word_count(seed_phrase_len)?,
-            Languages::to_bip39_language(seed_lang),
-        );
+    pub fn new(
+        seed_phrase_len: usize,
+        seed_lang: &MnemonicLanguage,
+        passphrase: &str,
+    ) -> AvailResult<Self> {
+        let mnemonic = Mnemonic::generate_in(seed_lang.to_bip39_language(), seed_phrase_len)
+            .map_err(seed_phrase_error)?;
 
-        // NOTE: EMPTY BECAUSE WE ARE NOT USING PASSWORDS FOR SPs
-        let seed = Seed::new(&mnemonic, "");
+        let seed = mnemonic.to_seed(passphrase);
 
         Self::from_mnemonic_seed(seed, mnemonic)
     }
 
     ///  This method returns the bytes of the [`Field`] used to derive an Aleo [(docs)](https://developer.aleo.org/concepts/accounts#create-an-account) [`PrivateKey`].
     ///
-    ///  Not to be confused with [`Seed`].
+    ///  Not to be confused with the BIP-39 seed of the [`Mnemonic`].
     pub fn get_seed_bytes(&self) -> AvailResult<Vec<u8>> {
         let seed_bytes = self.private_key.to_bytes_le()?;
 
@@ -61,16 +68,33 @@ impl<N: Network> BetterAvailWallet<N> {
     }
 
     /// Generates an [`AvailWallet`] from an arbitrary seed phrase, using the specified [`Language`].
-    pub fn from_seed_phrase(seed_phrase: &str, lang: bip39::Language) -> AvailResult<Self> {
-        let mnemonic = Mnemonic::from_phrase(seed_phrase, lang)?;
-        let seed = Seed::new(&mnemonic, "");
+    /// Every passphrase yields a different wallet from the same seed phrase.
+    pub fn from_seed_phrase(
+        seed_phrase: &str,
+        lang: bip39::Language,
+        passphrase: &str,
+    ) -> AvailResult<Self> {
+        let mnemonic = Mnemonic::parse_in(lang, seed_phrase).map_err(seed_phrase_error)?;
+        let seed = mnemonic.to_seed(passphrase);
 
         Self::from_mnemonic_seed(seed, mnemonic)
     }
 
-    /// Generates a [`BetterAvailWallet`] from the [`Seed`] of a [`Mnemonic`].
-    pub fn from_mnemonic_seed(seed: Seed, mnemonic: Mnemonic) -> AvailResult<Self> {
-        let bytes = &mut seed.as_bytes()[0..=32].to_vec();
+    /// Derives the account at `index` of an arbitrary seed phrase, using the specified [`Language`].
+    pub fn from_seed_phrase_at(
+        seed_phrase: &str,
+        lang: bip39::Language,
+        passphrase: &str,
+        index: u32,
+    ) -> AvailResult<Self> {
+        let mnemonic = Mnemonic::parse_in(lang, seed_phrase).map_err(seed_phrase_error)?;
+
+        Self::from_mnemonic_at(mnemonic, passphrase, index)
+    }
+
+    /// Generates a [`BetterAvailWallet`] from the BIP-39 seed of a [`Mnemonic`].
+    pub fn from_mnemonic_seed(seed: [u8; 64], mnemonic: Mnemonic) -> AvailResult<Self> {
+        let bytes = &mut seed[0..=32].to_vec();
 
         let field = <N as Environment>::Field::from_bytes_le_mod_order(bytes);
 
@@ -90,6 +114,40 @@ impl<N: Network> BetterAvailWallet<N> {
         })
     }
 
+    /// Derives the account at `index` of a [`Mnemonic`], the same index always yields the same account.
+    ///
+    /// Index 0 is the account [`BetterAvailWallet::from_mnemonic_seed`] derives, so existing wallets keep their address.
+    /// Every other index hashes the seed together with the index.
+    pub fn from_mnemonic_at(mnemonic: Mnemonic, passphrase: &str, index: u32) -> AvailResult<Self> {
+        let seed = mnemonic.to_seed(passphrase);
+
+        if index == 0 {
+            return Self::from_mnemonic_seed(seed, mnemonic);
+        }
+
+        let mut hasher = Sha512::new();
+        hasher.update(ACCOUNT_DERIVATION_DOMAIN);
+        hasher.update(seed);
+        hasher.update(index.to_le_bytes());
+        let bytes = &mut hasher.finalize().to_vec();
+
+        let field = <N as Environment>::Field::from_bytes_le_mod_order(bytes);
+
+        let private_key = PrivateKey::<N>::try_from(FromBytes::read_le(&*field.to_bytes_le()?)?)?;
+
+        bytes.zeroize();
+
+        let view_key = ViewKey::<N>::try_from(&private_key)?;
+        let address = Address::<N>::try_from(&private_key)?;
+
+        Ok(BetterAvailWallet::<N> {
+            address,
+            view_key,
+            private_key,
+            mnemonic: Some(mnemonic),
+        })
+    }
+
     /// Gets the private key string of an avail wallet.
     pub fn get_private_key(&self) -> String {
         self.private_key.to_string()
@@ -179,7 +237,8 @@ mod tests {
 
     #[rstest]
     fn test_create_random_avail_wallet(#[values(12, 15, 18, 21, 24)] seed_phrase_len: usize) {
-        let wallet = BetterAvailWallet::<TestnetV0>::new(seed_phrase_len, &Languages::English);
+        let wallet =
+            BetterAvailWallet::<TestnetV0>::new(seed_phrase_len, &MnemonicLanguage::English, "");
 
         assert!(wallet.is_ok());
 
@@ -189,15 +248,12 @@ mod tests {
 
     #[rstest]
     fn test_from_seed_bytes(#[values(12, 15, 18, 21, 24)] seed_phrase_len: usize) {
-        let mnemonic = Mnemonic::new(
-            MnemonicType::for_word_count(seed_phrase_len).unwrap(),
-            Languages::to_bip39_language(&Languages::English),
-        );
+        let mnemonic = Mnemonic::generate_in(bip39::Language::English, seed_phrase_len).unwrap();
 
-        assert_eq!(mnemonic.phrase().split(" ").count(), seed_phrase_len);
+        assert_eq!(mnemonic.to_string().split(" ").count(), seed_phrase_len);
 
-        let seed = Seed::new(&mnemonic, "");
-        let bytes = &mut seed.as_bytes()[0..32].to_vec();
+        let seed = mnemonic.to_seed("");
+        let bytes = &mut seed[0..32].to_vec();
 
         assert_eq!(bytes.len(), 32);
 
@@ -210,7 +266,8 @@ mod tests {
     /// Test that a wallet can be created from the seed phrase.
     fn test_get_seed_bytes(#[values(12, 15, 18, 21, 24)] seed_phrase_len: usize) {
         let wallet =
-            BetterAvailWallet::<TestnetV0>::new(seed_phrase_len, &Languages::English).unwrap();
+            BetterAvailWallet::<TestnetV0>::new(seed_phrase_len, &MnemonicLanguage::English, "")
+                .unwrap();
         let seed_bytes = wallet.get_seed_bytes().unwrap();
 
         assert_eq!(seed_bytes.len(), 32);
@@ -219,13 +276,14 @@ mod tests {
     #[rstest]
     /// Test that an avail wallet can be created from the seed phrase.
     fn test_from_seed_phrase() {
-        let mnemonic = Mnemonic::from_phrase(PHRASE, bip39::Language::English).unwrap();
+        let mnemonic = Mnemonic::parse_in(bip39::Language::English, PHRASE).unwrap();
 
-        let seed_phrase = mnemonic.phrase();
+        let seed_phrase = mnemonic.to_string();
 
         let wallet = BetterAvailWallet::<TestnetV0>::from_seed_phrase(
-            seed_phrase,
-            Languages::to_bip39_language(&Languages::English),
+            &seed_phrase,
+            bip39::Language::English,
+            "",
         );
 
         assert!(wallet.is_ok());
@@ -235,6 +293,23 @@ mod tests {
         assert_eq!(wallet.get_private_key(), PRIVATE_KEY)
     }
 
+    #[rstest]
+    /// Test that every passphrase yields a different wallet from the same seed phrase.
+    fn test_from_seed_phrase_with_passphrase() {
+        let lang = bip39::Language::English;
+
+        let protected =
+            BetterAvailWallet::<TestnetV0>::from_seed_phrase(PHRASE, lang, "TREZOR").unwrap();
+        let protected_again =
+            BetterAvailWallet::<TestnetV0>::from_seed_phrase(PHRASE, lang, "TREZOR").unwrap();
+        let other =
+            BetterAvailWallet::<TestnetV0>::from_seed_phrase(PHRASE, lang, "trezor").unwrap();
+
+        assert_eq!(protected, protected_again);
+        assert_ne!(protected.get_private_key(), PRIVATE_KEY);
+        assert_ne!(protected, other);
+    }
+
     #[rstest]
     /// Test that the private key string can be retrieved from the avail wallet.
     fn test_get_private_key() {
@@ -256,6 +331,24 @@ mod tests {
         );
     }
 
+    #[rstest]
+    /// Test that accounts are derived deterministically and that index 0 is the seed phrase's own account.
+    fn test_from_mnemonic_at() {
+        let mnemonic = || Mnemonic::parse_in(bip39::Language::English, PHRASE).unwrap();
+
+        let first = BetterAvailWallet::<TestnetV0>::from_mnemonic_at(mnemonic(), "", 0).unwrap();
+        assert_eq!(first.get_private_key(), PRIVATE_KEY);
+
+        let second = BetterAvailWallet::<TestnetV0>::from_mnemonic_at(mnemonic(), "", 1).unwrap();
+        let second_again =
+            BetterAvailWallet::<TestnetV0>::from_mnemonic_at(mnemonic(), "", 1).unwrap();
+        let third = BetterAvailWallet::<TestnetV0>::from_mnemonic_at(mnemonic(), "", 2).unwrap();
+
+        assert_eq!(second, second_again);
+        assert_ne!(second, first);
+        assert_ne!(second, third);
+    }
+
     #[rstest]
     fn test_get_address() {
         let wallet = BetterAvailWallet::<TestnetV0>::try_from(PRIVATE_KEY).unwrap();
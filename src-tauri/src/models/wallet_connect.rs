@@ -1,6 +1,13 @@
+pub mod abi;
 pub mod balance;
 pub mod create_event;
 pub mod decrypt;
+pub mod deploy;
+pub mod explain;
 pub mod get_event;
+pub mod mapping;
+pub mod policy;
 pub mod records;
+pub mod session;
 pub mod sign;
+pub mod typed_sign;
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/* Program ABI Interfaces */
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InputAbi {
+    pub register: String,
+    /// Type without visibility, i.e. `u64`, `token.record` or `credits.aleo/credits.record`
+    #[serde(rename = "type")]
+    pub value_type: String,
+    /// `constant`, `public`, `private`, `record`, `external_record` or `future`
+    pub visibility: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FunctionAbi {
+    pub name: String,
+    pub inputs: Vec<InputAbi>,
+}
+
+/// Public state of a program, key and value types are plaintext types
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MappingAbi {
+    pub name: String,
+    #[serde(rename = "keyType")]
+    pub key_type: String,
+    #[serde(rename = "valueType")]
+    pub value_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProgramAbi {
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    pub imports: Vec<String>,
+    pub functions: Vec<FunctionAbi>,
+    pub mappings: Vec<MappingAbi>,
+}
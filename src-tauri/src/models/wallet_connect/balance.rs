@@ -6,6 +6,14 @@ use serde::{Deserialize, Serialize};
 pub struct Balance {
     public: f64,
     private: f64,
+    #[serde(default)]
+    decimals: Option<u8>,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default)]
+    bonded: Option<f64>,
+    #[serde(default)]
+    unbonding: Option<f64>,
 }
 
 impl Balance {
@@ -25,8 +33,40 @@ impl Balance {
         self.total().to_string()
     }
 
-    pub fn new(public: f64, private: f64) -> Self {
-        Self { public, private }
+    pub fn decimals(&self) -> Option<u8> {
+        self.decimals
+    }
+
+    pub fn symbol(&self) -> Option<&String> {
+        self.symbol.as_ref()
+    }
+
+    /// Credits bonded to a validator, only reported for aleo credits
+    pub fn bonded(&self) -> Option<f64> {
+        self.bonded
+    }
+
+    /// Credits unbonding or waiting to be claimed, only reported for aleo credits
+    pub fn unbonding(&self) -> Option<f64> {
+        self.unbonding
+    }
+
+    pub fn new(
+        public: f64,
+        private: f64,
+        decimals: Option<u8>,
+        symbol: Option<String>,
+        bonded: Option<f64>,
+        unbonding: Option<f64>,
+    ) -> Self {
+        Self {
+            public,
+            private,
+            decimals,
+            symbol,
+            bonded,
+            unbonding,
+        }
     }
 }
 
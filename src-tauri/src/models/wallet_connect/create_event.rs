@@ -1,5 +1,7 @@
 use avail_common::models::encrypted_data::EventTypeCommon;
 use serde::{Deserialize, Serialize};
+
+use crate::models::reputation::ReputationEntry;
 /* Create Event Interfaces */
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -63,11 +65,26 @@ impl CreateEventRequest {
 pub struct CreateEventResponse {
     event_id: Option<String>,
     error: Option<String>,
+    /// Set when the request was refused because it involves a blocked program or address
+    #[serde(default)]
+    blocked: Option<ReputationEntry>,
 }
 
 impl CreateEventResponse {
     pub fn new(event_id: Option<String>, error: Option<String>) -> Self {
-        Self { event_id, error }
+        Self {
+            event_id,
+            error,
+            blocked: None,
+        }
+    }
+
+    pub fn blocked(entry: ReputationEntry) -> Self {
+        Self {
+            event_id: None,
+            error: Some(entry.blocked_message()),
+            blocked: Some(entry),
+        }
     }
 
     pub fn event_id(&self) -> Option<&String> {
@@ -77,4 +94,71 @@ impl CreateEventResponse {
     pub fn error(&self) -> Option<&String> {
         self.error.as_ref()
     }
+
+    pub fn blocked_entry(&self) -> Option<&ReputationEntry> {
+        self.blocked.as_ref()
+    }
+}
+
+/// Ordered executions requested in a single dapp call
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchCreateEventRequest {
+    steps: Vec<CreateEventRequest>,
+    /// Broadcast every step without waiting for the previous one to be confirmed
+    #[serde(default)]
+    parallel: bool,
+}
+
+impl BatchCreateEventRequest {
+    pub fn new(steps: Vec<CreateEventRequest>, parallel: bool) -> Self {
+        Self { steps, parallel }
+    }
+
+    pub fn steps(&self) -> &Vec<CreateEventRequest> {
+        &self.steps
+    }
+
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+}
+
+/// One response per step in the order of the request, steps after a failed one are reported as not executed
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchCreateEventResponse {
+    steps: Vec<CreateEventResponse>,
+    error: Option<String>,
+    /// Set when a step involves a blocked program or address, no step is executed then
+    #[serde(default)]
+    blocked: Option<ReputationEntry>,
+}
+
+impl BatchCreateEventResponse {
+    pub fn new(steps: Vec<CreateEventResponse>, error: Option<String>) -> Self {
+        Self {
+            steps,
+            error,
+            blocked: None,
+        }
+    }
+
+    pub fn blocked(step: usize, entry: ReputationEntry) -> Self {
+        Self {
+            steps: vec![],
+            error: Some(format!("Step {}: {}", step + 1, entry.blocked_message())),
+            blocked: Some(entry),
+        }
+    }
+
+    pub fn steps(&self) -> &Vec<CreateEventResponse> {
+        &self.steps
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    pub fn blocked_entry(&self) -> Option<&ReputationEntry> {
+        self.blocked.as_ref()
+    }
 }
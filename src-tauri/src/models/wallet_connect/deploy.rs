@@ -1 +1,26 @@
+use serde::{Deserialize, Serialize};
 
+/* Deployment Interfaces */
+
+/// Costs of a deployment in credits, before anything is signed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeploymentEstimate {
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    /// Imports that are deployed with it resolved, in the order they are loaded
+    pub imports: Vec<String>,
+    #[serde(rename = "storageCost")]
+    pub storage_cost: f64,
+    #[serde(rename = "namespaceCost")]
+    pub namespace_cost: f64,
+    #[serde(rename = "priorityFee")]
+    pub priority_fee: f64,
+    /// Minimum deployment cost plus the priority fee
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+    #[serde(rename = "feePrivate")]
+    pub fee_private: bool,
+    /// Whether the public balance, or a single private credits record, covers the total cost
+    #[serde(rename = "sufficientBalance")]
+    pub sufficient_balance: bool,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::reputation::ReputationEntry;
+
+/* Transaction Explanation Interfaces */
+
+/// A record of the wallet the execution spends
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConsumedRecord {
+    /// Position of the record among the inputs, starting at 0
+    pub input: usize,
+    pub nonce: String,
+    pub asset: Option<String>,
+    pub amount: Option<f64>,
+}
+
+/// A record the execution creates
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatedRecord {
+    /// Position of the record among the outputs, starting at 0
+    pub output: usize,
+    #[serde(rename = "recordName")]
+    pub record_name: String,
+    /// Owner of the record, unknown when the owner is computed while executing
+    pub recipient: Option<String>,
+    #[serde(rename = "toSelf")]
+    pub to_self: bool,
+}
+
+/// Public balance moved by a known program, no `from` or `to` means a private record is on that side
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublicTransfer {
+    pub asset: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExplainedInput {
+    pub register: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub visibility: String,
+    /// Records are left out, they are listed in `consumedRecords`
+    pub value: Option<String>,
+}
+
+/// What an execution request does, for approval screens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TransactionExplanation {
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    #[serde(rename = "functionId")]
+    pub function_id: String,
+    /// Only set for known programs
+    pub description: Option<String>,
+    pub inputs: Vec<ExplainedInput>,
+    #[serde(rename = "consumedRecords")]
+    pub consumed_records: Vec<ConsumedRecord>,
+    #[serde(rename = "createdRecords")]
+    pub created_records: Vec<CreatedRecord>,
+    #[serde(rename = "publicTransfers")]
+    pub public_transfers: Vec<PublicTransfer>,
+    /// Base fee plus priority fee in credits, only the priority fee when the base fee couldn't be estimated
+    pub fee: f64,
+    /// Cost of the execution or deployment charged by the network
+    #[serde(rename = "baseFee")]
+    pub base_fee: Option<f64>,
+    #[serde(rename = "priorityFee")]
+    pub priority_fee: f64,
+    #[serde(rename = "feePrivate")]
+    pub fee_private: bool,
+    /// Effects that couldn't be decoded, and blocked programs or addresses
+    pub warnings: Vec<String>,
+    /// Reputation of the program and the addresses involved, targets without an entry are left out
+    pub reputation: Vec<ReputationEntry>,
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/* Mapping Interfaces */
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MappingValueRequest {
+    #[serde(rename = "programId")]
+    program_id: String,
+    mapping: String,
+    /// Checked against the key type of the mapping, literal suffixes can be left out
+    key: String,
+}
+
+impl MappingValueRequest {
+    pub fn new(program_id: String, mapping: String, key: String) -> Self {
+        Self {
+            program_id,
+            mapping,
+            key,
+        }
+    }
+
+    pub fn program_id(&self) -> &String {
+        &self.program_id
+    }
+
+    pub fn mapping(&self) -> &String {
+        &self.mapping
+    }
+
+    pub fn key(&self) -> &String {
+        &self.key
+    }
+}
+
+/// No value and no error means the key isn't set in the mapping
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MappingValueResponse {
+    value: Option<String>,
+    error: Option<String>,
+}
+
+impl MappingValueResponse {
+    pub fn new(value: Option<String>, error: Option<String>) -> Self {
+        Self { value, error }
+    }
+
+    pub fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MappingKeysRequest {
+    #[serde(rename = "programId")]
+    program_id: String,
+    mapping: String,
+}
+
+impl MappingKeysRequest {
+    pub fn new(program_id: String, mapping: String) -> Self {
+        Self {
+            program_id,
+            mapping,
+        }
+    }
+
+    pub fn program_id(&self) -> &String {
+        &self.program_id
+    }
+
+    pub fn mapping(&self) -> &String {
+        &self.mapping
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MappingKeysResponse {
+    keys: Vec<String>,
+    error: Option<String>,
+}
+
+impl MappingKeysResponse {
+    pub fn new(keys: Vec<String>, error: Option<String>) -> Self {
+        Self { keys, error }
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/* Spending Policy Interfaces */
+
+/// Program function a policy auto-approves, no function id allows every function of the program
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AllowedCall {
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    #[serde(rename = "functionId")]
+    pub function_id: Option<String>,
+}
+
+impl AllowedCall {
+    pub fn matches(&self, program_id: &str, function_id: &str) -> bool {
+        self.program_id == program_id
+            && match &self.function_id {
+                Some(allowed) => allowed == function_id,
+                None => true,
+            }
+    }
+}
+
+/// Limits under which requests are executed without asking the user.
+/// Amounts are display amounts keyed by program id, token registry tokens by their token id.
+/// Fees and credits transfers count towards `credits.aleo`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpendingPolicy {
+    /// Dapp origin the policy applies to, a policy without origin applies to every dapp
+    pub origin: Option<String>,
+    #[serde(rename = "maxPerTransaction")]
+    pub max_per_transaction: Option<f64>,
+    #[serde(rename = "dailyLimits", default)]
+    pub daily_limits: HashMap<String, f64>,
+    #[serde(rename = "allowedCalls", default)]
+    pub allowed_calls: Vec<AllowedCall>,
+}
+
+impl SpendingPolicy {
+    pub fn new(
+        origin: Option<String>,
+        max_per_transaction: Option<f64>,
+        daily_limits: HashMap<String, f64>,
+        allowed_calls: Vec<AllowedCall>,
+    ) -> Self {
+        Self {
+            origin,
+            max_per_transaction,
+            daily_limits,
+            allowed_calls,
+        }
+    }
+
+    /// Checks a request against the policy, `spend` is the asset and amount the call transfers
+    /// and `spent_today` holds what was spent in the last 24 hours per asset.
+    /// Returns the reason when the request is outside the policy.
+    pub fn check(
+        &self,
+        program_id: &str,
+        function_id: &str,
+        spend: Option<(&str, f64)>,
+        fee: f64,
+        spent_today: &HashMap<String, f64>,
+    ) -> Result<(), String> {
+        if !self
+            .allowed_calls
+            .iter()
+            .any(|call| call.matches(program_id, function_id))
+        {
+            return Err(format!(
+                "{}/{} is not in the allowed calls",
+                program_id, function_id
+            ));
+        }
+
+        if let (Some(max), Some((_, amount))) = (self.max_per_transaction, spend) {
+            if amount > max {
+                return Err(format!(
+                    "Amount {} is above the limit of {} per transaction",
+                    amount, max
+                ));
+            }
+        }
+
+        let mut spending: HashMap<&str, f64> = HashMap::new();
+        if let Some((asset, amount)) = spend {
+            *spending.entry(asset).or_insert(0.0) += amount;
+        }
+        *spending.entry("credits.aleo").or_insert(0.0) += fee;
+
+        for (asset, value) in spending {
+            if value <= 0.0 {
+                continue;
+            }
+
+            let limit = match self.daily_limits.get(asset) {
+                Some(limit) => *limit,
+                None => return Err(format!("No daily limit is set for {}", asset)),
+            };
+
+            let spent = spent_today.get(asset).copied().unwrap_or(0.0);
+            if spent + value > limit {
+                return Err(format!(
+                    "Daily limit of {} for {} would be exceeded, {} was spent today",
+                    limit, asset, spent
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How a wallet connect request is approved
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "decision", content = "reason", rename_all = "snake_case")]
+pub enum PolicyDecision {
+    /// Within every applicable policy, executed without asking the user
+    AutoApprove,
+    /// No policy applies, the user confirms the request as before
+    Prompt,
+    /// Outside a policy, the user has to enter their password
+    Reauthenticate(String),
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/* Dapp Session Interfaces */
+
+/// Permission a dapp can be granted, program scopes only cover the listed programs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "programs", rename_all = "snake_case")]
+pub enum DappScope {
+    ReadBalance,
+    ReadRecords(Vec<String>),
+    Decrypt,
+    Sign,
+    Execute(Vec<String>),
+}
+
+impl DappScope {
+    /// Whether this granted scope covers the requested one
+    pub fn covers(&self, requested: &DappScope) -> bool {
+        match (self, requested) {
+            (DappScope::ReadBalance, DappScope::ReadBalance) => true,
+            (DappScope::Decrypt, DappScope::Decrypt) => true,
+            (DappScope::Sign, DappScope::Sign) => true,
+            (DappScope::ReadRecords(granted), DappScope::ReadRecords(requested))
+            | (DappScope::Execute(granted), DappScope::Execute(requested)) => {
+                !requested.is_empty() && requested.iter().all(|program| granted.contains(program))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Permissions granted to a dapp, keyed by its origin
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DappSession {
+    origin: String,
+    address: String,
+    scopes: Vec<DappScope>,
+    created_at: i64,
+    expires_at: Option<i64>,
+    revoked: bool,
+}
+
+impl DappSession {
+    pub fn new(
+        origin: String,
+        address: String,
+        scopes: Vec<DappScope>,
+        created_at: i64,
+        expires_at: Option<i64>,
+        revoked: bool,
+    ) -> Self {
+        Self {
+            origin,
+            address,
+            scopes,
+            created_at,
+            expires_at,
+            revoked,
+        }
+    }
+
+    pub fn origin(&self) -> &String {
+        &self.origin
+    }
+
+    pub fn address(&self) -> &String {
+        &self.address
+    }
+
+    pub fn scopes(&self) -> &Vec<DappScope> {
+        &self.scopes
+    }
+
+    pub fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    pub fn expires_at(&self) -> Option<i64> {
+        self.expires_at
+    }
+
+    pub fn revoked(&self) -> bool {
+        self.revoked
+    }
+
+    pub fn is_active(&self, now: i64) -> bool {
+        !self.revoked && !matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+
+    pub fn allows(&self, requested: &DappScope) -> bool {
+        self.scopes.iter().any(|scope| scope.covers(requested))
+    }
+
+    /// Adds a scope the user approved, program lists are merged into the scope already granted
+    pub fn grant(&mut self, scope: DappScope) {
+        for granted in self.scopes.iter_mut() {
+            match (granted, &scope) {
+                (DappScope::ReadRecords(programs), DappScope::ReadRecords(added))
+                | (DappScope::Execute(programs), DappScope::Execute(added)) => {
+                    for program in added {
+                        if !programs.contains(program) {
+                            programs.push(program.clone());
+                        }
+                    }
+                    return;
+                }
+                (granted, scope) if granted == scope => return,
+                _ => {}
+            }
+        }
+
+        self.scopes.push(scope);
+    }
+
+    /// Programs the dapp may read records and events of
+    pub fn readable_programs(&self) -> Vec<String> {
+        self.scopes
+            .iter()
+            .filter_map(|scope| match scope {
+                DappScope::ReadRecords(programs) => Some(programs.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use snarkvm::prelude::{Address, Field, Network};
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Prefix of every encoded typed message, plain `sign` requests starting with it are refused
+pub const TYPED_DATA_PREFIX: &str = "aleo-typed-data:v1";
+
+/// Limit on nested struct and array types
+const MAX_TYPE_DEPTH: usize = 16;
+
+const INTEGER_TYPES: [&str; 10] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/* Typed Signing Interfaces */
+
+/// Member of a struct type, `type` is a primitive (`address`, `field`, `bool`, `string`, `u8`..`u128`, `i8`..`i128`),
+/// another type declared in `types`, or either of them followed by `[]`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TypedField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+/// Binds a signature to the dapp, the network and a single use
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SigningDomain {
+    pub origin: String,
+    pub network: String,
+    #[serde(rename = "chainId")]
+    pub chain_id: u16,
+    pub nonce: String,
+    /// Unix timestamp after which the signature is no longer valid
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+}
+
+impl SigningDomain {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypedSignatureRequest {
+    pub domain: SigningDomain,
+    pub types: BTreeMap<String, Vec<TypedField>>,
+    #[serde(rename = "primaryType")]
+    pub primary_type: String,
+    pub message: Value,
+    pub address: Option<String>,
+}
+
+fn invalid(internal: String) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Validation,
+        internal.clone(),
+        format!("Invalid typed data: {}", internal),
+    )
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Integers are accepted as json numbers or strings, with or without their type suffix
+fn encode_integer(integer_type: &str, value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        _ => return None,
+    };
+    let text = text.strip_suffix(integer_type).unwrap_or(text.as_str());
+
+    let integer = match integer_type {
+        "u8" => text.parse::<u8>().ok()?.to_string(),
+        "u16" => text.parse::<u16>().ok()?.to_string(),
+        "u32" => text.parse::<u32>().ok()?.to_string(),
+        "u64" => text.parse::<u64>().ok()?.to_string(),
+        "u128" => text.parse::<u128>().ok()?.to_string(),
+        "i8" => text.parse::<i8>().ok()?.to_string(),
+        "i16" => text.parse::<i16>().ok()?.to_string(),
+        "i32" => text.parse::<i32>().ok()?.to_string(),
+        "i64" => text.parse::<i64>().ok()?.to_string(),
+        "i128" => text.parse::<i128>().ok()?.to_string(),
+        _ => return None,
+    };
+
+    Some(format!("{}{}", integer, integer_type))
+}
+
+impl TypedSignatureRequest {
+    fn check_types(&self) -> AvailResult<()> {
+        for (name, fields) in &self.types {
+            let primitive = ["address", "field", "bool", "string"].contains(&name.as_str())
+                || INTEGER_TYPES.contains(&name.as_str());
+            if !is_identifier(name) || primitive {
+                return Err(invalid(format!("'{}' can't be used as a type name", name)));
+            }
+
+            let mut names = HashSet::new();
+            for field in fields {
+                if !is_identifier(&field.name) || !names.insert(&field.name) {
+                    return Err(invalid(format!(
+                        "field '{}' of {} is not a unique identifier",
+                        field.name, name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn encode_value<N: Network>(
+        &self,
+        value_type: &str,
+        value: &Value,
+        depth: usize,
+    ) -> AvailResult<String> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(invalid("types are nested too deeply".to_string()));
+        }
+
+        let mismatch = || invalid(format!("value {} is not a valid {}", value, value_type));
+
+        if let Some(item_type) = value_type.strip_suffix("[]") {
+            let items = value.as_array().ok_or_else(mismatch)?;
+            let items = items
+                .iter()
+                .map(|item| self.encode_value::<N>(item_type, item, depth + 1))
+                .collect::<AvailResult<Vec<String>>>()?;
+
+            return Ok(format!("[{}]", items.join(",")));
+        }
+
+        if let Some(fields) = self.types.get(value_type) {
+            let object = value.as_object().ok_or_else(mismatch)?;
+
+            // everything that is signed has to be part of the schema the user is shown
+            if let Some(key) = object
+                .keys()
+                .find(|key| !fields.iter().any(|field| &field.name == *key))
+            {
+                return Err(invalid(format!("{} has no field '{}'", value_type, key)));
+            }
+
+            let members = fields
+                .iter()
+                .map(|field| {
+                    let member = object.get(&field.name).ok_or_else(|| {
+                        invalid(format!("{} is missing '{}'", value_type, field.name))
+                    })?;
+
+                    Ok(format!(
+                        "{}:{}={}",
+                        field.name,
+                        field.field_type,
+                        self.encode_value::<N>(&field.field_type, member, depth + 1)?
+                    ))
+                })
+                .collect::<AvailResult<Vec<String>>>()?;
+
+            return Ok(format!("{}{{{}}}", value_type, members.join(",")));
+        }
+
+        match value_type {
+            "address" => {
+                let address = value.as_str().ok_or_else(mismatch)?;
+                Ok(Address::<N>::from_str(address)
+                    .map_err(|_| mismatch())?
+                    .to_string())
+            }
+            "field" => {
+                let field = value.as_str().ok_or_else(mismatch)?;
+                Ok(Field::<N>::from_str(field).map_err(|_| mismatch())?.to_string())
+            }
+            "bool" => Ok(value.as_bool().ok_or_else(mismatch)?.to_string()),
+            "string" => Ok(serde_json::to_string(value.as_str().ok_or_else(mismatch)?)?),
+            _ if INTEGER_TYPES.contains(&value_type) => {
+                encode_integer(value_type, value).ok_or_else(mismatch)
+            }
+            _ => Err(invalid(format!("unknown type '{}'", value_type))),
+        }
+    }
+
+    fn encode_domain(&self) -> AvailResult<String> {
+        let domain = &self.domain;
+
+        Ok(format!(
+            "Domain{{origin:string={},network:string={},chainId:u16={}u16,nonce:string={},expiresAt:i64={}i64}}",
+            serde_json::to_string(&domain.origin)?,
+            serde_json::to_string(&domain.network)?,
+            domain.chain_id,
+            serde_json::to_string(&domain.nonce)?,
+            domain.expires_at
+        ))
+    }
+
+    /// Canonical text of the domain and the message, the same request always encodes to the same text.
+    /// Its BHP512 hash is what gets signed, like any other message.
+    pub fn encode<N: Network>(&self) -> AvailResult<String> {
+        self.check_types()?;
+
+        if !self.types.contains_key(&self.primary_type) {
+            return Err(invalid(format!(
+                "primary type '{}' is not declared",
+                self.primary_type
+            )));
+        }
+
+        Ok(format!(
+            "{}\n{}\n{}",
+            TYPED_DATA_PREFIX,
+            self.encode_domain()?,
+            self.encode_value::<N>(&self.primary_type, &self.message, 0)?
+        ))
+    }
+}
+
+#[cfg(test)]
+mod typed_sign_tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestnetV0};
+
+    fn order_request(message: Value) -> TypedSignatureRequest {
+        serde_json::from_value(serde_json::json!({
+            "domain": {
+                "origin": "https://dex.example",
+                "network": "testnet",
+                "chainId": 1,
+                "nonce": "42",
+                "expiresAt": 1735689600
+            },
+            "types": {
+                "Order": [
+                    {"name": "maker", "type": "address"},
+                    {"name": "amount", "type": "u64"},
+                    {"name": "tags", "type": "string[]"},
+                    {"name": "asset", "type": "Asset"}
+                ],
+                "Asset": [{"name": "id", "type": "field"}]
+            },
+            "primaryType": "Order",
+            "message": message,
+            "address": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_encode_typed_data() {
+        let rng = &mut rand::thread_rng();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let address = Address::<TestnetV0>::try_from(&private_key).unwrap();
+
+        let request = order_request(serde_json::json!({
+            "asset": {"id": "7field"},
+            "amount": "0500u64",
+            "maker": address.to_string(),
+            "tags": ["limit", "a\"b"]
+        }));
+
+        let encoded = request.encode::<TestnetV0>().unwrap();
+        assert_eq!(
+            encoded,
+            format!(
+                "aleo-typed-data:v1\nDomain{{origin:string=\"https://dex.example\",network:string=\"testnet\",chainId:u16=1u16,nonce:string=\"42\",expiresAt:i64=1735689600i64}}\nOrder{{maker:address={},amount:u64=500u64,tags:string[]=[\"limit\",\"a\\\"b\"],asset:Asset=Asset{{id:field=7field}}}}",
+                address
+            )
+        );
+
+        // the same values in another order or notation encode the same
+        let reordered = order_request(serde_json::json!({
+            "maker": address.to_string(),
+            "tags": ["limit", "a\"b"],
+            "amount": 500,
+            "asset": {"id": "7field"}
+        }));
+        assert_eq!(reordered.encode::<TestnetV0>().unwrap(), encoded);
+    }
+
+    #[test]
+    fn test_reject_invalid_typed_data() {
+        let rng = &mut rand::thread_rng();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let address = Address::<TestnetV0>::try_from(&private_key).unwrap();
+
+        let valid = serde_json::json!({
+            "maker": address.to_string(),
+            "amount": 1,
+            "tags": [],
+            "asset": {"id": "1field"}
+        });
+        assert!(order_request(valid.clone()).encode::<TestnetV0>().is_ok());
+
+        let mut extra = valid.clone();
+        extra["hidden"] = Value::from("transfer everything");
+        assert!(order_request(extra).encode::<TestnetV0>().is_err());
+
+        let mut missing = valid.clone();
+        missing.as_object_mut().unwrap().remove("amount");
+        assert!(order_request(missing).encode::<TestnetV0>().is_err());
+
+        let mut overflow = valid.clone();
+        overflow["amount"] = Value::from("-1");
+        assert!(order_request(overflow).encode::<TestnetV0>().is_err());
+
+        let mut bad_address = valid;
+        bad_address["maker"] = Value::from("aleo1notanaddress");
+        assert!(order_request(bad_address).encode::<TestnetV0>().is_err());
+    }
+
+    #[test]
+    fn test_domain_expiry() {
+        let request = order_request(Value::Null);
+
+        assert!(!request.domain.is_expired(1735689599));
+        assert!(request.domain.is_expired(1735689600));
+    }
+}
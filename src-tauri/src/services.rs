@@ -1,6 +1,8 @@
 pub mod account;
 pub mod authentication;
 pub mod local_storage;
+pub mod payment_request;
 pub mod record_handling;
 pub mod records;
+pub mod rpc_server;
 pub mod wallet_connect_api;
@@ -1,4 +1,9 @@
+pub mod derivation;
 pub mod generation;
 pub mod key_management;
+pub mod passphrase;
+pub mod password;
 pub mod phrase_recovery;
+pub mod shard_recovery;
+pub mod signer;
 pub mod utils;
@@ -0,0 +1,269 @@
+use snarkvm::prelude::{Identifier, Network, PrivateKey, TestnetV0, ViewKey};
+use std::str::FromStr;
+
+use crate::models::account::DerivedAccount;
+use crate::models::storage::envelope::is_envelope;
+use crate::models::wallet::BetterAvailWallet;
+use crate::services::account::key_management::key_controller::{
+    linuxKeyController, macKeyController, windowsKeyController, KeyController,
+};
+use crate::services::account::passphrase::seed_passphrase;
+use crate::services::local_storage::{
+    accounts::{
+        get_account, list_accounts, store_account, update_account_last_sync, update_account_name,
+        AccountRow,
+    },
+    persistent_storage::{
+        get_active_account, get_address_string, get_last_sync, get_mnemonic_language, get_network,
+        update_active_account, update_address, update_last_sync,
+    },
+    session::{password::PASS, view::VIEWSESSION},
+    utils::{decrypt_view_key_with_password, encrypt_view_key_with_password},
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+/// Falls back to the password of the current session
+fn session_password(password: Option<String>) -> AvailResult<String> {
+    match password {
+        Some(password) => Ok(password),
+        None => PASS.get_instance(),
+    }
+}
+
+fn default_name(index: u32) -> String {
+    format!("Account {}", index + 1)
+}
+
+/// Derives the account at `index` from the seed phrase stored in the keychain and the wallet's passphrase
+pub fn derive_wallet<N: Network>(index: u32, password: &str) -> AvailResult<BetterAvailWallet<N>> {
+    derive_wallet_with::<N>(index, password, &seed_passphrase::<N>(password)?)
+}
+
+pub fn derive_wallet_with<N: Network>(
+    index: u32,
+    password: &str,
+    passphrase: &str,
+) -> AvailResult<BetterAvailWallet<N>> {
+    let key_manager = {
+        #[cfg(target_os = "macos")]
+        {
+            macKeyController
+        }
+        #[cfg(target_os = "windows")]
+        {
+            windowsKeyController
+        }
+        #[cfg(target_os = "linux")]
+        {
+            linuxKeyController
+        }
+    };
+
+    let val: Identifier<N> = Identifier::<N>::from_str("test")?;
+    let seed_phrase = key_manager.read_phrase(password, val)?;
+
+    BetterAvailWallet::<N>::from_seed_phrase_at(
+        &seed_phrase,
+        get_mnemonic_language()?.to_bip39_language(),
+        passphrase,
+        index,
+    )
+}
+
+/// Private key of the active account, accounts after the first one are derived from the seed phrase
+pub fn get_derived_private_key<N: Network>(
+    index: u32,
+    password: Option<String>,
+) -> AvailResult<PrivateKey<N>> {
+    let wallet = derive_wallet::<N>(index, &session_password(password)?)?;
+
+    Ok(wallet.private_key)
+}
+
+/// Registers the seed phrase's own account the first time another account is derived
+fn register_first_account<N: Network>(password: &str) -> AvailResult<()> {
+    if get_account(0)?.is_some() {
+        return Ok(());
+    }
+
+    let wallet = derive_wallet::<N>(0, password)?;
+
+    // wallets imported from a private key don't have a seed phrase to derive from
+    if wallet.get_address() != get_address_string()? {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "The stored seed phrase and passphrase do not derive the wallet address".to_string(),
+            "Accounts can only be derived in wallets created or recovered from a seed phrase"
+                .to_string(),
+        ));
+    }
+
+    let view_key = encrypt_view_key_with_password::<N>(password, &wallet.view_key)?;
+    store_account(
+        0,
+        &wallet.get_address(),
+        &default_name(0),
+        &view_key.to_string(),
+        get_last_sync()?,
+    )
+}
+
+fn derive_account_raw<N: Network>(
+    name: Option<String>,
+    password: String,
+) -> AvailResult<DerivedAccount> {
+    register_first_account::<N>(&password)?;
+
+    let index = match list_accounts()?.last() {
+        Some(row) => row.account.index + 1,
+        None => 0,
+    };
+
+    let wallet = derive_wallet::<N>(index, &password)?;
+    let name = name.unwrap_or_else(|| default_name(index));
+    let view_key = encrypt_view_key_with_password::<N>(&password, &wallet.view_key)?;
+
+    // a new account is scanned from the start the first time it is switched to
+    store_account(
+        index,
+        &wallet.get_address(),
+        &name,
+        &view_key.to_string(),
+        0,
+    )?;
+    VIEWSESSION.set_account_view_key(&wallet.get_address(), &wallet.view_key.to_string())?;
+
+    Ok(DerivedAccount {
+        index,
+        name,
+        address: wallet.get_address(),
+        active: false,
+    })
+}
+
+/// Derives the next account from the wallet's seed phrase and adds it to the account registry
+#[tauri::command(rename_all = "snake_case")]
+pub fn derive_account(name: Option<String>, password: String) -> AvailResult<DerivedAccount> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => derive_account_raw::<TestnetV0>(name, password),
+        _ => derive_account_raw::<TestnetV0>(name, password),
+    }
+}
+
+/// Registered accounts, a wallet that never derived an account only has the one of its seed phrase
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_accounts() -> AvailResult<Vec<DerivedAccount>> {
+    let accounts = list_accounts()?;
+
+    if accounts.is_empty() {
+        return Ok(vec![DerivedAccount {
+            index: 0,
+            name: default_name(0),
+            address: get_address_string()?,
+            active: true,
+        }]);
+    }
+
+    Ok(accounts.into_iter().map(|row| row.account).collect())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn rename_account(index: u32, name: String) -> AvailResult<()> {
+    if get_account(index)?.is_none() {
+        return Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("No account with index {}", index),
+            "Account not found".to_string(),
+        ));
+    }
+
+    update_account_name(index, name.trim())
+}
+
+/// View key of a registered account, cached for the rest of the session once decrypted
+fn account_view_key<N: Network>(
+    row: &AccountRow,
+    password: Option<String>,
+) -> AvailResult<ViewKey<N>> {
+    if let Ok(view_key) = VIEWSESSION.get_account_instance::<N>(&row.account.address) {
+        return Ok(view_key);
+    }
+
+    let password = session_password(password)?;
+    let view_key = decrypt_view_key_with_password::<N>(&password, &row.view_key)?;
+
+    // view keys registered before envelopes are sealed again once the password opened them
+    if !is_envelope(&row.view_key) {
+        let sealed = encrypt_view_key_with_password::<N>(&password, &view_key)?;
+        store_account(
+            row.account.index,
+            &row.account.address,
+            &row.account.name,
+            &sealed,
+            row.last_sync,
+        )?;
+    }
+
+    VIEWSESSION.set_account_view_key(&row.account.address, &view_key.to_string())?;
+
+    Ok(view_key)
+}
+
+/// Decrypts the view keys of every registered account and returns the one of the active account
+pub fn unlock_accounts<N: Network>(password: Option<String>) -> AvailResult<Option<ViewKey<N>>> {
+    let active = get_active_account()?;
+    let mut active_view_key = None;
+
+    for row in list_accounts()? {
+        let view_key = account_view_key::<N>(&row, password.clone())?;
+
+        if row.account.index == active {
+            active_view_key = Some(view_key);
+        }
+    }
+
+    Ok(active_view_key)
+}
+
+/// Makes a registered account the active one.
+///
+/// The address, scan height and view session in use are swapped for the account's own,
+/// so balances, records and scanning only ever see the active account.
+fn switch_account_raw<N: Network>(index: u32, password: Option<String>) -> AvailResult<()> {
+    let row = match get_account(index)? {
+        Some(row) => row,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::NotFound,
+                format!("No account with index {}", index),
+                "Account not found".to_string(),
+            ))
+        }
+    };
+
+    let active = get_active_account()?;
+    if row.account.index == active {
+        return Ok(());
+    }
+
+    let view_key = account_view_key::<N>(&row, password)?;
+
+    update_account_last_sync(active, get_last_sync()?)?;
+
+    update_address(&row.account.address)?;
+    update_last_sync(row.last_sync)?;
+    VIEWSESSION.set_view_session(&view_key.to_string())?;
+    update_active_account(index)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn switch_account(index: u32, password: Option<String>) -> AvailResult<()> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => switch_account_raw::<TestnetV0>(index, password),
+        _ => switch_account_raw::<TestnetV0>(index, password),
+    }
+}
@@ -1,14 +1,15 @@
 use snarkvm::prelude::*;
 
-use crate::models::storage::languages::Languages;
+use crate::models::storage::languages::{Languages, MnemonicLanguage};
 use crate::services::account::{
-    key_management::key_controller::KeyController, utils::generate_discriminant,
+    key_management::key_controller::KeyController, passphrase::save_passphrase,
+    utils::generate_discriminant,
 };
 use crate::services::authentication::session::get_session_after_creation;
 use crate::services::local_storage::persistent_storage::get_language;
 use crate::services::local_storage::{
     encrypted_data::{get_and_store_all_data, initialize_encrypted_data_table},
-    persistent_storage::initial_user_preferences,
+    persistent_storage::{initial_user_preferences, update_mnemonic_language},
     session::{password::PASS, view::VIEWSESSION},
     tokens::init_tokens_table,
 };
@@ -33,8 +34,18 @@ pub async fn create_seed_phrase_wallet(
     backup: bool,
     language: Languages,
     length: usize,
+    passphrase: Option<String>,
+    store_passphrase: bool,
+    mnemonic_language: Option<MnemonicLanguage>,
 ) -> AvailResult<String> {
-    let avail_wallet = BetterAvailWallet::<TestnetV0>::new(length, &language)?;
+    // the seed phrase language is not tied to the interface language
+    let mnemonic_language = mnemonic_language.unwrap_or(MnemonicLanguage::English);
+
+    let avail_wallet = BetterAvailWallet::<TestnetV0>::new(
+        length,
+        &mnemonic_language,
+        passphrase.as_deref().unwrap_or(""),
+    )?;
 
     let tag = username.clone().map(|_| generate_discriminant());
 
@@ -55,8 +66,9 @@ pub async fn create_seed_phrase_wallet(
         false,
         backup,
         avail_wallet.address.to_string(),
-        language.clone(),
+        language,
     )?;
+    update_mnemonic_language(&mnemonic_language)?;
 
     init_tokens_table()?;
 
@@ -78,6 +90,7 @@ pub async fn create_seed_phrase_wallet(
     };
 
     key_manager.store_key(&password, &avail_wallet)?;
+    save_passphrase::<TestnetV0>(&password, passphrase.as_deref(), store_passphrase)?;
 
     VIEWSESSION.set_view_session(&avail_wallet.get_view_key())?;
 
@@ -87,7 +100,7 @@ pub async fn create_seed_phrase_wallet(
 
     // NOTE: We can safely unwrap here because we created
     // the wallet using the [`BetterAvailWallet::new`] method
-    let seed_phrase = avail_wallet.mnemonic.unwrap().phrase().to_string();
+    let seed_phrase = avail_wallet.mnemonic.unwrap().to_string();
 
     Ok(seed_phrase)
 }
@@ -11,6 +11,7 @@ use crate::api::{encrypted_data::delete_all_server_storage, user::delete_user};
 use crate::models::{
     auth::Options,
     storage::encryption::{EncryptedData, Keys, Keys::PrivateKey as PKey, Keys::ViewKey as VKey},
+    storage::profile::keyring_service_for,
     wallet::AvailWallet,
 };
 
@@ -18,6 +19,7 @@ use crate::services::local_storage::{
     persistent_storage::{
         delete_user_preferences, get_auth_type, get_network, remove_view_session,
     },
+    profiles::get_active_profile,
     records_storage::delete_user_encrypted_data,
     utils::encrypt_with_password,
 };
@@ -27,6 +29,11 @@ use avail_common::{
     errors::{AvailError, AvailErrorType, AvailResult},
 };
 
+/// Keystore alias of the active profile, the default profile keeps the alias of older installations
+fn profile_alias() -> AvailResult<String> {
+    Ok(keyring_service_for(&get_active_profile()?, "AV_KEYSTORE"))
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "system" fn Java_com_example_keystore_KeyStoreModule_create(
@@ -377,7 +384,7 @@ pub fn keystore_init<N: Network>(
 
     let options = result.l()?;
 
-    let alias = env.new_string("AV_KEYSTORE")?;
+    let alias = env.new_string(profile_alias()?)?;
 
     let context = unsafe { JObject::from_raw(activity as jni::sys::jobject) };
 
@@ -518,7 +525,7 @@ pub fn keystore_load<N: Network>(password: Option<&str>, key_type: &str) -> Avai
 
     let options = result.l()?;
     let jkey_type = env.new_string(key_type.to_string())?;
-    let alias = env.new_string("AV_KEYSTORE")?;
+    let alias = env.new_string(profile_alias()?)?;
     let context = unsafe { JObject::from_raw(activity as jni::sys::jobject) };
 
     let auth = match auth_type.as_str() {
@@ -610,7 +617,7 @@ pub fn keystore_delete(password: Option<&str>) -> AvailResult<String> {
 
     let class = env.find_class("com/example/keystore/KeyStoreModule")?;
 
-    let alias = env.new_string("AV_KEYSTORE")?;
+    let alias = env.new_string(profile_alias()?)?;
     let context = unsafe { JObject::from_raw(activity as jni::sys::jobject) };
 
     Java_com_example_keystore_KeyStoreModule_delete(env, class, alias, context)?;
@@ -1,16 +1,18 @@
 use bip39::Mnemonic;
 use keyring::Entry;
 use snarkvm::console::program::{FromFields, Itertools, ToFields};
-use snarkvm::prelude::{
-    Ciphertext, Field, FromStr, Literal, Network, Plaintext, PrivateKey, StringType, ViewKey,
-};
+use snarkvm::prelude::{Ciphertext, Field, FromStr, Network, PrivateKey, ViewKey};
 
 use crate::{
     helpers::validation::validate_secret_password,
-    models::wallet::BetterAvailWallet,
-    services::local_storage::utils::{
-        encrypt_private_key_with_password, encrypt_view_key_with_password,
+    models::{
+        storage::envelope::{
+            envelope_params, is_envelope, open_with_password, EnvelopeKey, KdfParams,
+        },
+        storage::profile::keyring_service_for,
+        wallet::BetterAvailWallet,
     },
+    services::local_storage::profiles::get_active_profile,
 };
 
 use crate::models::storage::encryption::{Keys, Keys::PrivateKey as PKey, Keys::ViewKey as VKey};
@@ -19,10 +21,26 @@ use avail_common::{
     errors::{AvailError, AvailErrorType, AvailResult},
 };
 
+/// Keyring entry of the active profile
+fn entry(service: &str, key_type: &str) -> AvailResult<Entry> {
+    entry_for(&get_active_profile()?, service, key_type)
+}
+
+fn entry_for(profile: &str, service: &str, key_type: &str) -> AvailResult<Entry> {
+    Ok(Entry::new(
+        &keyring_service_for(profile, service),
+        key_type,
+    )?)
+}
+
+/// Format seed phrases were stored in before envelopes, kept to test their migration
+#[cfg(test)]
 fn encrypt_seed_phrase_with_password<N: Network>(
     password: &str,
     seed_phrase: &str,
 ) -> AvailResult<Ciphertext<N>> {
+    use snarkvm::prelude::{Literal, Plaintext, StringType};
+
     let pass_field = Field::<N>::new_domain_separator(password);
 
     let seed_phrase = Plaintext::<N>::Literal(
@@ -49,6 +67,112 @@ fn decrypt_seed_phrase_with_password<N: Network>(
     Ok(seed_phrase)
 }
 
+/// Keyring entries sealed with the wallet password
+const KEY_ENTRIES: [(&str, &str); 4] = [
+    ("com.avail.wallet.p", "avl-p"),
+    ("com.avail.wallet.v", "avl-v"),
+    ("com.avail.wallet.phrase", "avl-s"),
+    ("com.avail.wallet.passphrase", "avl-pp"),
+];
+
+/// Key derivation parameters of a profile's wallet, wallets from before envelopes have none yet
+fn stored_kdf_params(profile: &str) -> AvailResult<Option<KdfParams>> {
+    match entry_for(profile, "com.avail.wallet.kdf", "avl-kdf")?.get_password() {
+        Ok(params) => Ok(Some(KdfParams::decode(&params)?)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Gives a profile's wallet a new salt with the current cost
+fn reset_kdf_params(profile: &str) -> AvailResult<KdfParams> {
+    let params = KdfParams::generate();
+    entry_for(profile, "com.avail.wallet.kdf", "avl-kdf")?.set_password(&params.encode())?;
+
+    Ok(params)
+}
+
+/// Key the active wallet's envelopes are sealed with, derived from the password and the wallet's salt
+pub fn wallet_key(password: &str) -> AvailResult<EnvelopeKey> {
+    let profile = get_active_profile()?;
+
+    let params = match stored_kdf_params(&profile)? {
+        Some(params) if !params.is_outdated() => params,
+        _ => reset_kdf_params(&profile)?,
+    };
+
+    EnvelopeKey::derive(password, &params)
+}
+
+/// Decrypts a keyring value stored in either format
+fn open_value<N: Network>(password: &str, key_type: &str, value: &str) -> AvailResult<String> {
+    if is_envelope(value) {
+        return open_with_password(password, value);
+    }
+
+    // keys stored before envelopes are snarkVM ciphertexts keyed by the unsalted password
+    let ciphertext = Ciphertext::<N>::from_str(value)?;
+
+    match key_type {
+        "avl-p" => {
+            Ok(Encryptor::<N>::decrypt_private_key_with_secret(&ciphertext, password)?.to_string())
+        }
+        "avl-v" => {
+            Ok(Encryptor::<N>::decrypt_view_key_with_secret(&ciphertext, password)?.to_string())
+        }
+        _ => decrypt_seed_phrase_with_password::<N>(ciphertext, password),
+    }
+}
+
+/// Whether a keyring value is an envelope sealed with the wallet's current parameters
+fn is_current(value: &str) -> AvailResult<bool> {
+    if !is_envelope(value) {
+        return Ok(false);
+    }
+
+    match stored_kdf_params(&get_active_profile()?)? {
+        Some(params) => Ok(!params.is_outdated() && envelope_params(value)? == params),
+        None => Ok(false),
+    }
+}
+
+/// Seals the active wallet's keyring entries again with the current format and parameters.
+/// Every entry is replaced on its own, so an entry that wasn't migrated yet still opens in its old format.
+pub fn migrate_keys<N: Network>(password: &str) -> AvailResult<()> {
+    let key = wallet_key(password)?;
+
+    for (service, key_type) in KEY_ENTRIES {
+        let entry = entry(service, key_type)?;
+
+        let value = match entry.get_password() {
+            Ok(value) => value,
+            Err(keyring::Error::NoEntry) => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        if is_envelope(&value) && envelope_params(&value)? == *key.params() {
+            continue;
+        }
+
+        let plaintext = open_value::<N>(password, key_type, &value)?;
+        entry.set_password(&key.seal(&plaintext)?)?;
+    }
+
+    Ok(())
+}
+
+/// Decrypts a keyring value of the active wallet, once the password opened an outdated value every entry is migrated
+fn open_entry<N: Network>(password: &str, key_type: &str, value: &str) -> AvailResult<String> {
+    let plaintext = open_value::<N>(password, key_type, value)?;
+
+    if !is_current(value)? {
+        // the value was opened either way, a failed migration is retried on the next unlock
+        let _ = migrate_keys::<N>(password);
+    }
+
+    Ok(plaintext)
+}
+
 pub fn store<N: Network>(wallet: &BetterAvailWallet<N>, password: &str) -> AvailResult<String> {
     //encrypt keys with password
     if validate_secret_password(password).is_err() {
@@ -59,26 +183,25 @@ pub fn store<N: Network>(wallet: &BetterAvailWallet<N>, password: &str) -> Avail
         ));
     }
 
-    let ciphertext_p = encrypt_private_key_with_password::<N>(password, &wallet.private_key)?;
-
-    let ciphertext_v = encrypt_view_key_with_password::<N>(password, &wallet.view_key)?;
+    // every new wallet gets its own salt
+    let params = reset_kdf_params(&get_active_profile()?)?;
+    let key = EnvelopeKey::derive(password, &params)?;
 
     if let Some(mnemonic) = &wallet.mnemonic {
-        let ciphertext_seed = encrypt_seed_phrase_with_password::<N>(password, mnemonic.phrase())?;
         //seed-phrase storage
-        let s_entry = Entry::new("com.avail.wallet.phrase", "avl-s")?;
-        let encrypted_seed_phrase = ciphertext_seed.to_string();
+        let s_entry = entry("com.avail.wallet.phrase", "avl-s")?;
+        let encrypted_seed_phrase = key.seal(&mnemonic.to_string())?;
         s_entry.set_password(&encrypted_seed_phrase)?;
     }
 
     //private-key storage
-    let p_entry = Entry::new("com.avail.wallet.p", "avl-p")?;
-    let encrypted_private_key = ciphertext_p.to_string();
+    let p_entry = entry("com.avail.wallet.p", "avl-p")?;
+    let encrypted_private_key = key.seal(&wallet.private_key.to_string())?;
     p_entry.set_password(&encrypted_private_key)?;
 
     //view-key storage
-    let v_entry = Entry::new("com.avail.wallet.v", "avl-v")?;
-    let encrypted_viewing_key = ciphertext_v.to_string();
+    let v_entry = entry("com.avail.wallet.v", "avl-v")?;
+    let encrypted_viewing_key = key.seal(&wallet.view_key.to_string())?;
     v_entry.set_password(&encrypted_viewing_key)?;
 
     Ok("Key Stored".to_string())
@@ -86,8 +209,8 @@ pub fn store<N: Network>(wallet: &BetterAvailWallet<N>, password: &str) -> Avail
 
 pub fn read_key<N: Network>(password: &str, key_type: &str) -> AvailResult<Keys<N>> {
     let entry = match key_type {
-        "avl-p" => Entry::new("com.avail.wallet.p", key_type)?,
-        "avl-v" => Entry::new("com.avail.wallet.v", key_type)?,
+        "avl-p" => entry("com.avail.wallet.p", key_type)?,
+        "avl-v" => entry("com.avail.wallet.v", key_type)?,
         _ => {
             return Err(AvailError::new(
                 AvailErrorType::InvalidData,
@@ -97,20 +220,11 @@ pub fn read_key<N: Network>(password: &str, key_type: &str) -> AvailResult<Keys<
         }
     };
     let key = entry.get_password()?;
+    let key = open_entry::<N>(password, key_type, &key)?;
 
     match key_type {
-        "avl-p" => {
-            let pkey_ciphertext = Ciphertext::<N>::from_str(&key)?;
-            let pkey = Encryptor::<N>::decrypt_private_key_with_secret(&pkey_ciphertext, password)?;
-
-            Ok(Keys::PrivateKey(pkey))
-        }
-        "avl-v" => {
-            let vkey_ciphertext = Ciphertext::<N>::from_str(&key)?;
-            let vkey = Encryptor::<N>::decrypt_view_key_with_secret(&vkey_ciphertext, password)?;
-
-            Ok(Keys::ViewKey(vkey))
-        }
+        "avl-p" => Ok(Keys::PrivateKey(PrivateKey::<N>::from_str(&key)?)),
+        "avl-v" => Ok(Keys::ViewKey(ViewKey::<N>::from_str(&key)?)),
         _ => Err(AvailError::new(
             AvailErrorType::InvalidData,
             "Invalid label".to_string(),
@@ -120,43 +234,208 @@ pub fn read_key<N: Network>(password: &str, key_type: &str) -> AvailResult<Keys<
 }
 
 pub fn read_seed_phrase<N: Network>(password: &str) -> AvailResult<String> {
-    let entry = Entry::new("com.avail.wallet.phrase", "avl-s")?;
+    let entry = entry("com.avail.wallet.phrase", "avl-s")?;
     let seed_phrase = entry.get_password()?;
 
-    let seed_phrase_ciphertext = Ciphertext::<N>::from_str(&seed_phrase)?;
-    let seed_phrase = decrypt_seed_phrase_with_password::<N>(seed_phrase_ciphertext, password)?;
+    open_entry::<N>(password, "avl-s", &seed_phrase)
+}
 
-    Ok(seed_phrase)
+/// Stores the seed passphrase encrypted with the password, only done when the user opts in
+pub fn store_passphrase<N: Network>(password: &str, passphrase: &str) -> AvailResult<String> {
+    let encrypted_passphrase = wallet_key(password)?.seal(passphrase)?;
+
+    let pp_entry = entry("com.avail.wallet.passphrase", "avl-pp")?;
+    pp_entry.set_password(&encrypted_passphrase)?;
+
+    Ok("Passphrase Stored".to_string())
+}
+
+pub fn read_passphrase<N: Network>(password: &str) -> AvailResult<Option<String>> {
+    let passphrase = match entry("com.avail.wallet.passphrase", "avl-pp")?.get_password() {
+        Ok(passphrase) => passphrase,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(open_entry::<N>(password, "avl-pp", &passphrase)?))
+}
+
+/// Keyring values of the active wallet as they were before a password change
+pub struct KeysSnapshot {
+    profile: String,
+    values: Vec<(&'static str, &'static str, Option<String>)>,
+}
+
+impl KeysSnapshot {
+    fn take() -> AvailResult<Self> {
+        let profile = get_active_profile()?;
+        let mut values = vec![];
+
+        for (service, key_type) in KEY_ENTRIES
+            .into_iter()
+            .chain([("com.avail.wallet.kdf", "avl-kdf")])
+        {
+            let value = match entry_for(&profile, service, key_type)?.get_password() {
+                Ok(value) => Some(value),
+                Err(keyring::Error::NoEntry) => None,
+                Err(e) => return Err(e.into()),
+            };
+
+            values.push((service, key_type, value));
+        }
+
+        Ok(Self { profile, values })
+    }
+
+    /// Writes every value back, entries that didn't exist are removed again
+    pub fn restore(&self) -> AvailResult<()> {
+        for (service, key_type, value) in &self.values {
+            let entry = entry_for(&self.profile, service, key_type)?;
+
+            match value {
+                Some(value) => entry.set_password(value)?,
+                None => match entry.delete_password() {
+                    Ok(_) | Err(keyring::Error::NoEntry) => {}
+                    Err(e) => return Err(e.into()),
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Seals the active wallet's keyring entries again with `key`, a key derived from the new password.
+/// Every entry is opened with the old password before anything is written, and if a write fails
+/// the entries written so far are restored. The returned snapshot undoes the change if a later step fails.
+pub fn change_keys_password<N: Network>(
+    old_password: &str,
+    key: &EnvelopeKey,
+) -> AvailResult<KeysSnapshot> {
+    let snapshot = KeysSnapshot::take()?;
+
+    let mut sealed = vec![];
+    for (service, key_type, value) in &snapshot.values {
+        let value = match value {
+            Some(value) if *key_type != "avl-kdf" => value,
+            _ => continue,
+        };
+
+        let plaintext = open_value::<N>(old_password, key_type, value)?;
+        sealed.push((*service, *key_type, key.seal(&plaintext)?));
+    }
+
+    // a wallet always has a view key, without one there is nothing the old password was checked against
+    if !sealed.iter().any(|(_, key_type, _)| *key_type == "avl-v") {
+        return Err(AvailError::new(
+            AvailErrorType::NotFound,
+            "No view key in the keyring".to_string(),
+            "No wallet found".to_string(),
+        ));
+    }
+
+    sealed.push(("com.avail.wallet.kdf", "avl-kdf", key.params().encode()));
+
+    let write = || -> AvailResult<()> {
+        for (service, key_type, value) in &sealed {
+            entry_for(&snapshot.profile, service, key_type)?.set_password(value)?;
+        }
+
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        return match snapshot.restore() {
+            Ok(_) => Err(e),
+            Err(restore_error) => Err(AvailError::new(
+                AvailErrorType::Internal,
+                format!(
+                    "{}, restoring the keyring failed: {}",
+                    e.internal_msg, restore_error.internal_msg
+                ),
+                "Changing the password failed and the keyring could not be restored".to_string(),
+            )),
+        };
+    }
+
+    Ok(snapshot)
+}
+
+/// Removes the seed passphrase and the key derivation parameters, wallets without them have nothing to remove
+fn delete_optional_keys(profile: &str) -> AvailResult<()> {
+    for (service, key_type) in [
+        ("com.avail.wallet.passphrase", "avl-pp"),
+        ("com.avail.wallet.kdf", "avl-kdf"),
+    ] {
+        match entry_for(profile, service, key_type)?.delete_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
 }
 
 pub fn delete_key<N: Network>(password: &str) -> AvailResult<String> {
     // verify password is correct before deletion
     read_key::<N>(password, "avl-v")?;
 
-    let p_entry = Entry::new("com.avail.wallet.p", "avl-p")?;
+    let p_entry = entry("com.avail.wallet.p", "avl-p")?;
     p_entry.delete_password()?;
 
-    let v_entry = Entry::new("com.avail.wallet.v", "avl-v")?;
+    let v_entry = entry("com.avail.wallet.v", "avl-v")?;
     v_entry.delete_password()?;
 
-    let s_entry = Entry::new("com.avail.wallet.phrase", "avl-s")?;
+    let s_entry = entry("com.avail.wallet.phrase", "avl-s")?;
     s_entry.delete_password()?;
 
+    delete_optional_keys(&get_active_profile()?)?;
+
     Ok("Key Deleted".to_string())
 }
 
 pub fn delete_key_for_recovery<N: Network>(password: &str) -> AvailResult<String> {
-    let p_entry = Entry::new("com.avail.wallet.p", "avl-p")?;
+    let p_entry = entry("com.avail.wallet.p", "avl-p")?;
     p_entry.delete_password()?;
 
-    let v_entry = Entry::new("com.avail.wallet.v", "avl-v")?;
+    let v_entry = entry("com.avail.wallet.v", "avl-v")?;
     v_entry.delete_password()?;
 
-    let s_entry = Entry::new("com.avail.wallet.phrase", "avl-s")?;
+    let s_entry = entry("com.avail.wallet.phrase", "avl-s")?;
     s_entry.delete_password()?;
 
+    delete_optional_keys(&get_active_profile()?)?;
+
     Ok("Key Deleted".to_string())
 }
+/// Checks the password of another profile against its view key, profiles without a wallet have nothing to check
+pub fn verify_profile_password<N: Network>(profile: &str, password: &str) -> AvailResult<()> {
+    let key = match entry_for(profile, "com.avail.wallet.v", "avl-v")?.get_password() {
+        Ok(key) => key,
+        Err(keyring::Error::NoEntry) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    open_value::<N>(password, "avl-v", &key)?;
+
+    Ok(())
+}
+
+/// Removes the keyring entries of a profile, entries that were never stored are skipped
+pub fn delete_profile_keys(profile: &str) -> AvailResult<()> {
+    for (service, key_type) in KEY_ENTRIES
+        .into_iter()
+        .chain([("com.avail.wallet.kdf", "avl-kdf")])
+    {
+        match entry_for(profile, service, key_type)?.delete_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod windows_linux_key_management_tests {
     use super::*;
@@ -241,4 +520,81 @@ mod windows_linux_key_management_tests {
 
         assert_eq!(seed_phrase, decrypted_seed_phrase);
     }
+
+    #[test]
+    fn test_open_old_and_new_formats() {
+        let mut rng = thread_rng();
+        let p_key = PrivateKey::<TestnetV0>::new(&mut rng).unwrap();
+        let v_key = ViewKey::<TestnetV0>::try_from(&p_key).unwrap();
+        let seed_phrase =
+            "light soon prepare wire blade charge female stage ridge happy pony chief";
+
+        let old_p =
+            Encryptor::<TestnetV0>::encrypt_private_key_with_secret(&p_key, STRONG_PASSWORD)
+                .unwrap()
+                .to_string();
+        let old_v = Encryptor::<TestnetV0>::encrypt_view_key_with_secret(&v_key, STRONG_PASSWORD)
+            .unwrap()
+            .to_string();
+        let old_s = encrypt_seed_phrase_with_password::<TestnetV0>(STRONG_PASSWORD, seed_phrase)
+            .unwrap()
+            .to_string();
+
+        let key = EnvelopeKey::derive(STRONG_PASSWORD, &KdfParams::new(10, 8, 1)).unwrap();
+        let new_p = key.seal(&p_key.to_string()).unwrap();
+        let new_v = key.seal(&v_key.to_string()).unwrap();
+        let new_s = key.seal(seed_phrase).unwrap();
+
+        for (key_type, old, new, plaintext) in [
+            ("avl-p", old_p, new_p, p_key.to_string()),
+            ("avl-v", old_v, new_v, v_key.to_string()),
+            ("avl-s", old_s, new_s, seed_phrase.to_string()),
+        ] {
+            assert!(!is_envelope(&old));
+            assert!(is_envelope(&new));
+
+            assert_eq!(
+                open_value::<TestnetV0>(STRONG_PASSWORD, key_type, &old).unwrap(),
+                plaintext
+            );
+            assert_eq!(
+                open_value::<TestnetV0>(STRONG_PASSWORD, key_type, &new).unwrap(),
+                plaintext
+            );
+
+            assert!(open_value::<TestnetV0>(WEAK_PASSWORD, key_type, &new).is_err());
+        }
+    }
+
+    #[test]
+    fn test_change_keys_password() {
+        let new_password = "Rotated-Passw0rd!";
+
+        let mut rng = thread_rng();
+        let p_key = PrivateKey::<TestnetV0>::new(&mut rng).unwrap();
+        let avail_wallet = BetterAvailWallet::<TestnetV0>::try_from(p_key).unwrap();
+
+        store::<TestnetV0>(&avail_wallet, STRONG_PASSWORD).unwrap();
+
+        let key = EnvelopeKey::derive(new_password, &KdfParams::new(10, 8, 1)).unwrap();
+
+        // a wrong password changes nothing
+        assert!(change_keys_password::<TestnetV0>(WEAK_PASSWORD, &key).is_err());
+        assert!(read_key::<TestnetV0>(STRONG_PASSWORD, "avl-p").is_ok());
+
+        let snapshot = change_keys_password::<TestnetV0>(STRONG_PASSWORD, &key).unwrap();
+
+        let read_p_key = read_key::<TestnetV0>(new_password, "avl-p")
+            .unwrap()
+            .is_private_key()
+            .unwrap();
+        assert_eq!(read_p_key, p_key);
+        assert!(read_key::<TestnetV0>(STRONG_PASSWORD, "avl-v").is_err());
+
+        // the snapshot brings the old password back
+        snapshot.restore().unwrap();
+        assert!(read_key::<TestnetV0>(STRONG_PASSWORD, "avl-v").is_ok());
+
+        delete_key::<TestnetV0>(STRONG_PASSWORD).unwrap();
+    }
 }
@@ -7,6 +7,14 @@ use security_framework::passwords::{self};
 
 use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
 
+use crate::models::storage::profile::keyring_service_for;
+use crate::services::local_storage::profiles::get_active_profile;
+
+/// Keychain service of the active profile, the default profile keeps the services of older installations
+fn profile_service(service: &str) -> AvailResult<String> {
+    Ok(keyring_service_for(&get_active_profile()?, service))
+}
+
 ///Accepts a private key and attempts to store it on the user's iOS device.
 ///Using apple's security framework we set access controls to protect the key entry.
 ///If biometrics are available we only add the SecAccessControl to the query.
@@ -30,11 +38,9 @@ pub fn store_key_local(
         false => "avail-user-view",
     };
 
-    let mut options = passwords_options::PasswordOptions::new_generic_password(
-        "com.avail.wallet",
-        account,
-        label,
-    );
+    let service = profile_service("com.avail.wallet")?;
+    let mut options =
+        passwords_options::PasswordOptions::new_generic_password(&service, account, label);
 
     let auth_control;
 
@@ -94,7 +100,13 @@ pub fn search<N: Network>(password: Option<&str>, label: &str) -> AvailResult<Ke
         })
     };
 
-    let key = get_generic_password("com.avail", &account, auth, context, label)?;
+    let key = get_generic_password(
+        &profile_service("com.avail")?,
+        &account,
+        auth,
+        context,
+        label,
+    )?;
 
     match label {
         "avl-p" => {
@@ -162,7 +174,9 @@ pub fn delete_ios(password: Option<&str>) -> AvailResult<String> {
 /* --Testing-- */
 #[tauri::command(rename_all = "snake_case")]
 pub async fn delete_ios(_password: Option<&str>) -> AvailResult<String> {
-    match passwords::delete_generic_password("com.avail", "avail-user-view", "avl-v") {
+    let service = profile_service("com.avail")?;
+
+    match passwords::delete_generic_password(&service, "avail-user-view", "avl-v") {
         Ok(_) => (),
         Err(e) => {
             return Err(AvailError::new(
@@ -173,7 +187,7 @@ pub async fn delete_ios(_password: Option<&str>) -> AvailResult<String> {
         }
     };
 
-    match passwords::delete_generic_password("com.avail", "avail-user-private", "avl-p") {
+    match passwords::delete_generic_password(&service, "avail-user-private", "avl-p") {
         Ok(_) => (),
         Err(e) => {
             return Err(AvailError::new(
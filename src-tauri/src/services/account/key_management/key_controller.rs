@@ -9,7 +9,10 @@ use super::ios::{delete_ios, search, store_keys_local};
 
 use snarkvm::prelude::{Identifier, Network, PrivateKey, ViewKey};
 
-use super::desktop::{delete_key, delete_key_for_recovery, read_key, read_seed_phrase, store};
+use super::desktop::{
+    delete_key, delete_key_for_recovery, read_key, read_passphrase, read_seed_phrase, store,
+    store_passphrase,
+};
 use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
 
 /// This trait is used as a standard interface for the key management service.
@@ -24,6 +27,9 @@ pub trait KeyController<N: Network> {
     ) -> AvailResult<String>;
     fn read_key(&self, password: Option<&str>, key_type: &str) -> AvailResult<Keys<N>>;
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String>;
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String>;
+    /// The seed passphrase is only stored when the user opted in
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>>;
 }
 
 pub struct AndroidKeyController;
@@ -55,6 +61,14 @@ impl<N: Network> KeyController<N> for AndroidKeyController {
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String> {
         read_seed_phrase::<N>(password)
     }
+
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String> {
+        store_passphrase::<N>(password, passphrase)
+    }
+
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>> {
+        read_passphrase::<N>(password)
+    }
 }
 
 pub struct iOSKeyController;
@@ -87,6 +101,14 @@ impl<N: Network> KeyController<N> for iOSKeyController {
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String> {
         read_seed_phrase(password, ext)
     }
+
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String> {
+        store_passphrase::<N>(password, passphrase)
+    }
+
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>> {
+        read_passphrase::<N>(password)
+    }
 }
 
 pub struct macKeyController;
@@ -144,6 +166,14 @@ impl<N: Network> KeyController<N> for macKeyController {
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String> {
         read_seed_phrase::<N>(password)
     }
+
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String> {
+        store_passphrase::<N>(password, passphrase)
+    }
+
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>> {
+        read_passphrase::<N>(password)
+    }
 }
 
 pub struct linuxKeyController;
@@ -201,6 +231,14 @@ impl<N: Network> KeyController<N> for linuxKeyController {
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String> {
         read_seed_phrase::<N>(password)
     }
+
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String> {
+        store_passphrase::<N>(password, passphrase)
+    }
+
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>> {
+        read_passphrase::<N>(password)
+    }
 }
 
 pub struct windowsKeyController;
@@ -259,4 +297,12 @@ impl<N: Network> KeyController<N> for windowsKeyController {
     fn read_phrase(&self, password: &str, ext: Identifier<N>) -> AvailResult<String> {
         read_seed_phrase::<N>(password)
     }
+
+    fn store_passphrase(&self, password: &str, passphrase: &str) -> AvailResult<String> {
+        store_passphrase::<N>(password, passphrase)
+    }
+
+    fn read_passphrase(&self, password: &str) -> AvailResult<Option<String>> {
+        read_passphrase::<N>(password)
+    }
 }
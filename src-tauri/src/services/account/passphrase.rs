@@ -0,0 +1,110 @@
+use snarkvm::prelude::{Network, TestnetV0};
+use std::str::FromStr;
+
+use crate::services::account::derivation::derive_wallet_with;
+use crate::services::account::key_management::key_controller::{
+    linuxKeyController, macKeyController, windowsKeyController, KeyController,
+};
+use crate::services::local_storage::{
+    accounts::get_account,
+    persistent_storage::{
+        get_address_string, get_network, get_passphrase_protected, update_passphrase_protected,
+    },
+    session::passphrase::PASSPHRASE,
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+/// Keeps the passphrase of a newly created or recovered wallet for the session,
+/// and in the keyring only if the user opted in with `store`
+pub fn save_passphrase<N: Network>(
+    password: &str,
+    passphrase: Option<&str>,
+    store: bool,
+) -> AvailResult<()> {
+    let passphrase = match passphrase {
+        Some(passphrase) if !passphrase.is_empty() => passphrase,
+        _ => return update_passphrase_protected(false),
+    };
+
+    let key_manager = {
+        #[cfg(target_os = "macos")]
+        {
+            macKeyController
+        }
+        #[cfg(target_os = "windows")]
+        {
+            windowsKeyController
+        }
+        #[cfg(target_os = "linux")]
+        {
+            linuxKeyController
+        }
+    };
+
+    update_passphrase_protected(true)?;
+
+    if store {
+        KeyController::<N>::store_passphrase(&key_manager, password, passphrase)?;
+    }
+
+    PASSPHRASE.set_passphrase_session(passphrase)
+}
+
+/// Passphrase the seed phrase is derived with, empty for seed phrases without one
+pub fn seed_passphrase<N: Network>(password: &str) -> AvailResult<String> {
+    if !get_passphrase_protected()? {
+        return Ok(String::new());
+    }
+
+    let key_manager = {
+        #[cfg(target_os = "macos")]
+        {
+            macKeyController
+        }
+        #[cfg(target_os = "windows")]
+        {
+            windowsKeyController
+        }
+        #[cfg(target_os = "linux")]
+        {
+            linuxKeyController
+        }
+    };
+
+    match KeyController::<N>::read_passphrase(&key_manager, password)? {
+        Some(passphrase) => Ok(passphrase),
+        None => PASSPHRASE.get_instance(),
+    }
+}
+
+fn unlock_passphrase_raw<N: Network>(passphrase: &str, password: &str) -> AvailResult<()> {
+    // the first account is the one the wallet was created with
+    let address = match get_account(0)? {
+        Some(row) => row.account.address,
+        None => get_address_string()?,
+    };
+
+    let wallet = derive_wallet_with::<N>(0, password, passphrase)?;
+    if wallet.get_address() != address {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "Passphrase does not derive the wallet address".to_string(),
+            "The passphrase does not match this wallet".to_string(),
+        ));
+    }
+
+    PASSPHRASE.set_passphrase_session(passphrase)
+}
+
+/// Unlocks the seed passphrase for the session, needed to derive accounts when the passphrase isn't stored
+#[tauri::command(rename_all = "snake_case")]
+pub fn unlock_passphrase(passphrase: String, password: String) -> AvailResult<()> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => unlock_passphrase_raw::<TestnetV0>(&passphrase, &password),
+        _ => unlock_passphrase_raw::<TestnetV0>(&passphrase, &password),
+    }
+}
@@ -0,0 +1,102 @@
+use snarkvm::prelude::{Network, TestnetV0};
+use std::str::FromStr;
+
+use crate::helpers::validation::validate_secret_password;
+use crate::models::storage::envelope::{EnvelopeKey, KdfParams};
+use crate::services::account::key_management::desktop::change_keys_password;
+use crate::services::local_storage::{
+    accounts::{list_accounts, store_account, AccountRow},
+    persistent_storage::get_network,
+    session::{password::PASS, policy::POLICY_PASS},
+    utils::decrypt_view_key_with_password,
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+fn store_account_row(row: &AccountRow, view_key: &str) -> AvailResult<()> {
+    store_account(
+        row.account.index,
+        &row.account.address,
+        &row.account.name,
+        view_key,
+        row.last_sync,
+    )
+}
+
+/// Encrypts the keys with a new password, the keyring and the derived accounts either all change or none do
+fn change_password_for<N: Network>(old_password: &str, new_password: &str) -> AvailResult<()> {
+    if validate_secret_password(new_password).is_err() {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "Invalid new password".to_string(),
+            "The new password must be at least 12 characters with a number, a capital letter and a special character".to_string(),
+        ));
+    }
+
+    if old_password == new_password {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "New password is the old password".to_string(),
+            "The new password must be different from the current one".to_string(),
+        ));
+    }
+
+    // the view keys of derived accounts are opened before anything is written
+    let accounts = list_accounts()?
+        .into_iter()
+        .map(|row| {
+            let view_key = decrypt_view_key_with_password::<N>(old_password, &row.view_key)?;
+            Ok((row, view_key))
+        })
+        .collect::<AvailResult<Vec<_>>>()?;
+
+    // a new password gets a new salt
+    let key = EnvelopeKey::derive(new_password, &KdfParams::generate())?;
+
+    let sealed_accounts = accounts
+        .iter()
+        .map(|(row, view_key)| Ok((row, key.seal(&view_key.to_string())?)))
+        .collect::<AvailResult<Vec<_>>>()?;
+
+    let snapshot = change_keys_password::<N>(old_password, &key)?;
+
+    for (i, (row, view_key)) in sealed_accounts.iter().enumerate() {
+        if let Err(e) = store_account_row(row, view_key) {
+            let restored = sealed_accounts[..i]
+                .iter()
+                .try_for_each(|(row, _)| store_account_row(row, &row.view_key))
+                .and_then(|_| snapshot.restore());
+
+            return match restored {
+                Ok(_) => Err(e),
+                Err(restore_error) => Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    format!(
+                        "{}, restoring the previous keys failed: {}",
+                        e.internal_msg, restore_error.internal_msg
+                    ),
+                    "Changing the password failed and the previous keys could not be restored"
+                        .to_string(),
+                )),
+            };
+        }
+    }
+
+    POLICY_PASS.update_password(new_password)?;
+    PASS.set_pass_session(new_password)
+}
+
+/// Changes the wallet password after checking the current one.
+/// The private key, view key, seed phrase and stored passphrase are encrypted again in one step.
+#[tauri::command(rename_all = "snake_case")]
+pub fn change_password(old_password: String, new_password: String) -> AvailResult<()> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            change_password_for::<TestnetV0>(&old_password, &new_password)
+        }
+        _ => change_password_for::<TestnetV0>(&old_password, &new_password),
+    }
+}
@@ -3,10 +3,13 @@ use snarkvm::prelude::{TestnetV0, ToBytes};
 
 use crate::{
     api::backup_recovery::{get_backup_timestamp, get_sync_height},
-    models::storage::languages::Languages,
+    models::storage::languages::{Languages, MnemonicLanguage},
     services::local_storage::{
         encrypted_data::{initialize_encrypted_data_table, process_private_tokens},
-        persistent_storage::{initial_user_preferences, update_last_backup_sync, update_last_sync},
+        persistent_storage::{
+            initial_user_preferences, update_last_backup_sync, update_last_sync,
+            update_mnemonic_language,
+        },
         session::view::VIEWSESSION,
     },
 };
@@ -18,6 +21,7 @@ use crate::models::wallet::BetterAvailWallet;
 use crate::services::account::key_management::key_controller::{
     linuxKeyController, macKeyController, windowsKeyController, KeyController,
 };
+use crate::services::account::passphrase::save_passphrase;
 use crate::services::authentication::session::get_session_after_creation;
 use crate::services::local_storage::{
     encrypted_data::get_and_store_all_data, tokens::init_tokens_table,
@@ -26,15 +30,25 @@ use avail_common::models::user::User;
 
 #[tauri::command(rename_all = "snake_case")]
 /// This function provides the tauri bindings to recover an avail wallet from a seed phrase.
+/// The seed phrase language is detected from its words when `mnemonic_language` is not given.
 pub async fn recover_wallet_from_seed_phrase(
     seed_phrase: &str,
     password: &str,
     access_type: bool,
     language: Languages,
+    passphrase: Option<String>,
+    store_passphrase: bool,
+    mnemonic_language: Option<MnemonicLanguage>,
 ) -> AvailResult<()> {
+    let mnemonic_language = match mnemonic_language {
+        Some(mnemonic_language) => mnemonic_language,
+        None => MnemonicLanguage::detect(seed_phrase)?,
+    };
+
     let avail_wallet = BetterAvailWallet::<TestnetV0>::from_seed_phrase(
         seed_phrase,
-        Languages::to_bip39_language(&language),
+        mnemonic_language.to_bip39_language(),
+        passphrase.as_deref().unwrap_or(""),
     )?;
 
     let key_manager = {
@@ -83,6 +97,9 @@ pub async fn recover_wallet_from_seed_phrase(
         avail_wallet.get_address(),
         language,
     )?;
+    update_mnemonic_language(&mnemonic_language)?;
+
+    save_passphrase::<TestnetV0>(password, passphrase.as_deref(), store_passphrase)?;
 
     init_tokens_table()?;
 
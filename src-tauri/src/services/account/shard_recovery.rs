@@ -1 +1,210 @@
+use bip39::Mnemonic;
+use sha2::{Digest, Sha256};
+use sharks::{Share, Sharks};
 
+use crate::models::backup_share::{validate_share_set, BackupShare, BackupShareInfo};
+use crate::models::storage::languages::{seed_phrase_error, Languages, MnemonicLanguage};
+use crate::services::account::phrase_recovery::recover_wallet_from_seed_phrase;
+use crate::services::local_storage::{
+    persistent_storage::get_mnemonic_language, utils::get_seed_phrase,
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Shares are numbered with a single byte and transcribed by hand, more than this isn't practical
+const MAX_SHARES: u8 = 16;
+const ENTROPY_CHECK_SIZE: usize = 4;
+
+fn entropy_check(entropy: &[u8]) -> Vec<u8> {
+    Sha256::digest(entropy)[..ENTROPY_CHECK_SIZE].to_vec()
+}
+
+fn shamir_error(e: impl std::fmt::Debug, external: &str) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Internal,
+        format!("{:?}", e),
+        external.to_string(),
+    )
+}
+
+/// Splits the entropy of a seed phrase into `share_count` shares of which any `threshold` recover it
+pub fn split_seed_phrase(
+    seed_phrase: &str,
+    language: &MnemonicLanguage,
+    threshold: u8,
+    share_count: u8,
+) -> AvailResult<Vec<String>> {
+    if threshold < 2 || threshold > share_count || share_count > MAX_SHARES {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Invalid backup of {} of {} shares", threshold, share_count),
+            format!(
+                "A backup needs at least 2 shares to recover it and at most {} shares",
+                MAX_SHARES
+            ),
+        ));
+    }
+
+    let mnemonic =
+        Mnemonic::parse_in(language.to_bip39_language(), seed_phrase).map_err(seed_phrase_error)?;
+
+    // the check tells a wrong combination apart from the seed phrase after recovery
+    let entropy = mnemonic.to_entropy();
+    let mut secret = entropy.clone();
+    secret.extend(entropy_check(&entropy));
+
+    let set_id = rand::random::<[u8; 4]>();
+
+    Ok(Sharks(threshold)
+        .dealer(&secret)
+        .take(share_count as usize)
+        .enumerate()
+        .map(|(i, share)| {
+            BackupShare {
+                set_id,
+                threshold,
+                share_count,
+                index: i as u8 + 1,
+                language: language.to_string_short(),
+                data: Vec::from(&share),
+            }
+            .encode()
+        })
+        .collect())
+}
+
+/// Combines the shares of a backup back into the seed phrase and its language
+pub fn combine_backup_shares(shares: &[String]) -> AvailResult<(String, MnemonicLanguage)> {
+    let shares = shares
+        .iter()
+        .filter(|share| !share.trim().is_empty())
+        .map(|share| BackupShare::decode(share))
+        .collect::<AvailResult<Vec<BackupShare>>>()?;
+
+    validate_share_set(&shares)?;
+
+    let first = &shares[0];
+    let language = match MnemonicLanguage::from_string_short(&first.language) {
+        Some(language) => language,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Unknown seed phrase language {}", first.language),
+                "The backup uses a seed phrase language this wallet doesn't support".to_string(),
+            ))
+        }
+    };
+
+    let shamir_shares = shares
+        .iter()
+        .take(first.threshold as usize)
+        .map(|share| Share::try_from(share.data.as_slice()))
+        .collect::<Result<Vec<Share>, _>>()
+        .map_err(|e| shamir_error(e, "The shares are malformed"))?;
+
+    let secret = Sharks(first.threshold)
+        .recover(&shamir_shares)
+        .map_err(|e| shamir_error(e, "The shares could not be combined"))?;
+
+    let mismatch = || {
+        AvailError::new(
+            AvailErrorType::Validation,
+            "Combined shares fail the entropy check".to_string(),
+            "The shares don't combine into a seed phrase, one of them is from another backup or was changed".to_string(),
+        )
+    };
+
+    if secret.len() <= ENTROPY_CHECK_SIZE {
+        return Err(mismatch());
+    }
+
+    let (entropy, check) = secret.split_at(secret.len() - ENTROPY_CHECK_SIZE);
+    if entropy_check(entropy) != check {
+        return Err(mismatch());
+    }
+
+    let mnemonic =
+        Mnemonic::from_entropy_in(language.to_bip39_language(), entropy).map_err(|_| mismatch())?;
+
+    Ok((mnemonic.to_string(), language))
+}
+
+/// Splits the wallet's seed phrase into shares to be written down and kept in different places
+#[tauri::command(rename_all = "snake_case")]
+pub fn create_seed_backup(
+    password: String,
+    threshold: u8,
+    share_count: u8,
+) -> AvailResult<Vec<String>> {
+    let seed_phrase = get_seed_phrase(Some(password))?;
+
+    split_seed_phrase(
+        &seed_phrase,
+        &get_mnemonic_language()?,
+        threshold,
+        share_count,
+    )
+}
+
+/// Checks a single share as it is entered, before the rest of the backup is
+#[tauri::command(rename_all = "snake_case")]
+pub fn inspect_backup_share(share: String) -> AvailResult<BackupShareInfo> {
+    Ok(BackupShare::decode(&share)?.info())
+}
+
+/// Recovers a wallet from enough shares of a seed phrase backup.
+/// The passphrase is never part of the shares, a passphrase protected wallet needs it as well.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn recover_wallet_from_shares(
+    shares: Vec<String>,
+    password: String,
+    access_type: bool,
+    language: Languages,
+    passphrase: Option<String>,
+    store_passphrase: bool,
+) -> AvailResult<()> {
+    let (seed_phrase, mnemonic_language) = combine_backup_shares(&shares)?;
+
+    recover_wallet_from_seed_phrase(
+        &seed_phrase,
+        &password,
+        access_type,
+        language,
+        passphrase,
+        store_passphrase,
+        Some(mnemonic_language),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod shard_recovery_tests {
+    use super::*;
+
+    const PHRASE: &str = "light soon prepare wire blade charge female stage ridge happy pony chief";
+
+    #[test]
+    fn test_split_and_combine() {
+        let shares = split_seed_phrase(PHRASE, &MnemonicLanguage::English, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        // a share holds its x coordinate and one byte per byte of entropy and check
+        let share = BackupShare::decode(&shares[1]).unwrap();
+        assert_eq!(share.data.len(), 1 + 16 + ENTROPY_CHECK_SIZE);
+        assert_eq!(share.data[0], share.index);
+
+        // any three shares recover the seed phrase
+        let some = vec![shares[4].clone(), shares[0].clone(), shares[2].clone()];
+        let (phrase, _) = combine_backup_shares(&some).unwrap();
+        assert_eq!(phrase, PHRASE);
+
+        assert!(combine_backup_shares(&shares[..2]).is_err());
+
+        let other = split_seed_phrase(PHRASE, &MnemonicLanguage::English, 3, 5).unwrap();
+        let mixed = vec![shares[0].clone(), shares[1].clone(), other[2].clone()];
+        assert!(combine_backup_shares(&mixed).is_err());
+
+        assert!(split_seed_phrase(PHRASE, &MnemonicLanguage::English, 1, 5).is_err());
+        assert!(split_seed_phrase(PHRASE, &MnemonicLanguage::English, 4, 3).is_err());
+    }
+}
@@ -0,0 +1,813 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use snarkvm::prelude::{
+    Address, Authorization, Field, Network, Plaintext, PrivateKey, ProgramID, Record, Signature,
+    TestnetV0, Value,
+};
+
+use crate::services::local_storage::{
+    persistent_storage::{get_address, get_network, get_signer_endpoint, update_signer_endpoint},
+    utils::{get_private_key, sign_message},
+};
+use crate::services::record_handling::prover::{authorize_execution, authorize_execution_fee};
+
+use avail_common::{
+    converters::messages::{field_to_fields, utf8_string_to_bits},
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+/// Standard interface for anything that can produce signatures on behalf of the wallet owner.
+/// Transfers, wallet connect requests and server authentication go through this trait instead of reading the keyring directly.
+pub trait Signer<N: Network>: Send + Sync {
+    /// Address of the account the signer holds the key for.
+    fn address(&self) -> AvailResult<Address<N>>;
+
+    /// Checks that the signer can sign right now, before anything is stored or marked spent.
+    fn unlock(&self) -> AvailResult<()> {
+        self.address().map(|_| ())
+    }
+
+    /// Signs a message that has already been converted to fields.
+    fn sign_fields(&self, message: &[Field<N>]) -> AvailResult<Signature<N>>;
+
+    /// Signs the authorization of a program call, proving happens afterwards without the key.
+    fn authorize(
+        &self,
+        program_id: &str,
+        function_name: &str,
+        inputs: Vec<Value<N>>,
+    ) -> AvailResult<Authorization<N>>;
+
+    /// Signs the fee of the proved execution `execution_id`, paying `base_fee` plus `priority_fee`.
+    /// The base fee depends on the proved execution, so the fee is signed after proving.
+    fn authorize_fee(
+        &self,
+        execution_id: Field<N>,
+        base_fee: u64,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+    ) -> AvailResult<Authorization<N>>;
+
+    /// Returns the private key for flows that can't be authorized yet, i.e. deployments.
+    /// Signers that keep the key elsewhere return an error.
+    fn private_key(&self) -> AvailResult<PrivateKey<N>>;
+
+    /// Hashes a utf8 message to fields the same way as [`sign_message`] and signs it.
+    fn sign_message(&self, message: &str) -> AvailResult<(Signature<N>, Field<N>)> {
+        let msg = utf8_string_to_bits(message);
+        let msg_field = N::hash_bhp512(&msg)?;
+        let msg = field_to_fields(&msg_field)?;
+
+        let signature = self.sign_fields(&msg)?;
+
+        Ok((signature, msg_field))
+    }
+}
+
+/// Signer backed by the keys stored in the local keyring.
+pub struct KeyringSigner {
+    password: Option<String>,
+}
+
+impl KeyringSigner {
+    pub fn new(password: Option<String>) -> Self {
+        Self { password }
+    }
+}
+
+impl<N: Network> Signer<N> for KeyringSigner {
+    fn address(&self) -> AvailResult<Address<N>> {
+        get_address::<N>()
+    }
+
+    fn unlock(&self) -> AvailResult<()> {
+        // fails with Unauthorized without a password or an open session
+        get_private_key::<N>(self.password.clone()).map(|_| ())
+    }
+
+    fn sign_fields(&self, message: &[Field<N>]) -> AvailResult<Signature<N>> {
+        let private_key = get_private_key::<N>(self.password.clone())?;
+        let rng = &mut rand::thread_rng();
+
+        Ok(private_key.sign(message, rng)?)
+    }
+
+    fn authorize(
+        &self,
+        program_id: &str,
+        function_name: &str,
+        inputs: Vec<Value<N>>,
+    ) -> AvailResult<Authorization<N>> {
+        let private_key = get_private_key::<N>(self.password.clone())?;
+
+        authorize_execution::<N>(&private_key, program_id, function_name, inputs)
+    }
+
+    fn authorize_fee(
+        &self,
+        execution_id: Field<N>,
+        base_fee: u64,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+    ) -> AvailResult<Authorization<N>> {
+        let private_key = get_private_key::<N>(self.password.clone())?;
+
+        authorize_execution_fee::<N>(
+            &private_key,
+            execution_id,
+            base_fee,
+            priority_fee,
+            fee_record,
+        )
+    }
+
+    fn private_key(&self) -> AvailResult<PrivateKey<N>> {
+        get_private_key::<N>(self.password.clone())
+    }
+
+    fn sign_message(&self, message: &str) -> AvailResult<(Signature<N>, Field<N>)> {
+        // keeps the view session refresh done by the keyring signing path
+        sign_message::<N>(message, self.password.clone())
+    }
+}
+
+/// Signer holding a private key in memory, used as a stand-in for the keyring in tests and by remote signer services.
+pub struct LocalSigner<N: Network> {
+    private_key: PrivateKey<N>,
+}
+
+impl<N: Network> LocalSigner<N> {
+    pub fn new(private_key: PrivateKey<N>) -> Self {
+        Self { private_key }
+    }
+}
+
+impl<N: Network> Signer<N> for LocalSigner<N> {
+    fn address(&self) -> AvailResult<Address<N>> {
+        Ok(Address::<N>::try_from(&self.private_key)?)
+    }
+
+    fn sign_fields(&self, message: &[Field<N>]) -> AvailResult<Signature<N>> {
+        let rng = &mut rand::thread_rng();
+
+        Ok(self.private_key.sign(message, rng)?)
+    }
+
+    fn authorize(
+        &self,
+        program_id: &str,
+        function_name: &str,
+        inputs: Vec<Value<N>>,
+    ) -> AvailResult<Authorization<N>> {
+        authorize_execution::<N>(&self.private_key, program_id, function_name, inputs)
+    }
+
+    fn authorize_fee(
+        &self,
+        execution_id: Field<N>,
+        base_fee: u64,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+    ) -> AvailResult<Authorization<N>> {
+        authorize_execution_fee::<N>(
+            &self.private_key,
+            execution_id,
+            base_fee,
+            priority_fee,
+            fee_record,
+        )
+    }
+
+    fn private_key(&self) -> AvailResult<PrivateKey<N>> {
+        Ok(self.private_key)
+    }
+}
+
+/* --Remote Signer Protocol-- */
+
+/// A single request sent to a remote signer, serialized as one line of JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteSignerRequest {
+    pub id: u64,
+    pub network: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// The reply of a remote signer to a [`RemoteSignerRequest`] with the same id, serialized as one line of JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteSignerResponse {
+    pub id: u64,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+impl RemoteSignerResponse {
+    pub fn ok(id: u64, result: serde_json::Value) -> Self {
+        Self {
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn err(id: u64, error: String) -> Self {
+        Self {
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Signer that forwards signing to a separate signing service over a TCP socket.
+///
+/// Every call opens a connection to `endpoint` (`host:port`), writes one [`RemoteSignerRequest`] as a
+/// newline terminated JSON line and reads one [`RemoteSignerResponse`] line back. Supported methods:
+///
+/// - `address`, no params, result `{ "address": "aleo1..." }`
+/// - `sign`, params `{ "message_fields": ["1field", ...] }`, result `{ "signature": "sign1..." }`
+/// - `authorize`, params `{ "program_id": "credits.aleo", "function_name": "transfer_public", "inputs": ["aleo1...", "1u64"] }`,
+///   result `{ "authorization": "{...}" }`
+/// - `authorize_fee`, params `{ "execution_id": "1field", "base_fee": 3000, "priority_fee": 1000, "fee_record": null }`,
+///   result `{ "fee_authorization": "{...}" }`
+///
+/// The private key never leaves the signing service, so [`Signer::private_key`] always fails.
+pub struct RemoteSigner {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn call<N: Network>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> AvailResult<serde_json::Value> {
+        let request = RemoteSignerRequest {
+            id: rand::random::<u64>(),
+            network: N::NAME.to_string(),
+            method: method.to_string(),
+            params,
+        };
+
+        let stream = match TcpStream::connect(&self.endpoint) {
+            Ok(stream) => stream,
+            Err(e) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Network,
+                    format!("Error connecting to remote signer {}: {}", self.endpoint, e),
+                    "Could not reach the remote signer.".to_string(),
+                ))
+            }
+        };
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+
+        let mut writer = stream.try_clone()?;
+        writer.write_all(line.as_bytes())?;
+        writer.flush()?;
+
+        let mut response_line = String::new();
+        BufReader::new(stream).read_line(&mut response_line)?;
+
+        let response: RemoteSignerResponse = serde_json::from_str(response_line.trim())?;
+
+        if response.id != request.id {
+            return Err(AvailError::new(
+                AvailErrorType::External,
+                format!(
+                    "Remote signer answered request {} with id {}",
+                    request.id, response.id
+                ),
+                "Invalid response from the remote signer.".to_string(),
+            ));
+        }
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(AvailError::new(
+                AvailErrorType::External,
+                format!("Remote signer error: {}", error),
+                format!("Remote signer error: {}", error),
+            )),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(AvailError::new(
+                AvailErrorType::External,
+                "Remote signer returned an empty response".to_string(),
+                "Invalid response from the remote signer.".to_string(),
+            )),
+        }
+    }
+
+    fn result_field(result: &serde_json::Value, key: &str) -> AvailResult<String> {
+        match result.get(key).and_then(|value| value.as_str()) {
+            Some(value) => Ok(value.to_string()),
+            None => Err(AvailError::new(
+                AvailErrorType::External,
+                format!("Remote signer response is missing '{}'", key),
+                "Invalid response from the remote signer.".to_string(),
+            )),
+        }
+    }
+}
+
+/// Checks that an authorization signed elsewhere is the call that was asked for, signed by the wallet's account
+fn check_authorization<N: Network>(
+    authorization: &Authorization<N>,
+    signer: &Address<N>,
+    program_id: &str,
+    function_names: &[&str],
+    inputs: Option<&[Value<N>]>,
+) -> AvailResult<()> {
+    let request = authorization.peek_next()?;
+
+    let matches = request.signer() == signer
+        && request.program_id() == &ProgramID::<N>::from_str(program_id)?
+        && function_names.contains(&request.function_name().to_string().as_str())
+        && inputs.map_or(true, |inputs| request.inputs() == inputs);
+
+    if !matches {
+        return Err(AvailError::new(
+            AvailErrorType::External,
+            format!(
+                "Remote signer authorized {}/{} instead of {}/{:?}",
+                request.program_id(),
+                request.function_name(),
+                program_id,
+                function_names
+            ),
+            "The remote signer authorized a different transaction.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a fee authorization signed elsewhere pays the asked fees for the given execution
+fn check_fee_authorization<N: Network>(
+    fee_authorization: &Authorization<N>,
+    execution_id: Field<N>,
+    base_fee: u64,
+    priority_fee: u64,
+) -> AvailResult<()> {
+    let request = fee_authorization.peek_next()?;
+    let inputs = request.inputs();
+
+    // fee_public(base_fee, priority_fee, execution_id), fee_private takes the fee record first
+    let paid = match inputs.len() {
+        n if n >= 3 => &inputs[n - 3..],
+        _ => {
+            return Err(AvailError::new(
+                AvailErrorType::External,
+                "Remote signer fee authorization has too few inputs".to_string(),
+                "The remote signer authorized a different fee.".to_string(),
+            ))
+        }
+    };
+
+    let expected = [
+        Value::<N>::from_str(&format!("{}u64", base_fee))?,
+        Value::<N>::from_str(&format!("{}u64", priority_fee))?,
+        Value::<N>::from_str(&execution_id.to_string())?,
+    ];
+
+    if paid != expected {
+        return Err(AvailError::new(
+            AvailErrorType::External,
+            format!(
+                "Remote signer authorized the fee {:?} instead of {:?}",
+                paid, expected
+            ),
+            "The remote signer authorized a different fee.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl<N: Network> Signer<N> for RemoteSigner {
+    fn address(&self) -> AvailResult<Address<N>> {
+        let result = self.call::<N>("address", serde_json::Value::Null)?;
+        let address = Self::result_field(&result, "address")?;
+
+        Ok(Address::<N>::from_str(&address)?)
+    }
+
+    fn sign_fields(&self, message: &[Field<N>]) -> AvailResult<Signature<N>> {
+        let message_fields = message
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<String>>();
+
+        let result = self.call::<N>(
+            "sign",
+            serde_json::json!({ "message_fields": message_fields }),
+        )?;
+        let signature = Signature::<N>::from_str(&Self::result_field(&result, "signature")?)?;
+
+        // never trust a signature the remote side claims is ours without checking it
+        let address = Signer::<N>::address(self)?;
+        if !signature.verify(&address, message) {
+            return Err(AvailError::new(
+                AvailErrorType::External,
+                "Remote signer returned a signature that does not verify".to_string(),
+                "Invalid signature from the remote signer.".to_string(),
+            ));
+        }
+
+        Ok(signature)
+    }
+
+    fn authorize(
+        &self,
+        program_id: &str,
+        function_name: &str,
+        inputs: Vec<Value<N>>,
+    ) -> AvailResult<Authorization<N>> {
+        let result = self.call::<N>(
+            "authorize",
+            serde_json::json!({
+                "program_id": program_id,
+                "function_name": function_name,
+                "inputs": inputs.iter().map(|input| input.to_string()).collect::<Vec<String>>(),
+            }),
+        )?;
+
+        let authorization =
+            Authorization::<N>::from_str(&Self::result_field(&result, "authorization")?)?;
+
+        // the wallet only proves and broadcasts what it asked for
+        let address = Signer::<N>::address(self)?;
+        check_authorization(
+            &authorization,
+            &address,
+            program_id,
+            &[function_name],
+            Some(&inputs),
+        )?;
+
+        Ok(authorization)
+    }
+
+    fn authorize_fee(
+        &self,
+        execution_id: Field<N>,
+        base_fee: u64,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+    ) -> AvailResult<Authorization<N>> {
+        let fee_function = match fee_record {
+            Some(_) => "fee_private",
+            None => "fee_public",
+        };
+
+        let result = self.call::<N>(
+            "authorize_fee",
+            serde_json::json!({
+                "execution_id": execution_id.to_string(),
+                "base_fee": base_fee,
+                "priority_fee": priority_fee,
+                "fee_record": fee_record.map(|record| record.to_string()),
+            }),
+        )?;
+
+        let fee_authorization =
+            Authorization::<N>::from_str(&Self::result_field(&result, "fee_authorization")?)?;
+
+        let address = Signer::<N>::address(self)?;
+        check_authorization(
+            &fee_authorization,
+            &address,
+            "credits.aleo",
+            &[fee_function],
+            None,
+        )?;
+        check_fee_authorization(&fee_authorization, execution_id, base_fee, priority_fee)?;
+
+        Ok(fee_authorization)
+    }
+
+    fn private_key(&self) -> AvailResult<PrivateKey<N>> {
+        Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            "Remote signer does not expose a private key".to_string(),
+            "This action is not supported with a remote signer.".to_string(),
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthorizeParams {
+    program_id: String,
+    function_name: String,
+    inputs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthorizeFeeParams {
+    execution_id: String,
+    base_fee: u64,
+    priority_fee: u64,
+    fee_record: Option<String>,
+}
+
+fn authorize_params<N: Network>(
+    params: &serde_json::Value,
+) -> AvailResult<(String, String, Vec<Value<N>>)> {
+    let params = serde_json::from_value::<AuthorizeParams>(params.clone())?;
+
+    let inputs = params
+        .inputs
+        .iter()
+        .map(|input| Ok(Value::<N>::from_str(input)?))
+        .collect::<AvailResult<Vec<Value<N>>>>()?;
+
+    Ok((params.program_id, params.function_name, inputs))
+}
+
+type AuthorizeFeeCall<N> = (Field<N>, u64, u64, Option<Record<N, Plaintext<N>>>);
+
+fn authorize_fee_params<N: Network>(
+    params: &serde_json::Value,
+) -> AvailResult<AuthorizeFeeCall<N>> {
+    let params = serde_json::from_value::<AuthorizeFeeParams>(params.clone())?;
+
+    let fee_record = match params.fee_record {
+        Some(record) => Some(Record::<N, Plaintext<N>>::from_str(&record)?),
+        None => None,
+    };
+
+    Ok((
+        Field::<N>::from_str(&params.execution_id)?,
+        params.base_fee,
+        params.priority_fee,
+        fee_record,
+    ))
+}
+
+/// Answers a single remote signer request with the given signer.
+/// Signing services and test stand-ins can use this to speak the same protocol as [`RemoteSigner`].
+pub fn handle_remote_signer_request<N: Network>(
+    signer: &dyn Signer<N>,
+    request: RemoteSignerRequest,
+) -> RemoteSignerResponse {
+    if request.network != N::NAME {
+        return RemoteSignerResponse::err(
+            request.id,
+            format!("Unsupported network '{}'", request.network),
+        );
+    }
+
+    let result = match request.method.as_str() {
+        "address" => signer
+            .address()
+            .map(|address| serde_json::json!({ "address": address.to_string() })),
+        "sign" => {
+            let fields = match request.params.get("message_fields") {
+                Some(fields) => serde_json::from_value::<Vec<String>>(fields.clone())
+                    .map_err(AvailError::from)
+                    .and_then(|fields| {
+                        fields
+                            .iter()
+                            .map(|field| Ok(Field::<N>::from_str(field)?))
+                            .collect::<AvailResult<Vec<Field<N>>>>()
+                    }),
+                None => Err(AvailError::new(
+                    AvailErrorType::InvalidData,
+                    "Missing message_fields".to_string(),
+                    "Missing message_fields".to_string(),
+                )),
+            };
+
+            fields
+                .and_then(|fields| signer.sign_fields(&fields))
+                .map(|signature| serde_json::json!({ "signature": signature.to_string() }))
+        }
+        "authorize" => authorize_params::<N>(&request.params).and_then(
+            |(program_id, function_name, inputs)| {
+                let authorization = signer.authorize(&program_id, &function_name, inputs)?;
+
+                Ok(serde_json::json!({ "authorization": authorization.to_string() }))
+            },
+        ),
+        "authorize_fee" => authorize_fee_params::<N>(&request.params).and_then(
+            |(execution_id, base_fee, priority_fee, fee_record)| {
+                let fee_authorization =
+                    signer.authorize_fee(execution_id, base_fee, priority_fee, fee_record)?;
+
+                Ok(serde_json::json!({ "fee_authorization": fee_authorization.to_string() }))
+            },
+        ),
+        method => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            format!("Unknown method '{}'", method),
+            format!("Unknown method '{}'", method),
+        )),
+    };
+
+    match result {
+        Ok(result) => RemoteSignerResponse::ok(request.id, result),
+        Err(e) => RemoteSignerResponse::err(request.id, e.internal_msg),
+    }
+}
+
+/// Returns the signer configured for this wallet, the local keyring unless a remote signer endpoint is set.
+pub fn get_signer<N: Network>(password: Option<String>) -> AvailResult<Box<dyn Signer<N>>> {
+    match get_signer_endpoint()? {
+        Some(endpoint) => Ok(Box::new(RemoteSigner::new(endpoint))),
+        None => Ok(Box::new(KeyringSigner::new(password))),
+    }
+}
+
+/// Switches between the local keyring signer (`None`) and a remote signer listening at `endpoint`.
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_remote_signer(endpoint: Option<String>) -> AvailResult<()> {
+    let network = get_network()?;
+
+    if let Some(endpoint) = endpoint.clone() {
+        let signer = RemoteSigner::new(endpoint);
+
+        let matches = match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => {
+                Signer::<TestnetV0>::address(&signer)? == get_address::<TestnetV0>()?
+            }
+        };
+
+        if !matches {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                "Remote signer address does not match the wallet address".to_string(),
+                "The remote signer holds a key for a different account.".to_string(),
+            ));
+        }
+    }
+
+    update_signer_endpoint(endpoint.as_deref())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_remote_signer() -> AvailResult<Option<String>> {
+    get_signer_endpoint()
+}
+
+#[cfg(test)]
+mod signer_tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    use avail_common::models::constants::TESTNET_PRIVATE_KEY;
+
+    /// Serves a single connection with a local stand-in signer.
+    fn spawn_stand_in_signer(private_key: PrivateKey<TestnetV0>, connections: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+
+        std::thread::spawn(move || {
+            let signer = LocalSigner::new(private_key);
+
+            for stream in listener.incoming().take(connections) {
+                let stream = stream.unwrap();
+                let mut line = String::new();
+                BufReader::new(stream.try_clone().unwrap())
+                    .read_line(&mut line)
+                    .unwrap();
+
+                let request: RemoteSignerRequest = serde_json::from_str(line.trim()).unwrap();
+                let response = handle_remote_signer_request::<TestnetV0>(&signer, request);
+
+                let mut writer = stream;
+                let mut response_line = serde_json::to_string(&response).unwrap();
+                response_line.push('\n');
+                writer.write_all(response_line.as_bytes()).unwrap();
+            }
+        });
+
+        endpoint
+    }
+
+    #[test]
+    fn test_local_signer_sign_message() {
+        let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let signer = LocalSigner::new(pk);
+
+        let (signature, msg_field) = signer.sign_message("Hello World").unwrap();
+        let msg = field_to_fields(&msg_field).unwrap();
+
+        assert!(signature.verify(&signer.address().unwrap(), &msg));
+    }
+
+    #[test]
+    fn test_remote_signer_round_trip() {
+        let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let address = Address::<TestnetV0>::try_from(&pk).unwrap();
+
+        // sign_message asks for the signature and then the address to verify it
+        let endpoint = spawn_stand_in_signer(pk, 3);
+        let signer = RemoteSigner::new(endpoint);
+
+        assert_eq!(Signer::<TestnetV0>::address(&signer).unwrap(), address);
+
+        let (signature, msg_field) =
+            Signer::<TestnetV0>::sign_message(&signer, "Hello World").unwrap();
+        let msg = field_to_fields(&msg_field).unwrap();
+
+        assert!(signature.verify(&address, &msg));
+        assert!(Signer::<TestnetV0>::private_key(&signer).is_err());
+    }
+
+    #[test]
+    fn test_remote_signer_authorize() {
+        let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let address = Address::<TestnetV0>::try_from(&pk).unwrap();
+
+        // each authorization is followed by asking the address to check it
+        let endpoint = spawn_stand_in_signer(pk, 4);
+        let signer = RemoteSigner::new(endpoint);
+
+        let inputs = vec![
+            Value::<TestnetV0>::from_str(&address.to_string()).unwrap(),
+            Value::<TestnetV0>::from_str("1000u64").unwrap(),
+        ];
+
+        let authorization = Signer::<TestnetV0>::authorize(
+            &signer,
+            "credits.aleo",
+            "transfer_public",
+            inputs.clone(),
+        )
+        .unwrap();
+
+        let request = authorization.peek_next().unwrap();
+        assert_eq!(request.signer(), &address);
+        assert_eq!(request.inputs(), inputs.as_slice());
+
+        let execution_id = authorization.to_execution_id().unwrap();
+        let fee_authorization =
+            Signer::<TestnetV0>::authorize_fee(&signer, execution_id, 5000, 10000, None).unwrap();
+
+        // an authorization for another call is refused
+        assert!(check_authorization(
+            &fee_authorization,
+            &address,
+            "credits.aleo",
+            &["fee_private"],
+            None
+        )
+        .is_err());
+        assert!(check_authorization(
+            &authorization,
+            &address,
+            "credits.aleo",
+            &["transfer_public"],
+            Some(&inputs[..1])
+        )
+        .is_err());
+
+        // so is a fee with other amounts or for another execution
+        check_fee_authorization(&fee_authorization, execution_id, 5000, 10000).unwrap();
+        assert!(check_fee_authorization(&fee_authorization, execution_id, 5000, 20000).is_err());
+        assert!(check_fee_authorization(&fee_authorization, execution_id, 4000, 10000).is_err());
+        assert!(
+            check_fee_authorization(&fee_authorization, Field::from_u64(1), 5000, 10000).is_err()
+        );
+    }
+
+    #[test]
+    fn test_remote_signer_unknown_method() {
+        let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let signer = LocalSigner::new(pk);
+
+        let request = RemoteSignerRequest {
+            id: 7,
+            network: TestnetV0::NAME.to_string(),
+            method: "export_private_key".to_string(),
+            params: serde_json::Value::Null,
+        };
+
+        let response = handle_remote_signer_request::<TestnetV0>(&signer, request);
+
+        assert_eq!(response.id, 7);
+        assert!(response.result.is_none());
+        assert!(response.error.is_some());
+    }
+}
@@ -1,10 +1,12 @@
 use crate::api::client::SESSION;
 use crate::helpers::utils::HOST;
 use crate::models::auth::{CreateSessionRequest, VerifySessionResponse};
+use crate::services::account::signer::{get_signer, LocalSigner, Signer};
 use crate::services::local_storage::{
     persistent_storage::{get_address_string, get_network},
     session::password::PASS,
-    utils::{sign_message, sign_message_w_key},
+    spending_policy::arm_policy_session,
+    utils::sign_message,
 };
 use snarkvm::prelude::*;
 use tauri_plugin_http::reqwest;
@@ -27,9 +29,9 @@ pub async fn get_session(password: Option<String>) -> AvailResult<String> {
 
     let (sig, _) = match SupportedNetworks::from_str(&network)? {
         SupportedNetworks::Testnet => {
-            sign_message::<TestnetV0>(&session_request.hash, password.clone())?
+            get_signer::<TestnetV0>(password.clone())?.sign_message(&session_request.hash)?
         }
-        _ => sign_message::<TestnetV0>(&session_request.hash, password.clone())?,
+        _ => get_signer::<TestnetV0>(password.clone())?.sign_message(&session_request.hash)?,
     };
 
     let verify_request = server_auth::VerifySessionRequest {
@@ -73,10 +75,15 @@ pub async fn get_session(password: Option<String>) -> AvailResult<String> {
 
         SESSION.set_session_token(session_cookie.value().to_string());
 
-        let _pass_session = match password {
-            Some(password) => PASS.set_pass_session(&password)?,
-            None => {}
-        };
+        if let Some(password) = password {
+            PASS.set_pass_session(&password)?;
+
+            // auto-approval keeps asking for the password when the policy session can't be unlocked
+            let _ = match SupportedNetworks::from_str(&network)? {
+                SupportedNetworks::Testnet => arm_policy_session::<TestnetV0>(&password),
+                _ => arm_policy_session::<TestnetV0>(&password),
+            };
+        }
 
         Ok(session_request.session_id.to_string())
     } else {
@@ -91,10 +98,15 @@ pub async fn get_session(password: Option<String>) -> AvailResult<String> {
 pub async fn get_session_after_creation<N: Network>(
     private_key: &PrivateKey<N>,
 ) -> AvailResult<String> {
-    let address = Address::<N>::try_from(private_key)?;
+    get_session_with_signer::<N>(&LocalSigner::new(*private_key)).await
+}
+
+/// Authenticates on the server with the configured signer, the private key is never read here.
+pub async fn get_session_with_signer<N: Network>(signer: &dyn Signer<N>) -> AvailResult<String> {
+    let address = signer.address()?;
     let session_request = request_hash(&address.to_string()).await?;
 
-    let (sig, _) = sign_message_w_key::<N>(&session_request.hash, private_key)?;
+    let (sig, _) = signer.sign_message(&session_request.hash)?;
 
     let verify_request = server_auth::VerifySessionRequest {
         signature: sig.to_string(),
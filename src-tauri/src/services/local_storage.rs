@@ -1,6 +1,14 @@
+pub mod accounts;
+pub mod dapp_sessions;
 pub mod encrypted_data;
 pub mod persistent_storage;
+pub mod profiles;
+pub mod program_cache;
+pub mod reputation;
+pub mod rpc_clients;
 pub mod session;
+pub mod spending_policy;
 pub mod storage_api;
+pub mod token_metadata;
 pub mod tokens;
 pub mod utils;
@@ -0,0 +1,205 @@
+use std::future::Future;
+
+use crate::models::account::DerivedAccount;
+use crate::models::storage::persistent::PersistentStorage;
+use crate::services::local_storage::persistent_storage::{
+    get_active_account, get_address_string, get_network,
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// A registered account together with the state it is switched in with
+#[derive(Debug, Clone)]
+pub struct AccountRow {
+    pub account: DerivedAccount,
+    /// View key encrypted with the wallet password
+    pub view_key: String,
+    pub last_sync: u32,
+}
+
+pub fn init_accounts_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS accounts (
+            account_index TEXT NOT NULL,
+            network TEXT NOT NULL,
+            address TEXT NOT NULL,
+            name TEXT NOT NULL,
+            view_key TEXT NOT NULL,
+            last_sync TEXT NOT NULL,
+            PRIMARY KEY (account_index, network)
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_accounts_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    match storage.execute_query("DROP TABLE IF EXISTS accounts") {
+        Ok(r) => r,
+        Err(e) => match e.error_type {
+            AvailErrorType::NotFound => {}
+            _ => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    e.internal_msg,
+                    "Error deleting accounts table".to_string(),
+                ))
+            }
+        },
+    };
+
+    Ok(())
+}
+
+fn row_to_account(row: &[String], active: u32) -> Option<AccountRow> {
+    let index = row.first()?.parse::<u32>().ok()?;
+
+    Some(AccountRow {
+        account: DerivedAccount {
+            index,
+            address: row.get(1)?.clone(),
+            name: row.get(2)?.clone(),
+            active: index == active,
+        },
+        view_key: row.get(3)?.clone(),
+        last_sync: row.get(4)?.parse::<u32>().ok()?,
+    })
+}
+
+fn query_accounts(condition: &str) -> AvailResult<Vec<AccountRow>> {
+    init_accounts_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT account_index, address, name, view_key, last_sync FROM accounts WHERE network='{}' {} ORDER BY CAST(account_index AS INTEGER) ASC",
+        get_network()?,
+        condition
+    );
+    let res = storage.get_all::<String>(&query, 5)?;
+
+    let active = get_active_account()?;
+
+    Ok(res
+        .iter()
+        .filter_map(|row| row_to_account(row, active))
+        .collect())
+}
+
+/// Registered accounts ordered by index
+pub fn list_accounts() -> AvailResult<Vec<AccountRow>> {
+    query_accounts("")
+}
+
+pub fn get_account(index: u32) -> AvailResult<Option<AccountRow>> {
+    let accounts = query_accounts(&format!("AND account_index='{}'", index))?;
+
+    Ok(accounts.into_iter().next())
+}
+
+pub fn get_account_by_address(address: &str) -> AvailResult<Option<AccountRow>> {
+    let accounts = query_accounts(&format!("AND address='{}'", address.replace('\'', "''")))?;
+
+    Ok(accounts.into_iter().next())
+}
+
+pub fn store_account(
+    index: u32,
+    address: &str,
+    name: &str,
+    view_key: &str,
+    last_sync: u32,
+) -> AvailResult<()> {
+    init_accounts_table()?;
+    let storage = PersistentStorage::new()?;
+
+    storage.save_mixed(
+        vec![
+            &index.to_string(),
+            &get_network()?,
+            &address.to_string(),
+            &name.to_string(),
+            &view_key.to_string(),
+            &last_sync.to_string(),
+        ],
+        "INSERT OR REPLACE INTO accounts (account_index, network, address, name, view_key, last_sync) VALUES (?1, ?2, ?3, ?4, ?5, ?6)".to_string(),
+    )
+}
+
+/// Keeps the scan height of an account while another account is active
+pub fn update_account_last_sync(index: u32, last_sync: u32) -> AvailResult<()> {
+    init_accounts_table()?;
+    let storage = PersistentStorage::new()?;
+
+    storage.save_mixed(
+        vec![&last_sync.to_string(), &index.to_string(), &get_network()?],
+        "UPDATE accounts SET last_sync = ?1 WHERE account_index = ?2 AND network = ?3".to_string(),
+    )
+}
+
+pub fn update_account_name(index: u32, name: &str) -> AvailResult<()> {
+    init_accounts_table()?;
+    let storage = PersistentStorage::new()?;
+
+    storage.save_mixed(
+        vec![&name.to_string(), &index.to_string(), &get_network()?],
+        "UPDATE accounts SET name = ?1 WHERE account_index = ?2 AND network = ?3".to_string(),
+    )
+}
+
+tokio::task_local! {
+    /// Registered account the current request acts as instead of the active account
+    static REQUEST_ACCOUNT: AccountRow;
+}
+
+/// Account the current request acts as, `None` when it acts as the active account.
+/// The address, private key and view key lookups follow it.
+pub fn request_account() -> Option<AccountRow> {
+    REQUEST_ACCOUNT.try_with(|row| row.clone()).ok()
+}
+
+/// Registered account a request names with `address`, `None` when it names the account in use or none
+fn requested_account(address: Option<&String>) -> AvailResult<Option<AccountRow>> {
+    let address = match address {
+        Some(address) if address != &get_address_string()? => address,
+        _ => return Ok(None),
+    };
+
+    match get_account_by_address(address)? {
+        Some(row) => Ok(Some(row)),
+        None => Err(AvailError::new(
+            AvailErrorType::Unauthorized,
+            format!("Request for account {} outside the registry", address),
+            "The request is for an account that is not in this wallet".to_string(),
+        )),
+    }
+}
+
+/// Runs `f` as the registered account with `address`, the active account stays as it is
+pub fn with_account<T>(
+    address: Option<&String>,
+    f: impl FnOnce() -> AvailResult<T>,
+) -> AvailResult<T> {
+    match requested_account(address)? {
+        Some(row) => REQUEST_ACCOUNT.sync_scope(row, f),
+        None => f(),
+    }
+}
+
+/// Async counterpart of [`with_account`]
+pub async fn with_account_async<T>(
+    address: Option<&String>,
+    f: impl Future<Output = AvailResult<T>>,
+) -> AvailResult<T> {
+    let account = requested_account(address)?;
+    in_account(account, f).await
+}
+
+/// Runs `f` as `account`, tasks spawned by a request use this to keep acting as its account
+pub async fn in_account<T>(account: Option<AccountRow>, f: impl Future<Output = T>) -> T {
+    match account {
+        Some(row) => REQUEST_ACCOUNT.scope(row, f).await,
+        None => f.await,
+    }
+}
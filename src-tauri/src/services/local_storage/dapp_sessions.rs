@@ -0,0 +1,448 @@
+use chrono::Utc;
+use url::Url;
+
+use crate::models::storage::persistent::PersistentStorage;
+use crate::models::wallet_connect::session::{DappScope, DappSession};
+use crate::services::local_storage::persistent_storage::{get_address_string, get_network};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+pub fn init_dapp_sessions_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS dapp_sessions (
+            origin TEXT NOT NULL,
+            network TEXT NOT NULL,
+            address TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            expires_at TEXT,
+            revoked TEXT NOT NULL,
+            PRIMARY KEY (origin, network)
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_dapp_sessions_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    match storage.execute_query("DROP TABLE IF EXISTS dapp_sessions") {
+        Ok(r) => r,
+        Err(e) => match e.error_type {
+            AvailErrorType::NotFound => {}
+            _ => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    e.internal_msg,
+                    "Error deleting dapp sessions table".to_string(),
+                ))
+            }
+        },
+    };
+
+    Ok(())
+}
+
+/// Origin the wallet names itself with when it relays a request. It is not a url with a host,
+/// so no dapp can pair with it.
+pub const WALLET_ORIGIN: &str = "avail:wallet";
+
+/// Dapp an authorized request came from, `None` for requests of the wallet itself
+pub fn requesting_dapp(origin: Option<String>) -> Option<String> {
+    origin.filter(|origin| origin != WALLET_ORIGIN)
+}
+
+/// Reduces a dapp url to its origin, i.e. `https://app.example:8080/swap` to `https://app.example:8080`
+pub fn normalize_origin(origin: &str) -> AvailResult<String> {
+    let url = match Url::parse(origin) {
+        Ok(url) => url,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Invalid dapp origin {}: {}", origin, e),
+                "Invalid dapp origin".to_string(),
+            ))
+        }
+    };
+
+    match url.origin() {
+        origin @ url::Origin::Tuple(..) => Ok(origin.ascii_serialization()),
+        url::Origin::Opaque(_) => Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Dapp origin {} has no host", origin),
+            "Invalid dapp origin".to_string(),
+        )),
+    }
+}
+
+fn save_dapp_session(session: &DappSession) -> AvailResult<()> {
+    init_dapp_sessions_table()?;
+    let storage = PersistentStorage::new()?;
+
+    storage.save_mixed(
+        vec![
+            session.origin(),
+            &get_network()?,
+            session.address(),
+            &serde_json::to_string(session.scopes())?,
+            &session.created_at().to_string(),
+            &session.expires_at().map(|expires_at| expires_at.to_string()),
+            &session.revoked().to_string(),
+        ],
+        "INSERT OR REPLACE INTO dapp_sessions (origin, network, address, scopes, created_at, expires_at, revoked) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)".to_string(),
+    )
+}
+
+fn row_to_dapp_session(row: &[Option<String>]) -> Option<DappSession> {
+    Some(DappSession::new(
+        row.first()?.clone()?,
+        row.get(1)?.clone()?,
+        serde_json::from_str::<Vec<DappScope>>(&row.get(2)?.clone()?).ok()?,
+        row.get(3)?.clone()?.parse::<i64>().ok()?,
+        match row.get(4)?.clone() {
+            Some(expires_at) => Some(expires_at.parse::<i64>().ok()?),
+            None => None,
+        },
+        row.get(5)?.clone()? == "true",
+    ))
+}
+
+fn query_dapp_sessions(condition: &str) -> AvailResult<Vec<DappSession>> {
+    init_dapp_sessions_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT origin, address, scopes, created_at, expires_at, revoked FROM dapp_sessions WHERE network='{}' {}",
+        get_network()?,
+        condition
+    );
+    let res = storage.get_all::<Option<String>>(&query, 6)?;
+
+    Ok(res
+        .iter()
+        .filter_map(|row| row_to_dapp_session(row))
+        .collect())
+}
+
+pub fn get_dapp_session(origin: &str) -> AvailResult<Option<DappSession>> {
+    let origin = normalize_origin(origin)?;
+    let sessions = query_dapp_sessions(&format!(
+        "AND origin='{}'",
+        origin.replace('\'', "''")
+    ))?;
+
+    Ok(sessions.into_iter().next())
+}
+
+fn unauthorized(internal: String, external: &str) -> AvailError {
+    AvailError::new(AvailErrorType::Unauthorized, internal, external.to_string())
+}
+
+/// Fails when a request names an account other than the one it acts as.
+/// Requests for a registered account run as that account, see [`with_account`](super::accounts::with_account).
+fn check_active_address(address: Option<&String>) -> AvailResult<()> {
+    match address {
+        Some(address) if address != &get_address_string()? => Err(unauthorized(
+            format!(
+                "Request for account {} while acting as another account",
+                address
+            ),
+            "The request is for another account than the one it was authorized for",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Active session of the dapp at `origin`, bound to the active account and the requested `address`.
+/// Requests of the wallet itself carry [`WALLET_ORIGIN`] and have no session, requests without an origin are refused.
+pub fn active_dapp_session(
+    origin: Option<&String>,
+    address: Option<&String>,
+) -> AvailResult<Option<DappSession>> {
+    let origin = match origin {
+        Some(origin) if origin == WALLET_ORIGIN => {
+            check_active_address(address)?;
+            return Ok(None);
+        }
+        Some(origin) => origin,
+        None => {
+            return Err(unauthorized(
+                "Dapp request without an origin".to_string(),
+                "The request did not name the dapp it came from",
+            ))
+        }
+    };
+
+    let session = match get_dapp_session(origin)? {
+        Some(session) => session,
+        None => {
+            return Err(unauthorized(
+                format!("No dapp session for {}", origin),
+                "This dapp is not connected to the wallet",
+            ))
+        }
+    };
+
+    if !session.is_active(Utc::now().timestamp()) {
+        return Err(unauthorized(
+            format!("Dapp session for {} was revoked or expired", origin),
+            "The dapp session has expired, please reconnect",
+        ));
+    }
+
+    // sessions are bound to the address they were granted for
+    let wallet_address = get_address_string()?;
+    if session.address() != &wallet_address {
+        return Err(unauthorized(
+            format!("Dapp session for {} was granted to another address", origin),
+            "The dapp is connected to another account, switch to it in the wallet first",
+        ));
+    }
+
+    if let Some(address) = address {
+        if address != session.address() {
+            return Err(unauthorized(
+                format!("Dapp {} requested address {}", origin, address),
+                "The dapp requested an account it is not connected to",
+            ));
+        }
+    }
+
+    Ok(Some(session))
+}
+
+/// Checks that the dapp at `origin` was granted `scope`, requests of the wallet itself are always allowed
+pub fn authorize_dapp(
+    origin: Option<&String>,
+    scope: &DappScope,
+    address: Option<&String>,
+) -> AvailResult<()> {
+    match active_dapp_session(origin, address)? {
+        Some(session) if !session.allows(scope) => Err(unauthorized(
+            format!("Dapp {} was not granted {:?}", session.origin(), scope),
+            "The dapp does not have permission for this request",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Grants a dapp the given scopes for the current address, replacing any earlier session
+#[tauri::command(rename_all = "snake_case")]
+pub fn grant_dapp_session(
+    origin: String,
+    scopes: Vec<DappScope>,
+    expires_in: Option<i64>,
+) -> AvailResult<DappSession> {
+    let now = Utc::now().timestamp();
+    let session = DappSession::new(
+        normalize_origin(&origin)?,
+        get_address_string()?,
+        scopes,
+        now,
+        expires_in.map(|seconds| now + seconds),
+        false,
+    );
+
+    save_dapp_session(&session)?;
+
+    Ok(session)
+}
+
+/// Adds a scope the user approved for a request to the active session of the dapp at `origin`
+#[tauri::command(rename_all = "snake_case")]
+pub fn extend_dapp_session(origin: String, scope: DappScope) -> AvailResult<DappSession> {
+    if let DappScope::ReadRecords(programs) | DappScope::Execute(programs) = &scope {
+        if programs.is_empty() {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Scope {:?} for {} names no programs", scope, origin),
+                "The request did not name any program".to_string(),
+            ));
+        }
+    }
+
+    let mut session = match active_dapp_session(Some(&origin), None)? {
+        Some(session) => session,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Scope {:?} requested for the wallet origin", scope),
+                "The wallet does not have a dapp session".to_string(),
+            ))
+        }
+    };
+
+    session.grant(scope);
+    save_dapp_session(&session)?;
+
+    Ok(session)
+}
+
+/// Fails when the dapp at `origin` has an active session, pairing must not take over a connected dapp
+pub fn check_dapp_unpaired(origin: &str) -> AvailResult<()> {
+    match get_dapp_session(origin)? {
+        Some(session) if session.is_active(Utc::now().timestamp()) => Err(unauthorized(
+            format!("Dapp {} already has an active session", origin),
+            "This dapp is already connected, revoke its session in the wallet to pair again",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Grants a session to a dapp pairing with the wallet, revoked and expired sessions are the only ones replaced
+pub fn grant_new_dapp_session(
+    origin: String,
+    scopes: Vec<DappScope>,
+    expires_in: Option<i64>,
+) -> AvailResult<DappSession> {
+    check_dapp_unpaired(&origin)?;
+    grant_dapp_session(origin, scopes, expires_in)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_dapp_sessions() -> AvailResult<Vec<DappSession>> {
+    query_dapp_sessions("ORDER BY created_at DESC")
+}
+
+/// Revokes a dapp session, the dapp has to be granted a new session to make requests again
+#[tauri::command(rename_all = "snake_case")]
+pub fn revoke_dapp_session(origin: String) -> AvailResult<()> {
+    let session = match get_dapp_session(&origin)? {
+        Some(session) => session,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::NotFound,
+                format!("No dapp session for {}", origin),
+                "Dapp session not found".to_string(),
+            ))
+        }
+    };
+
+    save_dapp_session(&DappSession::new(
+        session.origin().clone(),
+        session.address().clone(),
+        session.scopes().clone(),
+        session.created_at(),
+        session.expires_at(),
+        true,
+    ))
+}
+
+#[cfg(test)]
+mod dapp_sessions_tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_origin() {
+        assert_eq!(
+            normalize_origin("https://App.Example:8080/swap?x=1").unwrap(),
+            "https://app.example:8080"
+        );
+        assert_eq!(
+            normalize_origin("https://app.example").unwrap(),
+            "https://app.example"
+        );
+        assert!(normalize_origin("not a url").is_err());
+        assert!(normalize_origin("data:text/plain,hi").is_err());
+    }
+
+    #[test]
+    fn test_scope_coverage() {
+        let session = DappSession::new(
+            "https://app.example".to_string(),
+            "aleo1".to_string(),
+            vec![
+                DappScope::ReadBalance,
+                DappScope::ReadRecords(vec!["credits.aleo".to_string()]),
+                DappScope::Execute(vec!["swap.aleo".to_string()]),
+            ],
+            0,
+            Some(100),
+            false,
+        );
+
+        assert!(session.allows(&DappScope::ReadBalance));
+        assert!(!session.allows(&DappScope::Sign));
+        assert!(session.allows(&DappScope::ReadRecords(vec!["credits.aleo".to_string()])));
+        assert!(!session.allows(&DappScope::ReadRecords(vec![
+            "credits.aleo".to_string(),
+            "swap.aleo".to_string()
+        ])));
+        assert!(!session.allows(&DappScope::ReadRecords(vec![])));
+        assert!(session.allows(&DappScope::Execute(vec!["swap.aleo".to_string()])));
+        assert!(!session.allows(&DappScope::Execute(vec!["credits.aleo".to_string()])));
+
+        assert!(session.is_active(99));
+        assert!(!session.is_active(100));
+        assert_eq!(session.readable_programs(), vec!["credits.aleo".to_string()]);
+    }
+
+    #[test]
+    fn test_scope_grant() {
+        let mut session = DappSession::new(
+            "https://app.example".to_string(),
+            "aleo1".to_string(),
+            vec![
+                DappScope::Sign,
+                DappScope::Execute(vec!["swap.aleo".to_string()]),
+            ],
+            0,
+            None,
+            false,
+        );
+
+        session.grant(DappScope::Sign);
+        session.grant(DappScope::Execute(vec![
+            "swap.aleo".to_string(),
+            "credits.aleo".to_string(),
+        ]));
+        session.grant(DappScope::ReadRecords(vec!["credits.aleo".to_string()]));
+
+        assert_eq!(
+            session.scopes(),
+            &vec![
+                DappScope::Sign,
+                DappScope::Execute(vec!["swap.aleo".to_string(), "credits.aleo".to_string()]),
+                DappScope::ReadRecords(vec!["credits.aleo".to_string()]),
+            ]
+        );
+        assert!(session.allows(&DappScope::Execute(vec!["credits.aleo".to_string()])));
+        assert!(!session.allows(&DappScope::Decrypt));
+    }
+
+    #[test]
+    fn test_scope_serialization() {
+        let scopes = serde_json::from_str::<Vec<DappScope>>(
+            r#"[{"type":"read_balance"},{"type":"execute","programs":["swap.aleo"]}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            scopes,
+            vec![
+                DappScope::ReadBalance,
+                DappScope::Execute(vec!["swap.aleo".to_string()])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_authorize_without_origin() {
+        let error = authorize_dapp(None, &DappScope::Sign, None).unwrap_err();
+        assert!(error.error_type == AvailErrorType::Unauthorized);
+        assert!(active_dapp_session(None, None).is_err());
+
+        let wallet = WALLET_ORIGIN.to_string();
+        assert!(authorize_dapp(Some(&wallet), &DappScope::Sign, None).is_ok());
+        assert!(active_dapp_session(Some(&wallet), None).unwrap().is_none());
+
+        // a dapp can't pair with the wallet's own origin
+        assert!(normalize_origin(WALLET_ORIGIN).is_err());
+        assert_eq!(requesting_dapp(Some(wallet)), None);
+        assert_eq!(
+            requesting_dapp(Some("https://app.example".to_string())),
+            Some("https://app.example".to_string())
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use snarkvm::prelude::*;
 
-use crate::models::{event::Network as EventNetwork, storage::languages::Languages};
+use crate::models::{
+    event::Network as EventNetwork,
+    storage::languages::{Languages, MnemonicLanguage},
+};
 use crate::{
     api::aleo_client::setup_obscura_client, models::storage::persistent::PersistentStorage,
+    services::local_storage::accounts::request_account,
 };
 
 use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
@@ -303,6 +307,10 @@ pub fn update_last_tx_sync(timestamp: DateTime<Utc>) -> AvailResult<()> {
 
 /// get public address from view session
 pub fn get_address<N: Network>() -> AvailResult<Address<N>> {
+    if let Some(row) = request_account() {
+        return Ok(Address::<N>::from_str(&row.account.address)?);
+    }
+
     let storage = PersistentStorage::new()?;
     let query = "SELECT address FROM user_preferences".to_string();
 
@@ -320,6 +328,10 @@ pub fn get_address<N: Network>() -> AvailResult<Address<N>> {
 
 #[tauri::command(rename_all = "snake_case")]
 pub fn get_address_string() -> AvailResult<String> {
+    if let Some(row) = request_account() {
+        return Ok(row.account.address);
+    }
+
     let storage = PersistentStorage::new()?;
     let query = "SELECT address FROM user_preferences".to_string();
 
@@ -399,6 +411,71 @@ pub fn update_language(language: Languages) -> AvailResult<()> {
     Ok(())
 }
 
+/// Wordlist of the wallet's seed phrase, wallets from before it was stored get the legacy wordlist of their interface language stored
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_mnemonic_language() -> AvailResult<MnemonicLanguage> {
+    let storage = PersistentStorage::new()?;
+
+    // older installations do not have the mnemonic_language column yet
+    let stored = match storage.has_column("user_preferences", "mnemonic_language")? {
+        true => {
+            let query = "SELECT mnemonic_language FROM user_preferences".to_string();
+
+            storage
+                .get_all::<Option<String>>(&query, 1)?
+                .first()
+                .cloned()
+                .and_then(|row| row.into_iter().next().flatten())
+        }
+        false => None,
+    };
+
+    match stored {
+        Some(code) => match MnemonicLanguage::from_string_short(&code) {
+            Some(language) => Ok(language),
+            None => Err(AvailError::new(
+                AvailErrorType::LocalStorage,
+                format!("Unknown seed phrase language {}", code),
+                "Error getting seed phrase language".to_string(),
+            )),
+        },
+        None => {
+            // stored so that changing the interface language later can't change the wordlist
+            let language = get_language()?.legacy_mnemonic_language();
+            update_mnemonic_language(&language)?;
+
+            Ok(language)
+        }
+    }
+}
+
+pub fn update_mnemonic_language(language: &MnemonicLanguage) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+
+    // if storage.save fails add the mnemonic_language column to the user_preferences table
+    match storage.save(
+        vec![language.to_string_short()],
+        "UPDATE user_preferences SET mnemonic_language = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage
+                .execute_query("ALTER TABLE user_preferences ADD COLUMN mnemonic_language TEXT")?;
+
+            storage.save(
+                vec![language.to_string_short()],
+                "UPDATE user_preferences SET mnemonic_language = ?1".to_string(),
+            )
+        }
+    }
+}
+
+/// Wordlists a seed phrase can be created in or recovered from
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_supported_mnemonic_languages() -> Vec<MnemonicLanguage> {
+    MnemonicLanguage::supported()
+}
+
 pub fn get_base_url() -> AvailResult<String> {
     let storage = PersistentStorage::new()?;
 
@@ -441,6 +518,269 @@ pub fn update_base_url(base_url: &str) -> AvailResult<()> {
     }
 }
 
+/// Get the remote signer endpoint, `None` means keys are read from the local keyring
+pub fn get_signer_endpoint() -> AvailResult<Option<String>> {
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT signer_endpoint FROM user_preferences".to_string();
+
+    // older installations do not have the signer_endpoint column yet
+    let res = match storage.get_all::<Option<String>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(None),
+    };
+
+    match res.first() {
+        Some(endpoint) => Ok(endpoint.first().cloned().flatten()),
+        None => Ok(None),
+    }
+}
+
+pub fn update_signer_endpoint(endpoint: Option<&str>) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    let endpoint = endpoint.map(|endpoint| endpoint.to_string());
+
+    // if storage.save fails add the signer_endpoint column to the user_preferences table
+    match storage.save(
+        vec![endpoint.clone()],
+        "UPDATE user_preferences SET signer_endpoint = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage.execute_query("ALTER TABLE user_preferences ADD COLUMN signer_endpoint TEXT")?;
+
+            storage.save(
+                vec![endpoint],
+                "UPDATE user_preferences SET signer_endpoint = ?1".to_string(),
+            )
+        }
+    }
+}
+
+pub fn get_prover_endpoint() -> AvailResult<Option<String>> {
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT prover_endpoint FROM user_preferences".to_string();
+
+    // older installations do not have the prover_endpoint column yet
+    let res = match storage.get_all::<Option<String>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(None),
+    };
+
+    match res.first() {
+        Some(endpoint) => Ok(endpoint.first().cloned().flatten()),
+        None => Ok(None),
+    }
+}
+
+/// Whether proving falls back to the local prover when the delegated prover fails, defaults to true
+pub fn get_prover_fallback() -> AvailResult<bool> {
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT prover_fallback FROM user_preferences".to_string();
+
+    let res = match storage.get_all::<Option<bool>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(true),
+    };
+
+    match res.first() {
+        Some(fallback) => Ok(fallback.first().cloned().flatten().unwrap_or(true)),
+        None => Ok(true),
+    }
+}
+
+pub fn update_prover_settings(endpoint: Option<&str>, fallback: bool) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    let endpoint = endpoint.map(|endpoint| endpoint.to_string());
+
+    let query = "UPDATE user_preferences SET prover_endpoint = ?1, prover_fallback = ?2".to_string();
+
+    // if storage.save_mixed fails add the prover columns to the user_preferences table
+    match storage.save_mixed(vec![&endpoint, &fallback], query.clone()) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage.execute_query("ALTER TABLE user_preferences ADD COLUMN prover_endpoint TEXT")?;
+            storage
+                .execute_query("ALTER TABLE user_preferences ADD COLUMN prover_fallback BOOLEAN")?;
+
+            storage.save_mixed(vec![&endpoint, &fallback], query)
+        }
+    }
+}
+
+pub fn get_token_list_source() -> AvailResult<Option<String>> {
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT token_list_source FROM user_preferences".to_string();
+
+    // older installations do not have the token_list_source column yet
+    let res = match storage.get_all::<Option<String>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(None),
+    };
+
+    match res.first() {
+        Some(source) => Ok(source.first().cloned().flatten()),
+        None => Ok(None),
+    }
+}
+
+pub fn update_token_list_source(source: Option<&str>) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    let source = source.map(|source| source.to_string());
+
+    // if storage.save fails add the token_list_source column to the user_preferences table
+    match storage.save(
+        vec![source.clone()],
+        "UPDATE user_preferences SET token_list_source = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage
+                .execute_query("ALTER TABLE user_preferences ADD COLUMN token_list_source TEXT")?;
+
+            storage.save(
+                vec![source],
+                "UPDATE user_preferences SET token_list_source = ?1".to_string(),
+            )
+        }
+    }
+}
+
+/// Port of the local rpc server, None when it is turned off
+pub fn get_rpc_server_port() -> AvailResult<Option<u16>> {
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT rpc_server_port FROM user_preferences".to_string();
+
+    // older installations do not have the rpc_server_port column yet
+    let res = match storage.get_all::<Option<String>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(None),
+    };
+
+    match res.first() {
+        Some(port) => Ok(port
+            .first()
+            .cloned()
+            .flatten()
+            .and_then(|port| port.parse::<u16>().ok())),
+        None => Ok(None),
+    }
+}
+
+pub fn update_rpc_server_port(port: Option<u16>) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    let port = port.map(|port| port.to_string());
+
+    // if storage.save fails add the rpc_server_port column to the user_preferences table
+    match storage.save(
+        vec![port.clone()],
+        "UPDATE user_preferences SET rpc_server_port = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage.execute_query("ALTER TABLE user_preferences ADD COLUMN rpc_server_port TEXT")?;
+
+            storage.save(
+                vec![port],
+                "UPDATE user_preferences SET rpc_server_port = ?1".to_string(),
+            )
+        }
+    }
+}
+
+/// Index of the derived account the wallet is using, 0 for the account of the seed phrase itself
+pub fn get_active_account() -> AvailResult<u32> {
+    if let Some(row) = request_account() {
+        return Ok(row.account.index);
+    }
+
+    let storage = PersistentStorage::new()?;
+
+    let query = "SELECT active_account FROM user_preferences".to_string();
+
+    // older installations do not have the active_account column yet
+    let res = match storage.get_all::<Option<String>>(&query, 1) {
+        Ok(res) => res,
+        Err(_) => return Ok(0),
+    };
+
+    match res.first() {
+        Some(index) => Ok(index
+            .first()
+            .cloned()
+            .flatten()
+            .and_then(|index| index.parse::<u32>().ok())
+            .unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+pub fn update_active_account(index: u32) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    let index = index.to_string();
+
+    // if storage.save fails add the active_account column to the user_preferences table
+    match storage.save(
+        vec![index.clone()],
+        "UPDATE user_preferences SET active_account = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage.execute_query("ALTER TABLE user_preferences ADD COLUMN active_account TEXT")?;
+
+            storage.save(
+                vec![index],
+                "UPDATE user_preferences SET active_account = ?1".to_string(),
+            )
+        }
+    }
+}
+
+/// Whether the seed phrase is protected by a BIP-39 passphrase, the passphrase itself is only kept in the keyring if the user opted in
+pub fn get_passphrase_protected() -> AvailResult<bool> {
+    let storage = PersistentStorage::new()?;
+
+    // older installations do not have the passphrase_protected column yet
+    if !storage.has_column("user_preferences", "passphrase_protected")? {
+        return Ok(false);
+    }
+
+    let query = "SELECT passphrase_protected FROM user_preferences".to_string();
+
+    let res = storage.get_all::<Option<bool>>(&query, 1)?;
+
+    match res.first() {
+        Some(protected) => Ok(protected.first().cloned().flatten().unwrap_or(false)),
+        None => Ok(false),
+    }
+}
+
+pub fn update_passphrase_protected(protected: bool) -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+
+    // if storage.save fails add the passphrase_protected column to the user_preferences table
+    match storage.save(
+        vec![&protected],
+        "UPDATE user_preferences SET passphrase_protected = ?1".to_string(),
+    ) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            storage.execute_query(
+                "ALTER TABLE user_preferences ADD COLUMN passphrase_protected BOOLEAN",
+            )?;
+
+            storage.save(
+                vec![&protected],
+                "UPDATE user_preferences SET passphrase_protected = ?1".to_string(),
+            )
+        }
+    }
+}
+
 #[test]
 fn test_initial_user_preferences() {
     initial_user_preferences(
@@ -543,6 +883,33 @@ fn test_get_base_url() {
     print!("{}", base_url);
 }
 
+#[test]
+fn test_legacy_mnemonic_language_is_stored() {
+    initial_user_preferences(
+        true,
+        Some("Test".to_string()),
+        Some(1234),
+        false,
+        false,
+        "address".to_string(),
+        Languages::Japanese,
+    )
+    .unwrap();
+
+    // a wallet from before the seed phrase language was stored
+    update_mnemonic_language(&MnemonicLanguage::English).unwrap();
+    let storage = PersistentStorage::new().unwrap();
+    storage
+        .execute_query("UPDATE user_preferences SET mnemonic_language = NULL")
+        .unwrap();
+
+    assert_eq!(get_mnemonic_language().unwrap(), MnemonicLanguage::Japanese);
+
+    update_language(Languages::English).unwrap();
+
+    assert_eq!(get_mnemonic_language().unwrap(), MnemonicLanguage::Japanese);
+}
+
 #[tokio::test]
 async fn test_timestamp_to_blockheight() {
     let timestamp = Utc::now();
@@ -0,0 +1,287 @@
+use chrono::Utc;
+use snarkvm::prelude::TestnetV0;
+
+use crate::api::client::SESSION;
+use crate::models::storage::persistent::PersistentStorage;
+use crate::models::storage::profile::{
+    cache_active_profile, cached_active_profile, profile_dir, validate_profile_id, Profile,
+    DEFAULT_PROFILE,
+};
+use crate::services::account::key_management::desktop::{
+    delete_profile_keys, verify_profile_password,
+};
+use crate::services::local_storage::session::{
+    passphrase::PASSPHRASE, password::PASS, policy::POLICY_PASS, view::VIEWSESSION,
+};
+use crate::services::record_handling::records::stop_scans;
+use crate::services::rpc_server::stop_rpc_tasks;
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// The registry lives in its own database next to the profiles, so it can be read before a profile is opened
+fn init_profiles_tables(storage: &PersistentStorage) -> AvailResult<()> {
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS profiles (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+    )?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS active_profile (
+            id TEXT NOT NULL
+        )",
+    )?;
+
+    // installations from before profiles keep their wallet as the default profile
+    storage.save_mixed(
+        vec![
+            &DEFAULT_PROFILE.to_string(),
+            &"Default".to_string(),
+            &Utc::now().timestamp().to_string(),
+        ],
+        "INSERT OR IGNORE INTO profiles (id, name, created_at) VALUES (?1, ?2, ?3)".to_string(),
+    )
+}
+
+fn profiles_storage() -> AvailResult<PersistentStorage> {
+    let storage = PersistentStorage::new_profiles()?;
+    init_profiles_tables(&storage)?;
+
+    Ok(storage)
+}
+
+/// Profile the wallet's database and keyring entries are read from
+pub fn get_active_profile() -> AvailResult<String> {
+    if let Some(profile) = cached_active_profile() {
+        return Ok(profile);
+    }
+
+    let storage = profiles_storage()?;
+    let res = storage.get_all::<String>("SELECT id FROM active_profile", 1)?;
+
+    let profile = res
+        .into_iter()
+        .next()
+        .and_then(|row| row.into_iter().next())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+    cache_active_profile(&profile);
+
+    Ok(profile)
+}
+
+fn query_profiles(condition: &str) -> AvailResult<Vec<Profile>> {
+    let storage = profiles_storage()?;
+    let active = get_active_profile()?;
+
+    let query = format!(
+        "SELECT id, name, created_at FROM profiles {} ORDER BY CAST(created_at AS INTEGER) ASC",
+        condition
+    );
+    let res = storage.get_all::<String>(&query, 3)?;
+
+    Ok(res
+        .iter()
+        .filter_map(|row| {
+            let id = row.first()?.clone();
+
+            Some(Profile {
+                active: id == active,
+                name: row.get(1)?.clone(),
+                created_at: row.get(2)?.parse::<i64>().ok()?,
+                id,
+            })
+        })
+        .collect())
+}
+
+fn get_profile(id: &str) -> AvailResult<Profile> {
+    validate_profile_id(id)?;
+
+    match query_profiles(&format!("WHERE id='{}'", id))?
+        .into_iter()
+        .next()
+    {
+        Some(profile) => Ok(profile),
+        None => Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("No profile with id {}", id),
+            "Profile not found".to_string(),
+        )),
+    }
+}
+
+/// Profile names are shown in the profile picker, so they have to tell profiles apart
+fn validate_profile_name(name: &str, id: Option<&str>) -> AvailResult<String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "Profile name is empty".to_string(),
+            "Profile name can't be empty".to_string(),
+        ));
+    }
+
+    let taken = query_profiles("")?.into_iter().any(|profile| {
+        profile.name.to_lowercase() == name.to_lowercase() && Some(profile.id.as_str()) != id
+    });
+
+    if taken {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Profile name {} is taken", name),
+            "A profile with this name already exists".to_string(),
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Creates an empty profile, a wallet is created or imported in it after switching to it
+#[tauri::command(rename_all = "snake_case")]
+pub fn create_profile(name: String) -> AvailResult<Profile> {
+    let name = validate_profile_name(&name, None)?;
+    let id = hex::encode(rand::random::<[u8; 8]>());
+    let created_at = Utc::now().timestamp();
+
+    profile_dir(&id)?;
+
+    let storage = profiles_storage()?;
+    storage.save_mixed(
+        vec![&id, &name, &created_at.to_string()],
+        "INSERT INTO profiles (id, name, created_at) VALUES (?1, ?2, ?3)".to_string(),
+    )?;
+
+    Ok(Profile {
+        id,
+        name,
+        created_at,
+        active: false,
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn list_profiles() -> AvailResult<Vec<Profile>> {
+    query_profiles("")
+}
+
+/// Opens another profile, the unlocked keys, the seed passphrase and the server session of the previous profile are forgotten.
+/// Scans and the rpc server of the previous profile are stopped first, so they don't write into the new profile.
+#[tauri::command(rename_all = "snake_case")]
+pub fn switch_profile(id: String) -> AvailResult<Profile> {
+    let profile = get_profile(&id)?;
+
+    if !profile.active {
+        stop_rpc_tasks()?;
+        let _scans = stop_scans();
+
+        let storage = profiles_storage()?;
+        storage.execute_query("DELETE FROM active_profile")?;
+        storage.save(
+            vec![profile.id.clone()],
+            "INSERT INTO active_profile (id) VALUES (?1)".to_string(),
+        )?;
+
+        cache_active_profile(&profile.id);
+
+        PASS.clear_session()?;
+        PASSPHRASE.clear_session()?;
+        POLICY_PASS.clear_session()?;
+        VIEWSESSION.clear_session()?;
+        SESSION.clear_session_token();
+    }
+
+    Ok(Profile {
+        active: true,
+        ..profile
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn rename_profile(id: String, name: String) -> AvailResult<()> {
+    let profile = get_profile(&id)?;
+    let name = validate_profile_name(&name, Some(&profile.id))?;
+
+    let storage = profiles_storage()?;
+    storage.save(
+        vec![name, profile.id],
+        "UPDATE profiles SET name = ?1 WHERE id = ?2".to_string(),
+    )
+}
+
+/// Deletes a profile's database files and keyring entries.
+/// The active profile and the default profile can't be deleted, their wallets are deleted with `delete_util` instead.
+#[tauri::command(rename_all = "snake_case")]
+pub fn delete_profile(id: String, password: String) -> AvailResult<()> {
+    let profile = get_profile(&id)?;
+
+    if profile.id == DEFAULT_PROFILE || profile.active {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Profile {} is the default or active profile", profile.id),
+            "Switch to another profile before deleting this one".to_string(),
+        ));
+    }
+
+    verify_profile_password::<TestnetV0>(&profile.id, &password)?;
+
+    delete_profile_keys(&profile.id)?;
+    std::fs::remove_dir_all(profile_dir(&profile.id)?)?;
+
+    let storage = profiles_storage()?;
+    storage.save(
+        vec![profile.id],
+        "DELETE FROM profiles WHERE id = ?1".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod profiles_tests {
+    use super::*;
+
+    #[test]
+    fn test_create_switch_delete_profile() {
+        let original = get_active_profile().unwrap();
+        let name = format!("Profile {}", hex::encode(rand::random::<[u8; 4]>()));
+
+        let profile = create_profile(name.clone()).unwrap();
+        assert!(!profile.active);
+        assert!(list_profiles()
+            .unwrap()
+            .iter()
+            .any(|listed| listed.id == profile.id && listed.name == name));
+
+        // names tell profiles apart, regardless of case
+        assert!(create_profile(name.to_uppercase()).is_err());
+
+        PASS.set_pass_session("password").unwrap();
+        let switched = switch_profile(profile.id.clone()).unwrap();
+        assert!(switched.active);
+        assert_eq!(get_active_profile().unwrap(), profile.id);
+        assert!(PASS.get_instance().is_err());
+
+        // the active profile can't be deleted
+        assert!(delete_profile(profile.id.clone(), "password".to_string()).is_err());
+
+        switch_profile(original.clone()).unwrap();
+        assert_eq!(get_active_profile().unwrap(), original);
+
+        delete_profile(profile.id.clone(), "password".to_string()).unwrap();
+        assert!(list_profiles()
+            .unwrap()
+            .iter()
+            .all(|listed| listed.id != profile.id));
+        assert!(get_profile(&profile.id).is_err());
+    }
+
+    #[test]
+    fn test_default_profile_is_kept() {
+        let error =
+            delete_profile(DEFAULT_PROFILE.to_string(), "password".to_string()).unwrap_err();
+        assert!(error.error_type == AvailErrorType::Validation);
+
+        assert!(switch_profile("../default".to_string()).is_err());
+    }
+}
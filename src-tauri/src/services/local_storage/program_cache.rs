@@ -0,0 +1,74 @@
+use chrono::Utc;
+
+use crate::models::storage::persistent::PersistentStorage;
+use crate::services::local_storage::persistent_storage::get_network;
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Cached sources are fetched again after a day, programs can be upgraded
+const PROGRAM_CACHE_MAX_AGE: i64 = 24 * 60 * 60;
+
+pub fn init_program_cache_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS program_cache (
+            program_id TEXT NOT NULL,
+            network TEXT NOT NULL,
+            source TEXT NOT NULL,
+            fetched_at TEXT NOT NULL,
+            PRIMARY KEY (program_id, network)
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_program_cache_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    match storage.execute_query("DROP TABLE IF EXISTS program_cache") {
+        Ok(r) => r,
+        Err(e) => match e.error_type {
+            AvailErrorType::NotFound => {}
+            _ => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    e.internal_msg,
+                    "Error deleting program cache table".to_string(),
+                ))
+            }
+        },
+    };
+
+    Ok(())
+}
+
+/// Source of a program fetched within the last day
+pub fn get_cached_program(program_id: &str) -> AvailResult<Option<String>> {
+    init_program_cache_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT source FROM program_cache WHERE program_id='{}' AND network='{}' AND CAST(fetched_at AS INTEGER) > {}",
+        program_id.replace('\'', "''"),
+        get_network()?,
+        Utc::now().timestamp() - PROGRAM_CACHE_MAX_AGE
+    );
+    let res = storage.get_all::<String>(&query, 1)?;
+
+    Ok(res.into_iter().next().and_then(|row| row.into_iter().next()))
+}
+
+pub fn store_cached_program(program_id: &str, source: &str) -> AvailResult<()> {
+    init_program_cache_table()?;
+    let storage = PersistentStorage::new()?;
+
+    storage.save_mixed(
+        vec![
+            &program_id.to_string(),
+            &get_network()?,
+            &source.to_string(),
+            &Utc::now().timestamp().to_string(),
+        ],
+        "INSERT OR REPLACE INTO program_cache (program_id, network, source, fetched_at) VALUES (?1, ?2, ?3, ?4)".to_string(),
+    )
+}
@@ -0,0 +1,349 @@
+use rusqlite::types::ToSql;
+use snarkvm::prelude::{Address, Network, TestnetV0};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::models::reputation::{
+    parse_target, ReputationEntry, ReputationSource, ReputationStatus, SignedReputationList,
+};
+use crate::models::storage::persistent::PersistentStorage;
+use crate::models::wallet_connect::create_event::CreateEventRequest;
+use crate::services::local_storage::persistent_storage::get_network;
+use crate::services::wallet_connect_api::verify_signature;
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+pub fn init_reputation_tables() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS reputation_entries (
+            target TEXT NOT NULL,
+            network TEXT NOT NULL,
+            status TEXT NOT NULL,
+            source TEXT NOT NULL,
+            reason TEXT,
+            PRIMARY KEY (target, network, source)
+        )",
+    )?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS reputation_lists (
+            network TEXT PRIMARY KEY,
+            publisher TEXT NOT NULL,
+            version TEXT NOT NULL
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_reputation_tables() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    for table in ["reputation_entries", "reputation_lists"] {
+        match storage.execute_query(&format!("DROP TABLE IF EXISTS {}", table)) {
+            Ok(r) => r,
+            Err(e) => match e.error_type {
+                AvailErrorType::NotFound => {}
+                _ => {
+                    return Err(AvailError::new(
+                        AvailErrorType::Internal,
+                        e.internal_msg,
+                        "Error deleting reputation lists".to_string(),
+                    ))
+                }
+            },
+        };
+    }
+
+    Ok(())
+}
+
+/// Entries of the current network, `condition` binds `params` after the network
+fn query_reputation_entries(
+    condition: &str,
+    params: &[String],
+) -> AvailResult<Vec<ReputationEntry>> {
+    init_reputation_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    // user entries sort before list entries of the same target
+    let query = format!(
+        "SELECT target, status, source, reason FROM reputation_entries WHERE network = ? {} ORDER BY target ASC, source DESC",
+        condition
+    );
+
+    let network = get_network()?;
+    let mut data: Vec<&dyn ToSql> = vec![&network];
+    data.extend(params.iter().map(|param| param as &dyn ToSql));
+
+    let res = storage.get_all_mixed::<String>(&query, data, 4)?;
+
+    let mut targets = HashSet::new();
+    let mut entries = vec![];
+    for row in res {
+        let (target, status, source) = match (row.first(), row.get(1), row.get(2)) {
+            (Some(target), Some(status), Some(source)) => (target, status, source),
+            _ => continue,
+        };
+
+        if !targets.insert(target.clone()) {
+            continue;
+        }
+
+        entries.push(ReputationEntry {
+            target: target.clone(),
+            status: ReputationStatus::from_str(status)?,
+            reason: row.get(3).cloned(),
+            source: ReputationSource::from_str(source)?,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Reputation of the given targets, targets without an entry are left out
+pub fn get_reputation(targets: &[String]) -> AvailResult<Vec<ReputationEntry>> {
+    if targets.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let placeholders = vec!["?"; targets.len()].join(", ");
+
+    query_reputation_entries(&format!("AND target IN ({})", placeholders), targets)
+}
+
+/// First blocked target among the given ones
+pub fn find_blocked(targets: &[String]) -> AvailResult<Option<ReputationEntry>> {
+    Ok(get_reputation(targets)?
+        .into_iter()
+        .find(|entry| entry.is_blocked()))
+}
+
+/// Program id and address inputs of a wallet connect request
+pub fn request_targets<N: Network>(request: &CreateEventRequest) -> Vec<String> {
+    let mut targets = vec![request.program_id().clone()];
+
+    for input in request.inputs() {
+        if let Ok(address) = Address::<N>::from_str(input.trim()) {
+            targets.push(address.to_string());
+        }
+    }
+
+    targets
+}
+
+fn get_publisher() -> AvailResult<Option<(String, u64)>> {
+    init_reputation_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT publisher, version FROM reputation_lists WHERE network='{}'",
+        get_network()?
+    );
+    let res = storage.get_all::<String>(&query, 2)?;
+
+    Ok(res.into_iter().next().and_then(|row| {
+        let version = row.get(1)?.parse::<u64>().ok()?;
+        Some((row.first()?.clone(), version))
+    }))
+}
+
+fn delete_list_entries(storage: &PersistentStorage, network: &str) -> AvailResult<()> {
+    storage.save_mixed(
+        vec![&network, &ReputationSource::List.to_str().to_string()],
+        "DELETE FROM reputation_entries WHERE network = ?1 AND source = ?2".to_string(),
+    )
+}
+
+fn save_entry(
+    storage: &PersistentStorage,
+    network: &str,
+    entry: &ReputationEntry,
+) -> AvailResult<()> {
+    storage.save_mixed(
+        vec![
+            &entry.target,
+            &network,
+            &entry.status.to_str().to_string(),
+            &entry.source.to_str().to_string(),
+            &entry.reason,
+        ],
+        "INSERT OR REPLACE INTO reputation_entries (target, network, status, source, reason) VALUES (?1, ?2, ?3, ?4, ?5)".to_string(),
+    )
+}
+
+fn set_reputation_publisher_raw<N: Network>(publisher: &str) -> AvailResult<()> {
+    let publisher = Address::<N>::from_str(publisher.trim())
+        .map_err(|e| {
+            AvailError::new(
+                AvailErrorType::Validation,
+                e.to_string(),
+                "The list publisher has to be an address".to_string(),
+            )
+        })?
+        .to_string();
+
+    if let Some((current, _)) = get_publisher()? {
+        if current == publisher {
+            return Ok(());
+        }
+    }
+
+    let storage = PersistentStorage::new()?;
+    let network = get_network()?;
+
+    // entries of the previous publisher are no longer trusted
+    delete_list_entries(&storage, &network)?;
+    storage.save_mixed(
+        vec![&network, &publisher, &"0".to_string()],
+        "INSERT OR REPLACE INTO reputation_lists (network, publisher, version) VALUES (?1, ?2, ?3)"
+            .to_string(),
+    )
+}
+
+/// Sets the address whose signed reputation lists are accepted, removing the entries of the previous publisher
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_reputation_publisher(publisher: String) -> AvailResult<()> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => set_reputation_publisher_raw::<TestnetV0>(&publisher),
+        _ => set_reputation_publisher_raw::<TestnetV0>(&publisher),
+    }
+}
+
+fn import_reputation_list_raw<N: Network>(signed: SignedReputationList) -> AvailResult<u64> {
+    let (publisher, version) = match get_publisher()? {
+        Some(publisher) => publisher,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                "No reputation list publisher is set".to_string(),
+                "Set a list publisher before importing a reputation list".to_string(),
+            ))
+        }
+    };
+
+    let unsigned = || {
+        AvailError::new(
+            AvailErrorType::Validation,
+            format!("Reputation list is not signed by {}", publisher),
+            "The reputation list is not signed by the list publisher".to_string(),
+        )
+    };
+
+    if signed.publisher != publisher {
+        return Err(unsigned());
+    }
+    if !verify_signature::<N>(&signed.list, &publisher, &signed.signature).unwrap_or(false) {
+        return Err(unsigned());
+    }
+
+    let network = get_network()?;
+    let list = signed.parse::<N>(&network)?;
+
+    // an older list signed by the same publisher can't bring back removed entries
+    if list.version <= version {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!(
+                "Reputation list version {} is not newer than {}",
+                list.version, version
+            ),
+            "The reputation list is not newer than the imported one".to_string(),
+        ));
+    }
+
+    let storage = PersistentStorage::new()?;
+    delete_list_entries(&storage, &network)?;
+    for entry in &list.entries {
+        save_entry(&storage, &network, entry)?;
+    }
+
+    storage.save_mixed(
+        vec![&list.version.to_string(), &network],
+        "UPDATE reputation_lists SET version = ?1 WHERE network = ?2".to_string(),
+    )?;
+
+    Ok(list.version)
+}
+
+/// Replaces the list entries with a newer list of the configured publisher, returns the imported version
+#[tauri::command(rename_all = "snake_case")]
+pub fn import_reputation_list(list: SignedReputationList) -> AvailResult<u64> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => import_reputation_list_raw::<TestnetV0>(list),
+        _ => import_reputation_list_raw::<TestnetV0>(list),
+    }
+}
+
+/// Blocks or verifies a program id or address, overriding the list entry of the same target
+#[tauri::command(rename_all = "snake_case")]
+pub fn add_reputation_entry(
+    target: String,
+    status: ReputationStatus,
+    reason: Option<String>,
+) -> AvailResult<ReputationEntry> {
+    init_reputation_tables()?;
+
+    let target = match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => parse_target::<TestnetV0>(&target)?,
+        _ => parse_target::<TestnetV0>(&target)?,
+    };
+
+    let entry = ReputationEntry {
+        target,
+        status,
+        reason,
+        source: ReputationSource::User,
+    };
+
+    let storage = PersistentStorage::new()?;
+    save_entry(&storage, &get_network()?, &entry)?;
+
+    Ok(entry)
+}
+
+/// Removes the user's own entry of a target, list entries can only be replaced by a newer list
+#[tauri::command(rename_all = "snake_case")]
+pub fn remove_reputation_entry(target: String) -> AvailResult<()> {
+    init_reputation_tables()?;
+
+    // entries are stored in their canonical form, so the target is matched in it as well
+    let target = match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => parse_target::<TestnetV0>(&target)?,
+        _ => parse_target::<TestnetV0>(&target)?,
+    };
+
+    let storage = PersistentStorage::new()?;
+    storage.save_mixed(
+        vec![
+            &target,
+            &get_network()?,
+            &ReputationSource::User.to_str().to_string(),
+        ],
+        "DELETE FROM reputation_entries WHERE target = ?1 AND network = ?2 AND source = ?3"
+            .to_string(),
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_reputation_entries() -> AvailResult<Vec<ReputationEntry>> {
+    query_reputation_entries("", &[])
+}
+
+/// Reputation of program ids and addresses, lets the frontend flag them before a request is made
+#[tauri::command(rename_all = "snake_case")]
+pub fn check_reputation(targets: Vec<String>) -> AvailResult<Vec<ReputationEntry>> {
+    let parse = match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => parse_target::<TestnetV0>,
+        _ => parse_target::<TestnetV0>,
+    };
+
+    let targets = targets
+        .iter()
+        .map(|target| parse(target))
+        .collect::<AvailResult<Vec<String>>>()?;
+
+    get_reputation(&targets)
+}
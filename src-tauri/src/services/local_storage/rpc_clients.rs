@@ -0,0 +1,156 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::storage::persistent::PersistentStorage;
+use crate::services::local_storage::{
+    dapp_sessions::{normalize_origin, revoke_dapp_session},
+    persistent_storage::get_network,
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// A local application paired with the rpc server
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RpcClient {
+    pub origin: String,
+    pub name: String,
+    pub created_at: i64,
+}
+
+pub fn init_rpc_clients_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS rpc_clients (
+            token_hash TEXT PRIMARY KEY,
+            network TEXT NOT NULL,
+            origin TEXT NOT NULL,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_rpc_clients_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    match storage.execute_query("DROP TABLE IF EXISTS rpc_clients") {
+        Ok(r) => r,
+        Err(e) => match e.error_type {
+            AvailErrorType::NotFound => {}
+            _ => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    e.internal_msg,
+                    "Error deleting rpc clients table".to_string(),
+                ))
+            }
+        },
+    };
+
+    Ok(())
+}
+
+/// Only the hash of a pairing token is stored
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Stores a paired client and returns the token it authenticates with
+pub fn store_rpc_client(origin: &str, name: &str) -> AvailResult<String> {
+    init_rpc_clients_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let token = hex::encode(rand::random::<[u8; 32]>());
+
+    storage.save_mixed(
+        vec![
+            &token_hash(&token),
+            &get_network()?,
+            &normalize_origin(origin)?,
+            &name.to_string(),
+            &Utc::now().timestamp().to_string(),
+        ],
+        "INSERT INTO rpc_clients (token_hash, network, origin, name, created_at) VALUES (?1, ?2, ?3, ?4, ?5)".to_string(),
+    )?;
+
+    Ok(token)
+}
+
+fn row_to_rpc_client(row: &[String]) -> Option<RpcClient> {
+    Some(RpcClient {
+        origin: row.first()?.clone(),
+        name: row.get(1)?.clone(),
+        created_at: row.get(2)?.parse::<i64>().ok()?,
+    })
+}
+
+fn query_rpc_clients(condition: &str) -> AvailResult<Vec<RpcClient>> {
+    init_rpc_clients_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT origin, name, created_at FROM rpc_clients WHERE network='{}' {}",
+        get_network()?,
+        condition
+    );
+    let res = storage.get_all::<String>(&query, 3)?;
+
+    Ok(res.iter().filter_map(|row| row_to_rpc_client(row)).collect())
+}
+
+pub fn get_rpc_client_by_token(token: &str) -> AvailResult<Option<RpcClient>> {
+    let clients = query_rpc_clients(&format!("AND token_hash='{}'", token_hash(token)))?;
+
+    Ok(clients.into_iter().next())
+}
+
+/// Origins with a paired client, the only origins browsers may read responses for
+pub fn get_rpc_client_origins() -> AvailResult<Vec<String>> {
+    let mut origins = query_rpc_clients("ORDER BY origin ASC")?
+        .into_iter()
+        .map(|client| client.origin)
+        .collect::<Vec<String>>();
+    origins.dedup();
+
+    Ok(origins)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_rpc_clients() -> AvailResult<Vec<RpcClient>> {
+    query_rpc_clients("ORDER BY created_at DESC")
+}
+
+/// Removes every token of a client and revokes its dapp session
+#[tauri::command(rename_all = "snake_case")]
+pub fn revoke_rpc_client(origin: String) -> AvailResult<()> {
+    init_rpc_clients_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let origin = normalize_origin(&origin)?;
+    storage.save_mixed(
+        vec![&origin, &get_network()?],
+        "DELETE FROM rpc_clients WHERE origin=?1 AND network=?2".to_string(),
+    )?;
+
+    match revoke_dapp_session(origin) {
+        Ok(_) => Ok(()),
+        Err(e) if e.error_type == AvailErrorType::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod rpc_clients_tests {
+    use super::*;
+
+    #[test]
+    fn test_token_hash() {
+        let hash = token_hash("token");
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, token_hash("token"));
+        assert_ne!(hash, token_hash("other token"));
+    }
+}
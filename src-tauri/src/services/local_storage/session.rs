@@ -1,2 +1,4 @@
+pub mod passphrase;
 pub mod password;
+pub mod policy;
 pub mod view;
@@ -0,0 +1,43 @@
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+
+/// Seed passphrase the user entered in this session, it is never written to disk unless the user opted in
+pub struct PassphraseSession {
+    passphrase: RwLock<Option<String>>,
+}
+
+impl PassphraseSession {
+    pub fn new() -> Self {
+        Self {
+            passphrase: RwLock::new(None),
+        }
+    }
+
+    pub fn set_passphrase_session(&self, passphrase: &str) -> AvailResult<()> {
+        let mut passphrase_lock = self.passphrase.write().unwrap();
+        *passphrase_lock = Some(passphrase.to_string());
+        Ok(())
+    }
+
+    pub fn clear_session(&self) -> AvailResult<()> {
+        let mut passphrase_lock = self.passphrase.write().unwrap();
+        *passphrase_lock = None;
+        Ok(())
+    }
+
+    pub fn get_instance(&self) -> AvailResult<String> {
+        let passphrase_lock = self.passphrase.read().unwrap();
+        match &*passphrase_lock {
+            Some(passphrase) => Ok(passphrase.to_owned()),
+            None => Err(AvailError::new(
+                AvailErrorType::Unauthorized,
+                "Seed passphrase is not unlocked".to_string(),
+                "Enter your seed passphrase to continue.".to_string(),
+            )),
+        }
+    }
+}
+
+pub static PASSPHRASE: Lazy<Arc<PassphraseSession>> =
+    Lazy::new(|| Arc::new(PassphraseSession::new()));
@@ -28,6 +28,12 @@ impl PassSession {
         Ok(())
     }
 
+    pub fn clear_session(&self) -> AvailResult<()> {
+        let mut password_lock = self.password.write().unwrap();
+        *password_lock = None;
+        Ok(())
+    }
+
     pub fn extend_session(&self) -> AvailResult<()> {
         let mut expiration_lock = self.expiration.write().unwrap();
         // Extend expiration to 5 minutes from now
@@ -0,0 +1,52 @@
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+
+/// Password that signs requests a spending policy auto-approves, it is kept while the app runs
+/// so auto-approval doesn't depend on the password session being unlocked
+pub struct PolicySession {
+    password: RwLock<Option<String>>,
+}
+
+impl PolicySession {
+    pub fn new() -> Self {
+        Self {
+            password: RwLock::new(None),
+        }
+    }
+
+    pub fn set_policy_session(&self, password: &str) -> AvailResult<()> {
+        let mut password_lock = self.password.write().unwrap();
+        *password_lock = Some(password.to_string());
+        Ok(())
+    }
+
+    /// Replaces the password when the session is armed, used when the wallet password changes
+    pub fn update_password(&self, password: &str) -> AvailResult<()> {
+        let mut password_lock = self.password.write().unwrap();
+        if password_lock.is_some() {
+            *password_lock = Some(password.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn clear_session(&self) -> AvailResult<()> {
+        let mut password_lock = self.password.write().unwrap();
+        *password_lock = None;
+        Ok(())
+    }
+
+    pub fn get_instance(&self) -> AvailResult<String> {
+        let password_lock = self.password.read().unwrap();
+        match &*password_lock {
+            Some(password) => Ok(password.to_owned()),
+            None => Err(AvailError::new(
+                AvailErrorType::Unauthorized,
+                "Spending policies are not unlocked".to_string(),
+                "Unauthorized, please reauthenticate.".to_string(),
+            )),
+        }
+    }
+}
+
+pub static POLICY_PASS: Lazy<Arc<PolicySession>> = Lazy::new(|| Arc::new(PolicySession::new()));
@@ -1,20 +1,54 @@
 use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
 use once_cell::sync::Lazy;
 use std::{
+    collections::HashMap,
     str::FromStr,
     sync::{Arc, RwLock},
 };
 
 use snarkvm::prelude::{Network, ViewKey};
 
+use crate::services::local_storage::accounts::request_account;
+
 pub struct ViewSession {
     view_key: RwLock<Option<String>>,
+    /// View keys of the derived accounts unlocked in this session, keyed by address
+    accounts: RwLock<HashMap<String, String>>,
 }
 
 impl ViewSession {
     pub fn new() -> Self {
         Self {
             view_key: RwLock::new(None),
+            accounts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Forgets every view key, the wallet has to be unlocked again
+    pub fn clear_session(&self) -> AvailResult<()> {
+        let mut view_key_lock = self.view_key.write().unwrap();
+        let mut accounts_lock = self.accounts.write().unwrap();
+        *view_key_lock = None;
+        accounts_lock.clear();
+        Ok(())
+    }
+
+    pub fn set_account_view_key(&self, address: &str, view_key: &str) -> AvailResult<()> {
+        let mut accounts_lock = self.accounts.write().unwrap();
+        accounts_lock.insert(address.to_string(), view_key.to_string());
+        Ok(())
+    }
+
+    /// View key of an account unlocked in this session, switching to it doesn't need the password
+    pub fn get_account_instance<N: Network>(&self, address: &str) -> AvailResult<ViewKey<N>> {
+        let accounts_lock = self.accounts.read().unwrap();
+        match accounts_lock.get(address) {
+            Some(view_key) => Ok(ViewKey::<N>::from_str(view_key)?),
+            None => Err(AvailError::new(
+                AvailErrorType::Unauthorized,
+                format!("View key of {} is not unlocked", address),
+                "Unauthorized, please reauthenticate.".to_string(),
+            )),
         }
     }
 
@@ -24,7 +58,12 @@ impl ViewSession {
         Ok(())
     }
 
+    /// View key of the active account, or of the account the current request acts as
     pub fn get_instance<N: Network>(&self) -> AvailResult<ViewKey<N>> {
+        if let Some(row) = request_account() {
+            return self.get_account_instance::<N>(&row.account.address);
+        }
+
         let view_key_lock = self.view_key.read().unwrap();
         let view_key = match &*view_key_lock {
             Some(view_key) => view_key,
@@ -0,0 +1,788 @@
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use snarkvm::prelude::{Network, TestnetV0};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+};
+
+use crate::models::storage::persistent::PersistentStorage;
+use crate::models::wallet_connect::{
+    create_event::CreateEventRequest,
+    policy::{PolicyDecision, SpendingPolicy},
+};
+use crate::services::local_storage::{
+    dapp_sessions::normalize_origin,
+    persistent_storage::get_network,
+    session::policy::POLICY_PASS,
+    token_metadata::{format_amount, token_decimals},
+    utils::get_private_key,
+};
+use crate::services::record_handling::{known_calls::known_call, token_registry::TOKEN_REGISTRY};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::{encrypted_data::EventTypeCommon, network::SupportedNetworks},
+};
+
+/// Key of the policy that applies to every dapp
+const GLOBAL_POLICY: &str = "*";
+
+/// Window daily limits are counted over, in seconds
+const DAILY_WINDOW: i64 = 24 * 60 * 60;
+
+pub fn init_spending_policy_tables() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS spending_policies (
+            origin TEXT NOT NULL,
+            network TEXT NOT NULL,
+            policy TEXT NOT NULL,
+            PRIMARY KEY (origin, network)
+        )",
+    )?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS policy_spending (
+            origin TEXT NOT NULL,
+            network TEXT NOT NULL,
+            asset TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+    )?;
+
+    Ok(())
+}
+
+pub fn drop_spending_policy_tables() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    for table in ["spending_policies", "policy_spending"] {
+        match storage.execute_query(&format!("DROP TABLE IF EXISTS {}", table)) {
+            Ok(r) => r,
+            Err(e) => match e.error_type {
+                AvailErrorType::NotFound => {}
+                _ => {
+                    return Err(AvailError::new(
+                        AvailErrorType::Internal,
+                        e.internal_msg,
+                        "Error deleting spending policies".to_string(),
+                    ))
+                }
+            },
+        };
+    }
+
+    Ok(())
+}
+
+fn policy_key(origin: Option<&String>) -> AvailResult<String> {
+    match origin {
+        Some(origin) => normalize_origin(origin),
+        None => Ok(GLOBAL_POLICY.to_string()),
+    }
+}
+
+fn query_spending_policies(condition: &str) -> AvailResult<Vec<SpendingPolicy>> {
+    init_spending_policy_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT policy FROM spending_policies WHERE network='{}' {}",
+        get_network()?,
+        condition
+    );
+    let res = storage.get_all::<String>(&query, 1)?;
+
+    Ok(res
+        .iter()
+        .filter_map(|row| serde_json::from_str::<SpendingPolicy>(row.first()?).ok())
+        .collect())
+}
+
+pub fn get_spending_policy(origin: Option<&String>) -> AvailResult<Option<SpendingPolicy>> {
+    let key = policy_key(origin)?;
+    let policies = query_spending_policies(&format!("AND origin='{}'", key.replace('\'', "''")))?;
+
+    Ok(policies.into_iter().next())
+}
+
+/// Spending a request is about to make, it counts towards the daily limits until it is committed or released
+struct Reservation {
+    origin: String,
+    network: String,
+    spending: HashMap<String, f64>,
+}
+
+/// Reserved spending of requests being executed, keyed by reservation id.
+/// Requests are evaluated and reserved under its lock so concurrent requests can't pass the same limit.
+static RESERVED: Lazy<Mutex<HashMap<u64, Reservation>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_RESERVATION: AtomicU64 = AtomicU64::new(0);
+
+/// Spending reserved for a request, it is recorded by [`PolicyReservation::commit`] once the request
+/// was broadcast and released when it is dropped without being committed
+pub struct PolicyReservation {
+    id: Option<u64>,
+}
+
+impl PolicyReservation {
+    fn none() -> Self {
+        Self { id: None }
+    }
+
+    /// Records the reserved spending so it counts towards the daily limits
+    pub fn commit(mut self) -> AvailResult<()> {
+        let id = match self.id.take() {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        let mut reserved = RESERVED.lock().unwrap();
+        let recorded = match reserved.get(&id) {
+            Some(reservation) => record_spending(reservation),
+            None => Ok(()),
+        };
+        reserved.remove(&id);
+
+        recorded
+    }
+}
+
+impl Drop for PolicyReservation {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            if let Ok(mut reserved) = RESERVED.lock() {
+                reserved.remove(&id);
+            }
+        }
+    }
+}
+
+fn reserve(
+    reserved: &mut HashMap<u64, Reservation>,
+    origin: Option<&String>,
+    spending: HashMap<String, f64>,
+) -> AvailResult<PolicyReservation> {
+    let origin = match origin {
+        Some(origin) => normalize_origin(origin)?,
+        None => return Ok(PolicyReservation::none()),
+    };
+
+    let id = NEXT_RESERVATION.fetch_add(1, Ordering::Relaxed);
+    reserved.insert(
+        id,
+        Reservation {
+            origin,
+            network: get_network()?,
+            spending,
+        },
+    );
+
+    Ok(PolicyReservation { id: Some(id) })
+}
+
+/// Spending in the last 24 hours per asset, of one dapp or of every dapp, including reserved spending
+fn spent_today(
+    origin: Option<&String>,
+    reserved: &HashMap<u64, Reservation>,
+) -> AvailResult<HashMap<String, f64>> {
+    init_spending_policy_tables()?;
+    let storage = PersistentStorage::new()?;
+    let network = get_network()?;
+
+    let mut query = format!(
+        "SELECT asset, amount FROM policy_spending WHERE network='{}' AND CAST(created_at AS INTEGER) > {}",
+        network,
+        Utc::now().timestamp() - DAILY_WINDOW
+    );
+    if let Some(origin) = origin {
+        query.push_str(&format!(" AND origin='{}'", origin.replace('\'', "''")));
+    }
+
+    let res = storage.get_all::<String>(&query, 2)?;
+
+    let mut spent: HashMap<String, f64> = HashMap::new();
+    for row in res {
+        if let (Some(asset), Some(amount)) = (row.first(), row.get(1)) {
+            if let Ok(amount) = amount.parse::<f64>() {
+                *spent.entry(asset.clone()).or_insert(0.0) += amount;
+            }
+        }
+    }
+
+    for reservation in reserved.values().filter(|reservation| {
+        reservation.network == network
+            && origin.map_or(true, |origin| &reservation.origin == origin)
+    }) {
+        spent = add_pending(spent, &reservation.spending);
+    }
+
+    Ok(spent)
+}
+
+/// Records what a dapp request spent so it counts towards the daily limits
+fn record_spending(reservation: &Reservation) -> AvailResult<()> {
+    init_spending_policy_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    let created_at = Utc::now().timestamp().to_string();
+
+    for (asset, value) in &reservation.spending {
+        if *value <= 0.0 {
+            continue;
+        }
+
+        storage.save_mixed(
+            vec![&reservation.origin, &reservation.network, asset, &value.to_string(), &created_at],
+            "INSERT INTO policy_spending (origin, network, asset, amount, created_at) VALUES (?1, ?2, ?3, ?4, ?5)".to_string(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Adds spending that is about to happen to what was already spent
+fn add_pending(
+    mut spent: HashMap<String, f64>,
+    pending: &HashMap<String, f64>,
+) -> HashMap<String, f64> {
+    for (asset, amount) in pending {
+        *spent.entry(asset.clone()).or_insert(0.0) += amount;
+    }
+
+    spent
+}
+
+/// Asset and display amount a call transfers out of the wallet.
+/// Only calls of known token functions are counted, their amount is read by its position in the abi.
+fn call_spend<N: Network>(
+    program_id: &str,
+    function_id: &str,
+    inputs: &[String],
+) -> Option<(String, f64)> {
+    let call = known_call::<N>(program_id, function_id, inputs)?;
+    if !call.abi.spends {
+        return None;
+    }
+
+    let asset = match program_id {
+        TOKEN_REGISTRY => call.asset_id.clone(),
+        _ => program_id.to_string(),
+    };
+
+    Some((
+        asset,
+        format_amount(call.amount?, token_decimals::<N>(&call.asset_id)),
+    ))
+}
+
+/// Everything a request spends per asset, the fee counts towards `credits.aleo`
+fn request_spending(spend: &Option<(String, f64)>, fee: f64) -> HashMap<String, f64> {
+    let mut spending = HashMap::from([("credits.aleo".to_string(), fee)]);
+    if let Some((asset, amount)) = spend {
+        *spending.entry(asset.clone()).or_insert(0.0) += amount;
+    }
+
+    spending
+}
+
+/// Decides how a request is approved, every policy that applies to the origin has to allow it
+pub fn evaluate_policies(
+    origin: Option<&String>,
+    program_id: &str,
+    function_id: &str,
+    spend: Option<(&str, f64)>,
+    fee: f64,
+) -> AvailResult<PolicyDecision> {
+    let reserved = RESERVED.lock().unwrap();
+    evaluate_policies_with_pending(
+        origin,
+        program_id,
+        function_id,
+        spend,
+        fee,
+        &HashMap::new(),
+        &reserved,
+    )
+}
+
+/// Like [`evaluate_policies`], counting `pending` spending of the same call towards the daily limits
+fn evaluate_policies_with_pending(
+    origin: Option<&String>,
+    program_id: &str,
+    function_id: &str,
+    spend: Option<(&str, f64)>,
+    fee: f64,
+    pending: &HashMap<String, f64>,
+    reserved: &HashMap<u64, Reservation>,
+) -> AvailResult<PolicyDecision> {
+    let origin = match origin {
+        Some(origin) => normalize_origin(origin)?,
+        None => return Ok(PolicyDecision::Prompt),
+    };
+
+    let policies = [
+        (get_spending_policy(Some(&origin))?, Some(&origin)),
+        (get_spending_policy(None)?, None),
+    ];
+
+    if policies.iter().all(|(policy, _)| policy.is_none()) {
+        return Ok(PolicyDecision::Prompt);
+    }
+
+    for (policy, spent_origin) in policies
+        .iter()
+        .filter_map(|(policy, spent_origin)| Some((policy.as_ref()?, *spent_origin)))
+    {
+        // a dapp policy counts the dapp's own spending, the global policy counts every dapp's
+        let spent = add_pending(spent_today(spent_origin, reserved)?, pending);
+        if let Err(reason) = policy.check(program_id, function_id, spend, fee, &spent) {
+            return Ok(PolicyDecision::Reauthenticate(reason));
+        }
+    }
+
+    Ok(PolicyDecision::AutoApprove)
+}
+
+fn evaluate_deployment_with(
+    origin: Option<&String>,
+    program_id: &str,
+    total_cost: f64,
+    reserved: &HashMap<u64, Reservation>,
+) -> AvailResult<PolicyDecision> {
+    match evaluate_policies_with_pending(
+        origin,
+        program_id,
+        "",
+        None,
+        total_cost,
+        &HashMap::new(),
+        reserved,
+    )? {
+        PolicyDecision::AutoApprove => Ok(PolicyDecision::Reauthenticate(
+            "Deployments are not covered by spending policies".to_string(),
+        )),
+        decision => Ok(decision),
+    }
+}
+
+/// Decision for a deployment of the given total cost in credits, deployments are never auto-approved
+pub fn evaluate_deployment(
+    origin: Option<&String>,
+    program_id: &str,
+    total_cost: f64,
+) -> AvailResult<PolicyDecision> {
+    let reserved = RESERVED.lock().unwrap();
+    evaluate_deployment_with(origin, program_id, total_cost, &reserved)
+}
+
+/// Decides a deployment and reserves its total cost
+pub fn reserve_deployment(
+    origin: Option<&String>,
+    program_id: &str,
+    total_cost: f64,
+) -> AvailResult<(PolicyDecision, PolicyReservation)> {
+    let mut reserved = RESERVED.lock().unwrap();
+    let decision = evaluate_deployment_with(origin, program_id, total_cost, &reserved)?;
+    let reservation = reserve(&mut reserved, origin, request_spending(&None, total_cost))?;
+
+    Ok((decision, reservation))
+}
+
+fn evaluate_request_with(
+    origin: Option<&String>,
+    request: &CreateEventRequest,
+    spend: &Option<(String, f64)>,
+    reserved: &HashMap<u64, Reservation>,
+) -> AvailResult<PolicyDecision> {
+    if request.event_type() == &EventTypeCommon::Deploy {
+        return evaluate_deployment_with(origin, request.program_id(), 0.0, reserved);
+    }
+
+    evaluate_policies_with_pending(
+        origin,
+        request.program_id(),
+        request.function_id(),
+        spend
+            .as_ref()
+            .map(|(asset, amount)| (asset.as_str(), *amount)),
+        request.fee(),
+        &HashMap::new(),
+        reserved,
+    )
+}
+
+/// Decision for a wallet connect request.
+/// The cost of a deployment is only known once it is estimated, so it is decided by [`evaluate_deployment`] before signing.
+pub fn evaluate_request<N: Network>(
+    origin: Option<&String>,
+    request: &CreateEventRequest,
+) -> AvailResult<PolicyDecision> {
+    let spend = call_spend::<N>(
+        request.program_id(),
+        request.function_id(),
+        request.inputs(),
+    );
+
+    let reserved = RESERVED.lock().unwrap();
+    evaluate_request_with(origin, request, &spend, &reserved)
+}
+
+/// Decides a wallet connect request and reserves what it spends
+pub fn reserve_request<N: Network>(
+    origin: Option<&String>,
+    request: &CreateEventRequest,
+) -> AvailResult<(PolicyDecision, PolicyReservation)> {
+    // programs may be fetched to read the call, so this happens before taking the lock
+    let spend = call_spend::<N>(
+        request.program_id(),
+        request.function_id(),
+        request.inputs(),
+    );
+
+    let mut reserved = RESERVED.lock().unwrap();
+    let decision = evaluate_request_with(origin, request, &spend, &reserved)?;
+    let reservation = reserve(
+        &mut reserved,
+        origin,
+        request_spending(&spend, request.fee()),
+    )?;
+
+    Ok((decision, reservation))
+}
+
+fn evaluate_batch_with(
+    origin: Option<&String>,
+    steps: &[CreateEventRequest],
+    spends: &[Option<(String, f64)>],
+    reserved: &HashMap<u64, Reservation>,
+) -> AvailResult<PolicyDecision> {
+    let mut pending: HashMap<String, f64> = HashMap::new();
+
+    for (step, spend) in steps.iter().zip(spends) {
+        if step.event_type() == &EventTypeCommon::Deploy {
+            return evaluate_request_with(origin, step, spend, reserved);
+        }
+
+        match evaluate_policies_with_pending(
+            origin,
+            step.program_id(),
+            step.function_id(),
+            spend
+                .as_ref()
+                .map(|(asset, amount)| (asset.as_str(), *amount)),
+            step.fee(),
+            &pending,
+            reserved,
+        )? {
+            PolicyDecision::AutoApprove => {}
+            decision => return Ok(decision),
+        }
+
+        pending = add_pending(pending, &request_spending(spend, step.fee()));
+    }
+
+    Ok(PolicyDecision::AutoApprove)
+}
+
+fn step_spends<N: Network>(steps: &[CreateEventRequest]) -> Vec<Option<(String, f64)>> {
+    steps
+        .iter()
+        .map(|step| call_spend::<N>(step.program_id(), step.function_id(), step.inputs()))
+        .collect()
+}
+
+/// Decision for a batch of wallet connect requests, the batch is only auto-approved when every step
+/// is within policy once the spending of the steps before it is counted
+pub fn evaluate_batch<N: Network>(
+    origin: Option<&String>,
+    steps: &[CreateEventRequest],
+) -> AvailResult<PolicyDecision> {
+    let spends = step_spends::<N>(steps);
+
+    let reserved = RESERVED.lock().unwrap();
+    evaluate_batch_with(origin, steps, &spends, &reserved)
+}
+
+/// Decides a batch and reserves the spending of every step, the reservations are in step order
+pub fn reserve_batch<N: Network>(
+    origin: Option<&String>,
+    steps: &[CreateEventRequest],
+) -> AvailResult<(PolicyDecision, Vec<PolicyReservation>)> {
+    let spends = step_spends::<N>(steps);
+
+    let mut reserved = RESERVED.lock().unwrap();
+    let decision = evaluate_batch_with(origin, steps, &spends, &reserved)?;
+    let reservations = steps
+        .iter()
+        .zip(&spends)
+        .map(|(step, spend)| reserve(&mut reserved, origin, request_spending(spend, step.fee())))
+        .collect::<AvailResult<Vec<_>>>()?;
+
+    Ok((decision, reservations))
+}
+
+/// Unlocks auto-approval with the wallet password when spending policies exist,
+/// so auto-approved requests are signed without asking for the password again
+pub fn arm_policy_session<N: Network>(password: &str) -> AvailResult<()> {
+    if query_spending_policies("")?.is_empty() {
+        return Ok(());
+    }
+
+    get_private_key::<N>(Some(password.to_string()))?;
+    POLICY_PASS.set_policy_session(password)
+}
+
+/// Lets the wallet connect screens skip confirmation for requests within policy
+#[tauri::command(rename_all = "snake_case")]
+pub fn check_spending_policy(
+    origin: Option<String>,
+    request: CreateEventRequest,
+) -> AvailResult<PolicyDecision> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => evaluate_request::<TestnetV0>(origin.as_ref(), &request),
+        _ => evaluate_request::<TestnetV0>(origin.as_ref(), &request),
+    }
+}
+
+/// Batch counterpart of [`check_spending_policy`]
+#[tauri::command(rename_all = "snake_case")]
+pub fn check_batch_spending_policy(
+    origin: Option<String>,
+    steps: Vec<CreateEventRequest>,
+) -> AvailResult<PolicyDecision> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => evaluate_batch::<TestnetV0>(origin.as_ref(), &steps),
+        _ => evaluate_batch::<TestnetV0>(origin.as_ref(), &steps),
+    }
+}
+
+/// Stores a policy, replacing the earlier policy of its origin.
+/// The password unlocks auto-approval of the requests the policy allows.
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_spending_policy(policy: SpendingPolicy, password: String) -> AvailResult<()> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => get_private_key::<TestnetV0>(Some(password.clone()))?,
+        _ => get_private_key::<TestnetV0>(Some(password.clone()))?,
+    };
+
+    save_spending_policy(policy)?;
+
+    POLICY_PASS.set_policy_session(&password)
+}
+
+/// Stores a policy under its normalized origin, replacing the earlier policy of the origin
+pub fn save_spending_policy(policy: SpendingPolicy) -> AvailResult<()> {
+    init_spending_policy_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    let key = policy_key(policy.origin.as_ref())?;
+    let policy = SpendingPolicy {
+        origin: policy.origin.as_ref().map(|_| key.clone()),
+        ..policy
+    };
+
+    storage.save_mixed(
+        vec![&key, &get_network()?, &serde_json::to_string(&policy)?],
+        "INSERT OR REPLACE INTO spending_policies (origin, network, policy) VALUES (?1, ?2, ?3)"
+            .to_string(),
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_spending_policies() -> AvailResult<Vec<SpendingPolicy>> {
+    query_spending_policies("ORDER BY origin ASC")
+}
+
+/// Removes the policy of a dapp, or the global policy when no origin is given
+#[tauri::command(rename_all = "snake_case")]
+pub fn remove_spending_policy(origin: Option<String>) -> AvailResult<()> {
+    init_spending_policy_tables()?;
+    let storage = PersistentStorage::new()?;
+
+    let key = policy_key(origin.as_ref())?;
+    storage.save_mixed(
+        vec![&key, &get_network()?],
+        "DELETE FROM spending_policies WHERE origin=?1 AND network=?2".to_string(),
+    )?;
+
+    // nothing is auto-approved anymore, so the password isn't kept
+    if query_spending_policies("")?.is_empty() {
+        POLICY_PASS.clear_session()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod spending_policy_tests {
+    use super::*;
+    use crate::models::wallet_connect::policy::AllowedCall;
+
+    fn game_policy() -> SpendingPolicy {
+        SpendingPolicy::new(
+            Some("https://game.example".to_string()),
+            Some(1.0),
+            HashMap::from([
+                ("credits.aleo".to_string(), 2.0),
+                ("game_token.aleo".to_string(), 10.0),
+            ]),
+            vec![
+                AllowedCall {
+                    program_id: "game.aleo".to_string(),
+                    function_id: Some("move".to_string()),
+                },
+                AllowedCall {
+                    program_id: "game_token.aleo".to_string(),
+                    function_id: None,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_policy_allowed_calls() {
+        let policy = game_policy();
+        let spent = HashMap::new();
+        let tokens = Some(("game_token.aleo", 0.5));
+
+        assert!(policy.check("game.aleo", "move", None, 0.1, &spent).is_ok());
+        assert!(policy
+            .check("game.aleo", "withdraw", None, 0.1, &spent)
+            .is_err());
+        assert!(policy
+            .check("game_token.aleo", "transfer_public", tokens, 0.1, &spent)
+            .is_ok());
+        assert!(policy
+            .check(
+                "credits.aleo",
+                "transfer_public",
+                Some(("credits.aleo", 0.5)),
+                0.1,
+                &spent
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_policy_limits() {
+        let policy = game_policy();
+
+        let spent = HashMap::from([("credits.aleo".to_string(), 1.95)]);
+        assert!(policy
+            .check("game.aleo", "move", None, 0.1, &spent)
+            .is_err());
+
+        let spent = HashMap::from([("game_token.aleo".to_string(), 9.5)]);
+        assert!(policy
+            .check(
+                "game_token.aleo",
+                "transfer_public",
+                Some(("game_token.aleo", 0.6)),
+                0.1,
+                &spent
+            )
+            .is_err());
+
+        let spent = HashMap::new();
+        assert!(policy
+            .check(
+                "game_token.aleo",
+                "transfer_public",
+                Some(("game_token.aleo", 1.5)),
+                0.1,
+                &spent
+            )
+            .is_err());
+
+        // credits moved by an allowed call count towards the credits limit
+        let spent = HashMap::from([("credits.aleo".to_string(), 1.0)]);
+        assert!(policy
+            .check(
+                "game.aleo",
+                "move",
+                Some(("credits.aleo", 0.95)),
+                0.1,
+                &spent
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_batch_pending_spending() {
+        let policy = game_policy();
+
+        let spent = HashMap::from([("credits.aleo".to_string(), 1.0)]);
+        let pending = HashMap::from([
+            ("credits.aleo".to_string(), 0.5),
+            ("game_token.aleo".to_string(), 9.5),
+        ]);
+        let spent = add_pending(spent, &pending);
+
+        assert_eq!(spent.get("credits.aleo"), Some(&1.5));
+        assert!(policy.check("game.aleo", "move", None, 0.1, &spent).is_ok());
+        assert!(policy
+            .check(
+                "game_token.aleo",
+                "transfer_public",
+                Some(("game_token.aleo", 0.6)),
+                0.1,
+                &spent
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_call_spend_by_abi() {
+        let recipient = "aleo1dg722m22fzpz6xjdrvl9tzu5t68zmypj5p74khlqcac0gvednygqxaax0j";
+
+        let inputs = vec![recipient.to_string(), "1500000u64".to_string()];
+        assert_eq!(
+            call_spend::<TestnetV0>("credits.aleo", "transfer_public", &inputs),
+            Some(("credits.aleo".to_string(), 1.5))
+        );
+
+        // the amount isn't guessed from the function name
+        assert_eq!(
+            call_spend::<TestnetV0>("credits.aleo", "transfer_public", &inputs[..1]),
+            None
+        );
+        assert_eq!(
+            call_spend::<TestnetV0>("game.aleo", "transfer_public", &inputs),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reservations_count_until_released() {
+        let origin = normalize_origin("https://reserve.example").unwrap();
+        let spending = HashMap::from([("credits.aleo".to_string(), 1.5)]);
+
+        let reservation = {
+            let mut reserved = RESERVED.lock().unwrap();
+            reserve(&mut reserved, Some(&origin), spending).unwrap()
+        };
+
+        {
+            let reserved = RESERVED.lock().unwrap();
+            let spent = spent_today(Some(&origin), &reserved).unwrap();
+            assert_eq!(spent.get("credits.aleo"), Some(&1.5));
+        }
+
+        drop(reservation);
+
+        let reserved = RESERVED.lock().unwrap();
+        let spent = spent_today(Some(&origin), &reserved).unwrap();
+        assert_eq!(spent.get("credits.aleo"), None);
+    }
+
+    #[test]
+    fn test_wallet_requests_prompt() {
+        assert_eq!(
+            evaluate_policies(None, "game.aleo", "move", None, 0.1).unwrap(),
+            PolicyDecision::Prompt
+        );
+    }
+}
@@ -17,6 +17,7 @@ use crate::services::local_storage::encrypted_data::{
 };
 use crate::services::local_storage::persistent_storage::get_address_string;
 use crate::services::local_storage::tokens::{add_balance, subtract_balance};
+use crate::services::record_handling::token_registry::token_balance_name;
 use crate::services::local_storage::{
     encrypted_data::{get_encrypted_data_by_flavour, store_encrypted_data},
     persistent_storage::{get_address, get_network},
@@ -229,6 +230,19 @@ pub fn get_record_pointers_for_record_type<N: Network>(
 
 /* Utilities */
 
+/// Amount of a record balance entry, registry tokens hold u128 amounts
+fn entry_balance<N: Network>(entry: &Entry<N, Plaintext<N>>) -> u128 {
+    match entry {
+        Entry::Private(Plaintext::Literal(Literal::<N>::U64(amount), _))
+        | Entry::Public(Plaintext::Literal(Literal::<N>::U64(amount), _))
+        | Entry::Constant(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount as u128,
+        Entry::Private(Plaintext::Literal(Literal::<N>::U128(amount), _))
+        | Entry::Public(Plaintext::Literal(Literal::<N>::U128(amount), _))
+        | Entry::Constant(Plaintext::Literal(Literal::<N>::U128(amount), _)) => **amount,
+        _ => 0,
+    }
+}
+
 /// Update record spent status on local storage via nonce
 pub fn update_record_spent_local_via_nonce<N: Network>(
     nonce: &str,
@@ -269,7 +283,11 @@ pub fn update_record_spent_local_via_nonce<N: Network>(
         {
             let record = record_pointer.clone().to_record()?;
             let record_data_keys = record.data().clone().into_keys();
-            let record_name = record_pointer.clone().metadata.name;
+            let record_name = token_balance_name::<N>(
+                &record_pointer.metadata.name,
+                &record_pointer.metadata.program_id,
+                &record,
+            );
             for key in record_data_keys {
                 let is_key: bool = matches!(key.to_string().as_str(), "amount" | "microcredits");
 
@@ -279,20 +297,7 @@ pub fn update_record_spent_local_via_nonce<N: Network>(
                         None => Err(()),
                     };
 
-                    let balance = match balance_entry.unwrap() {
-                        Entry::Private(Plaintext::Literal(Literal::<N>::U64(amount), _)) => {
-                            **amount
-                        }
-                        Entry::Public(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                        Entry::Constant(Plaintext::Literal(Literal::<N>::U64(amount), _)) => {
-                            **amount
-                        }
-
-                        Entry::Private(Plaintext::Literal(Literal::<N>::U128(amount), _)) => {
-                            (**amount).try_into()?
-                        }
-                        _ => 0u64,
-                    };
+                    let balance = entry_balance(balance_entry.unwrap());
 
                     let _ = match spent {
                         true => subtract_balance(&record_name, &balance.to_string(), v_key)?,
@@ -324,6 +329,20 @@ pub fn check_if_record_exists<N: Network>(nonce: &str) -> AvailResult<bool> {
     }
 }
 
+/// Spent status of a stored record, `None` when the record isn't stored in the wallet
+pub fn get_record_spent_local<N: Network>(nonce: &str) -> AvailResult<Option<bool>> {
+    let encrypted_data = match get_encrypted_data_by_nonce(nonce)? {
+        Some(encrypted_data) => encrypted_data,
+        None => return Ok(None),
+    };
+
+    let v_key = VIEWSESSION.get_instance::<N>()?;
+    let encrypted_struct = encrypted_data.to_enrypted_struct::<N>()?;
+    let record_pointer: AvailRecord<N> = encrypted_struct.decrypt(v_key)?;
+
+    Ok(Some(record_pointer.metadata.spent))
+}
+
 /// Update record spent status on local storage
 pub fn update_record_spent_local<N: Network>(id: &str, spent: bool) -> AvailResult<()> {
     let address = get_address::<N>()?;
@@ -346,7 +365,11 @@ pub fn update_record_spent_local<N: Network>(id: &str, spent: bool) -> AvailResu
     {
         let record = record_pointer.clone().to_record()?;
         let record_data_keys = record.data().clone().into_keys();
-        let record_name = record_pointer.clone().metadata.name;
+        let record_name = token_balance_name::<N>(
+            &record_pointer.metadata.name,
+            &record_pointer.metadata.program_id,
+            &record,
+        );
         for key in record_data_keys {
             let is_key: bool = matches!(key.to_string().as_str(), "amount" | "microcredits");
 
@@ -356,15 +379,7 @@ pub fn update_record_spent_local<N: Network>(id: &str, spent: bool) -> AvailResu
                     None => Err(()),
                 };
 
-                let balance = match balance_entry.unwrap() {
-                    Entry::Private(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Public(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Constant(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Private(Plaintext::Literal(Literal::<N>::U128(amount), _)) => {
-                        (**amount).try_into()?
-                    }
-                    _ => 0u64,
-                };
+                let balance = entry_balance(balance_entry.unwrap());
 
                 let _ = match spent {
                     true => subtract_balance(&record_name, &balance.to_string(), v_key)?,
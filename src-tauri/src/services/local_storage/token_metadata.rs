@@ -0,0 +1,328 @@
+use sha2::{Digest, Sha256};
+use snarkvm::prelude::Network;
+use tauri_plugin_http::reqwest;
+
+use crate::models::storage::persistent::PersistentStorage;
+use crate::models::token::TokenMetadata;
+use crate::services::local_storage::persistent_storage::{
+    get_token_list_source, update_token_list_source,
+};
+use crate::services::record_handling::token_registry::{
+    get_registry_token_metadata, parse_token_id,
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Decimals of aleo credits, used for tokens without known metadata.
+pub const DEFAULT_DECIMALS: u8 = 6;
+
+/// Verified token list shipped with the wallet.
+const BUNDLED_TOKEN_LIST: &str = include_str!("../../../resources/token_list.json");
+
+pub fn init_token_metadata_table() -> AvailResult<()> {
+    let storage = PersistentStorage::new()?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS token_metadata (
+            asset_id TEXT PRIMARY KEY,
+            program_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            symbol TEXT NOT NULL,
+            decimals TEXT NOT NULL,
+            icon TEXT,
+            verified TEXT NOT NULL
+        )",
+    )?;
+    storage.execute_query(
+        "CREATE TABLE IF NOT EXISTS token_metadata_seed (
+            version TEXT PRIMARY KEY
+        )",
+    )?;
+
+    Ok(())
+}
+
+/// Writes the bundled token list into the table when it was not seeded with this version of the list yet.
+/// The version is the hash of the bundled list, so a wallet update with a new list seeds it again.
+pub fn seed_token_metadata() -> AvailResult<()> {
+    init_token_metadata_table()?;
+    let storage = PersistentStorage::new()?;
+
+    let version = hex::encode(Sha256::digest(BUNDLED_TOKEN_LIST.as_bytes()));
+    let seeded = storage.get_all::<String>("SELECT version FROM token_metadata_seed", 1)?;
+    if seeded.iter().any(|row| row.first() == Some(&version)) {
+        return Ok(());
+    }
+
+    for token in bundled_token_list()? {
+        save_token_metadata(&storage, &token, "INSERT OR REPLACE")?;
+    }
+
+    storage.execute_query("DELETE FROM token_metadata_seed")?;
+    storage.save(
+        vec![version],
+        "INSERT INTO token_metadata_seed (version) VALUES (?1)".to_string(),
+    )?;
+
+    Ok(())
+}
+
+fn bundled_token_list() -> AvailResult<Vec<TokenMetadata>> {
+    Ok(serde_json::from_str::<Vec<TokenMetadata>>(
+        BUNDLED_TOKEN_LIST,
+    )?)
+}
+
+fn save_token_metadata(
+    storage: &PersistentStorage,
+    token: &TokenMetadata,
+    insert: &str,
+) -> AvailResult<()> {
+    storage.save_mixed(
+        vec![
+            &token.asset_id,
+            &token.program_id,
+            &token.name,
+            &token.symbol,
+            &token.decimals.to_string(),
+            &token.icon,
+            &token.verified.to_string(),
+        ],
+        format!("{} INTO token_metadata (asset_id, program_id, name, symbol, decimals, icon, verified) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", insert),
+    )
+}
+
+/// Stores metadata of tokens, entries of the bundled list are never replaced
+pub fn store_token_metadata(tokens: &[TokenMetadata]) -> AvailResult<()> {
+    // only the bundled list is stored verified
+    let verified = query_token_metadata("WHERE verified='true'")?;
+    let storage = PersistentStorage::new()?;
+
+    for token in tokens
+        .iter()
+        .filter(|token| !verified.iter().any(|v| v.asset_id == token.asset_id))
+    {
+        save_token_metadata(&storage, token, "INSERT OR REPLACE")?;
+    }
+
+    Ok(())
+}
+
+fn row_to_token_metadata(row: &[Option<String>]) -> Option<TokenMetadata> {
+    Some(TokenMetadata::new(
+        row.first()?.clone()?,
+        row.get(1)?.clone()?,
+        row.get(2)?.clone()?,
+        row.get(3)?.clone()?,
+        row.get(4)?.clone()?.parse::<u8>().ok()?,
+        row.get(5)?.clone(),
+        row.get(6)?.clone()? == "true",
+    ))
+}
+
+fn query_token_metadata(condition: &str) -> AvailResult<Vec<TokenMetadata>> {
+    seed_token_metadata()?;
+    let storage = PersistentStorage::new()?;
+
+    let query = format!(
+        "SELECT asset_id, program_id, name, symbol, decimals, icon, verified FROM token_metadata {}",
+        condition
+    );
+    let res = storage.get_all::<Option<String>>(&query, 7)?;
+
+    Ok(res
+        .iter()
+        .filter_map(|row| row_to_token_metadata(row))
+        .collect())
+}
+
+pub fn get_token_metadata(asset_id: &str) -> AvailResult<Option<TokenMetadata>> {
+    let tokens = query_token_metadata(&format!(
+        "WHERE asset_id='{}'",
+        asset_id.replace('\'', "''")
+    ))?;
+
+    Ok(tokens.into_iter().next())
+}
+
+/// Metadata of a token, registry tokens that are not known yet are looked up on chain and stored unverified.
+pub fn resolve_token_metadata<N: Network>(asset_id: &str) -> AvailResult<Option<TokenMetadata>> {
+    if let Some(token) = get_token_metadata(asset_id)? {
+        return Ok(Some(token));
+    }
+
+    match parse_token_id::<N>(asset_id) {
+        Some(token_id) => {
+            let token = get_registry_token_metadata::<N>(&token_id)?;
+            store_token_metadata(&[token.clone()])?;
+            Ok(Some(token))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Decimals of the asset, falls back to the credits decimals when the token is unknown.
+pub fn token_decimals<N: Network>(asset_id: &str) -> u8 {
+    match resolve_token_metadata::<N>(asset_id) {
+        Ok(Some(token)) => token.decimals,
+        _ => DEFAULT_DECIMALS,
+    }
+}
+
+/// Decimals of the token a program holds, used where only the program id is known.
+pub fn program_decimals(program_id: &str) -> u8 {
+    let tokens = match query_token_metadata(&format!(
+        "WHERE program_id='{}'",
+        program_id.replace('\'', "''")
+    )) {
+        Ok(tokens) => tokens,
+        Err(_) => return DEFAULT_DECIMALS,
+    };
+
+    // the registry holds many tokens, one program id says nothing about their decimals
+    match tokens.as_slice() {
+        [token] => token.decimals,
+        _ => DEFAULT_DECIMALS,
+    }
+}
+
+/// Converts an on chain integer amount into a display amount.
+pub fn format_amount(amount: u128, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_token_metadata_list() -> AvailResult<Vec<TokenMetadata>> {
+    query_token_metadata("ORDER BY verified DESC, symbol ASC")
+}
+
+/// Sets the url the token list is refreshed from, None only keeps the bundled list
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_token_list_source(source: Option<String>) -> AvailResult<()> {
+    if let Some(source) = &source {
+        if !source.starts_with("https://") && !source.starts_with("http://") {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Invalid token list source {}", source),
+                "Token list source must be an http or https url".to_string(),
+            ));
+        }
+    }
+
+    update_token_list_source(source.as_deref())
+}
+
+/// Fetches the token list from the configured source and stores it as unverified metadata
+#[tauri::command(rename_all = "snake_case")]
+pub async fn refresh_token_metadata() -> AvailResult<Vec<TokenMetadata>> {
+    let source = match get_token_list_source()? {
+        Some(source) => source,
+        None => return get_token_metadata_list(),
+    };
+
+    let res = match reqwest::get(&source).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Network,
+                e.to_string(),
+                "Error fetching token list.".to_string(),
+            ))
+        }
+    };
+
+    let tokens = match res.json::<Vec<TokenMetadata>>().await {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::External,
+                e.to_string(),
+                "Error reading token list.".to_string(),
+            ))
+        }
+    };
+
+    // only the bundled list marks tokens verified, a source can't vouch for the tokens it lists
+    let tokens = tokens
+        .into_iter()
+        .map(|token| TokenMetadata {
+            verified: false,
+            ..token
+        })
+        .collect::<Vec<TokenMetadata>>();
+
+    store_token_metadata(&tokens)?;
+
+    get_token_metadata_list()
+}
+
+#[cfg(test)]
+mod token_metadata_tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_list_parses() {
+        let bundled = serde_json::from_str::<Vec<TokenMetadata>>(BUNDLED_TOKEN_LIST).unwrap();
+        let credits = bundled.iter().find(|token| token.asset_id == "credits");
+
+        assert_eq!(credits.unwrap().decimals, DEFAULT_DECIMALS);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1_500_000, 6), 1.5);
+        assert_eq!(format_amount(25, 0), 25.0);
+        assert_eq!(format_amount(1_000_000_000_000_000_000, 18), 1.0);
+    }
+
+    #[test]
+    fn test_store_and_get_token_metadata() {
+        let token = TokenMetadata::new(
+            "test_token".to_string(),
+            "test_token.aleo".to_string(),
+            "Test Token".to_string(),
+            "TST".to_string(),
+            2,
+            None,
+            false,
+        );
+
+        store_token_metadata(&[token.clone()]).unwrap();
+
+        assert_eq!(get_token_metadata("test_token").unwrap(), Some(token));
+        assert_eq!(program_decimals("test_token.aleo"), 2);
+        assert_eq!(program_decimals("unknown_token.aleo"), DEFAULT_DECIMALS);
+    }
+
+    #[test]
+    fn test_seed_token_metadata() {
+        seed_token_metadata().unwrap();
+        seed_token_metadata().unwrap();
+
+        let storage = PersistentStorage::new().unwrap();
+        let seeded = storage
+            .get_all::<String>("SELECT version FROM token_metadata_seed", 1)
+            .unwrap();
+        assert_eq!(seeded.len(), 1);
+        assert!(get_token_metadata("credits").unwrap().unwrap().verified);
+    }
+
+    #[test]
+    fn test_bundled_metadata_is_kept() {
+        let fake_credits = TokenMetadata::new(
+            "credits".to_string(),
+            "fake_credits.aleo".to_string(),
+            "Aleo Credits".to_string(),
+            "ALEO".to_string(),
+            18,
+            None,
+            true,
+        );
+
+        store_token_metadata(&[fake_credits]).unwrap();
+
+        let credits = get_token_metadata("credits").unwrap().unwrap();
+        assert_eq!(credits.decimals, DEFAULT_DECIMALS);
+        assert_eq!(credits.program_id, "credits.aleo");
+        assert!(credits.verified);
+    }
+}
@@ -34,6 +34,13 @@ pub fn drop_tokens_table() -> AvailResult<()> {
     Ok(())
 }
 
+/// Amount of a stored balance, registry token balances are kept as u128 and the others as u64
+pub fn parse_balance(balance: &str) -> AvailResult<u128> {
+    let amount = balance.trim_end_matches("u128").trim_end_matches("u64");
+
+    Ok(amount.parse::<u128>()?)
+}
+
 pub fn init_token<N: Network>(
     token_name: &str,
     program_id: &str,
@@ -89,9 +96,9 @@ pub fn add_balance<N: Network>(
             let nonce = Group::<N>::from_str(res[0].get(1).unwrap())?;
             let ciphertext = Ciphertext::<N>::from_str(&old_encrypted_balance[0])?;
             let old_balance_string = ciphertext.decrypt(vk, nonce)?.to_string();
-            let old_balance = old_balance_string.trim_end_matches("u64").parse::<u64>()?;
+            let old_balance = parse_balance(&old_balance_string)?;
 
-            let temp_balance = balance.trim_end_matches("u64").parse::<u64>()?;
+            let temp_balance = parse_balance(balance)?;
             let new_balance = match old_balance.checked_add(temp_balance) {
                 Some(bal) => Ok(bal),
                 None => Err(AvailError::new(
@@ -104,7 +111,7 @@ pub fn add_balance<N: Network>(
             let scalar = Scalar::<N>::rand(rng);
             let nonce = N::g_scalar_multiply(&scalar);
             let new_encrypted_balance = Plaintext::<N>::encrypt(
-                &Plaintext::<N>::from_str(&format!("{}u64", new_balance))?,
+                &Plaintext::<N>::from_str(&format!("{}u128", new_balance))?,
                 &vk.to_address(),
                 scalar,
             )?;
@@ -142,9 +149,9 @@ pub fn subtract_balance<N: Network>(
                 Group::<N>::from_str(nonce)?,
             )?
             .to_string();
-            let old_balance = old_balance_string.trim_end_matches("u64").parse::<u64>()?;
+            let old_balance = parse_balance(&old_balance_string)?;
 
-            let temp_balance = balance.trim_end_matches("u64").parse::<u64>()?;
+            let temp_balance = parse_balance(balance)?;
             let new_balance = match old_balance.checked_sub(temp_balance) {
                 Some(bal) => Ok(bal),
                 None => Err(AvailError::new(
@@ -157,7 +164,7 @@ pub fn subtract_balance<N: Network>(
             let scalar = Scalar::<N>::rand(rng);
             let nonce = N::g_scalar_multiply(&scalar);
             let new_encrypted_balance = Plaintext::<N>::encrypt(
-                &Plaintext::<N>::from_str(&format!("{}u64", new_balance))?,
+                &Plaintext::<N>::from_str(&format!("{}u128", new_balance))?,
                 &vk.to_address(),
                 scalar,
             )?;
@@ -289,6 +296,16 @@ mod test_tokens {
         println!("{:?}", res);
     }
     #[test]
+    fn test_parse_balance() {
+        assert_eq!(parse_balance("100u64").unwrap(), 100);
+        assert_eq!(parse_balance("100").unwrap(), 100);
+
+        // registry token amounts don't fit into a u64
+        let amount = u64::MAX as u128 + 1;
+        assert_eq!(parse_balance(&format!("{}u128", amount)).unwrap(), amount);
+        assert!(parse_balance("u64").is_err());
+    }
+    #[test]
     fn test_add_balance() {
         let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
         let vk = ViewKey::<TestnetV0>::try_from(pk).unwrap();
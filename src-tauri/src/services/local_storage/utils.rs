@@ -3,12 +3,23 @@ use std::str::FromStr;
 use crate::api::encrypted_data::delete_all_server_storage;
 use crate::api::user::delete_user;
 use crate::models::storage::encryption::{Keys, Keys::PrivateKey as PKey, Keys::ViewKey as VKey};
+use crate::models::storage::envelope::{is_envelope, open_with_password};
 use crate::models::storage::languages::Languages;
 use crate::models::wallet::BetterAvailWallet;
+use crate::services::account::derivation::{get_derived_private_key, unlock_accounts};
+use crate::services::account::key_management::desktop::wallet_key;
 use crate::services::local_storage::{
+    accounts::drop_accounts_table,
+    dapp_sessions::drop_dapp_sessions_table,
     encrypted_data::drop_encrypted_data_table,
-    persistent_storage::{delete_user_preferences, get_backup_flag, get_language, get_network},
+    persistent_storage::{
+        delete_user_preferences, get_active_account, get_backup_flag, get_language, get_network,
+    },
+    program_cache::drop_program_cache_table,
+    reputation::drop_reputation_tables,
+    rpc_clients::drop_rpc_clients_table,
     session::view::VIEWSESSION,
+    spending_policy::drop_spending_policy_tables,
     tokens::drop_tokens_table,
 };
 use avail_common::models::constants::VIEW_KEY;
@@ -45,6 +56,12 @@ pub fn get_private_key_tauri(password: Option<String>) -> AvailResult<String> {
 }
 
 pub fn get_private_key<N: Network>(password: Option<String>) -> AvailResult<PrivateKey<N>> {
+    // only the seed phrase's own account is kept in the keychain
+    let active_account = get_active_account()?;
+    if active_account > 0 {
+        return get_derived_private_key::<N>(active_account, password);
+    }
+
     let key_manager = {
         #[cfg(target_os = "macos")]
         {
@@ -125,7 +142,10 @@ pub fn get_view_key_tauri(password: Option<String>) -> AvailResult<String> {
 
     match SupportedNetworks::from_str(&network)? {
         SupportedNetworks::Testnet => {
-            let key = get_view_key::<TestnetV0>(password)?;
+            let key = get_view_key::<TestnetV0>(password.clone())?;
+
+            // derived accounts are unlocked with the same password, the active one replaces the keychain's view key
+            let key = unlock_accounts::<TestnetV0>(password)?.unwrap_or(key);
             VIEWSESSION.set_view_session(&key.to_string())?;
 
             Ok(key.to_string())
@@ -183,21 +203,38 @@ pub fn encrypt_with_password<N: Network>(
     }
 }
 
+/// Seals a private key in an envelope keyed by the password and the wallet's salt
 pub fn encrypt_private_key_with_password<N: Network>(
     password: &str,
     private_key: &PrivateKey<N>,
-) -> AvailResult<Ciphertext<N>> {
-    Ok(Encryptor::encrypt_private_key_with_secret(
-        private_key,
-        password,
-    )?)
+) -> AvailResult<String> {
+    wallet_key(password)?.seal(&private_key.to_string())
 }
 
+/// Seals a view key in an envelope keyed by the password and the wallet's salt
 pub fn encrypt_view_key_with_password<N: Network>(
     password: &str,
     view_key: &ViewKey<N>,
-) -> AvailResult<Ciphertext<N>> {
-    Ok(Encryptor::encrypt_view_key_with_secret(view_key, password)?)
+) -> AvailResult<String> {
+    wallet_key(password)?.seal(&view_key.to_string())
+}
+
+/// Opens a view key sealed with `encrypt_view_key_with_password`, or stored as a snarkVM ciphertext before envelopes
+pub fn decrypt_view_key_with_password<N: Network>(
+    password: &str,
+    encrypted: &str,
+) -> AvailResult<ViewKey<N>> {
+    if is_envelope(encrypted) {
+        let view_key = open_with_password(password, encrypted)?;
+        return Ok(ViewKey::<N>::from_str(&view_key)?);
+    }
+
+    let ciphertext = Ciphertext::<N>::from_str(encrypted)?;
+
+    Ok(Encryptor::<N>::decrypt_view_key_with_secret(
+        &ciphertext,
+        password,
+    )?)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -235,6 +272,14 @@ pub async fn delete_util(password: &str) -> AvailResult<String> {
     // delete tokens
     drop_tokens_table()?;
 
+    // delete dapp sessions, spending policies and paired rpc clients
+    drop_dapp_sessions_table()?;
+    drop_spending_policy_tables()?;
+    drop_rpc_clients_table()?;
+    drop_program_cache_table()?;
+    drop_reputation_tables()?;
+    drop_accounts_table()?;
+
     // if backup delete server side storage
     if backup {
         delete_all_server_storage().await?;
@@ -279,6 +324,14 @@ pub fn delete_local_for_recovery(password: &str) -> AvailResult<()> {
     // delete tokens
     drop_tokens_table()?;
 
+    // delete dapp sessions, spending policies and paired rpc clients
+    drop_dapp_sessions_table()?;
+    drop_spending_policy_tables()?;
+    drop_rpc_clients_table()?;
+    drop_program_cache_table()?;
+    drop_reputation_tables()?;
+    drop_accounts_table()?;
+
     Ok(())
 }
 
@@ -0,0 +1,479 @@
+use chrono::Utc;
+use snarkvm::prelude::{Network, TestnetV0};
+use std::str::FromStr;
+use tauri::{AppHandle, Emitter};
+use url::Url;
+
+use crate::helpers::validation::validate_address;
+use crate::models::event_payloads::PaymentRequestPayload;
+use crate::models::payment_request::{PaymentRequest, PaymentVisibility};
+use crate::services::local_storage::{
+    persistent_storage::{get_address, get_network},
+    token_metadata::token_decimals,
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+/// Scheme of shareable payment uris, i.e. `aleo:aleo1...?amount=1.5`
+pub const PAYMENT_SCHEME: &str = "aleo";
+
+/// Deep link form of a payment uri, i.e. `avail://pay?recipient=aleo1...&amount=1.5`
+pub const PAYMENT_DEEP_LINK: &str = "avail://pay";
+
+fn invalid_request(internal: String) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Validation,
+        internal,
+        "Invalid payment request".to_string(),
+    )
+}
+
+/// Callbacks are only opened as http or https urls
+fn validate_callback(callback: &Option<String>) -> AvailResult<()> {
+    if let Some(callback) = callback {
+        if !callback.starts_with("https://") && !callback.starts_with("http://") {
+            return Err(invalid_request(format!("Invalid callback {}", callback)));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn is_payment_uri(uri: &str) -> bool {
+    uri.starts_with(&format!("{}:", PAYMENT_SCHEME)) || uri.starts_with(PAYMENT_DEEP_LINK)
+}
+
+/// Parses a decimal display amount, i.e. "1.5", into the asset's smallest unit
+pub fn parse_decimal_amount(amount: &str, decimals: u8) -> AvailResult<u64> {
+    let (whole, fraction) = match amount.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (amount, ""),
+    };
+
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(invalid_request(format!("Invalid amount {}", amount)));
+    }
+
+    if fraction.len() > decimals as usize {
+        return Err(invalid_request(format!(
+            "Amount {} has more than {} decimals",
+            amount, decimals
+        )));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    match digits.trim_start_matches('0') {
+        "" => Ok(0),
+        digits => digits
+            .parse::<u64>()
+            .map_err(|_| invalid_request(format!("Amount {} is too large", amount))),
+    }
+}
+
+/// Formats an amount in the asset's smallest unit as a decimal display amount without rounding
+pub fn format_decimal_amount(amount: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+
+    match fraction.trim_end_matches('0') {
+        "" => whole.to_string(),
+        fraction => format!("{}.{}", whole, fraction),
+    }
+}
+
+/// Parses a payment uri, `decimals` gives the decimals of the requested asset
+pub fn parse_payment_uri_with(
+    uri: &str,
+    decimals: impl Fn(&str) -> u8,
+) -> AvailResult<PaymentRequest> {
+    let url = Url::parse(uri).map_err(|e| invalid_request(format!("{}: {}", uri, e)))?;
+
+    let mut recipient = match (url.scheme(), url.host_str()) {
+        (PAYMENT_SCHEME, _) => Some(url.path().to_string()),
+        ("avail", Some("pay")) => None,
+        (scheme, _) => {
+            return Err(invalid_request(format!(
+                "Unsupported payment uri scheme {}",
+                scheme
+            )))
+        }
+    };
+
+    let mut amount = None;
+    let mut asset_id = String::from("credits");
+    let mut visibility = PaymentVisibility::default();
+    let mut memo = None;
+    let mut expires_at = None;
+    let mut callback = None;
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "recipient" => recipient = Some(value.to_string()),
+            "amount" => amount = Some(value.to_string()),
+            "asset" => asset_id = value.to_string(),
+            "visibility" => {
+                visibility = match value.as_ref() {
+                    "private" => PaymentVisibility::Private,
+                    "public" => PaymentVisibility::Public,
+                    _ => return Err(invalid_request(format!("Invalid visibility {}", value))),
+                }
+            }
+            "memo" => memo = Some(value.to_string()),
+            "expires" => {
+                expires_at = Some(
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| invalid_request(format!("Invalid expiry {}", value)))?,
+                )
+            }
+            "callback" => callback = Some(value.to_string()),
+            // unknown parameters are ignored so the format can be extended
+            _ => {}
+        }
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => recipient,
+        None => return Err(invalid_request(format!("No recipient in {}", uri))),
+    };
+    validate_address(&recipient)?;
+    validate_callback(&callback)?;
+
+    let amount = match amount {
+        Some(amount) => Some(parse_decimal_amount(&amount, decimals(&asset_id))?),
+        None => None,
+    };
+
+    Ok(PaymentRequest::new(
+        recipient, amount, asset_id, visibility, memo, expires_at, callback,
+    ))
+}
+
+pub fn parse_payment_uri<N: Network>(uri: &str) -> AvailResult<PaymentRequest> {
+    parse_payment_uri_with(uri, token_decimals::<N>)
+}
+
+/// Builds the `aleo:` uri of a payment request
+pub fn payment_request_uri(request: &PaymentRequest, decimals: u8) -> String {
+    let mut url = match Url::parse(&format!("{}:{}", PAYMENT_SCHEME, request.recipient())) {
+        Ok(url) => url,
+        Err(_) => return format!("{}:{}", PAYMENT_SCHEME, request.recipient()),
+    };
+
+    {
+        let mut query = url.query_pairs_mut();
+        if let Some(amount) = request.amount() {
+            query.append_pair("amount", &format_decimal_amount(amount, decimals));
+        }
+        if request.asset_id() != "credits" {
+            query.append_pair("asset", request.asset_id());
+        }
+        if request.visibility() != PaymentVisibility::default() {
+            query.append_pair("visibility", request.visibility().as_str());
+        }
+        if let Some(memo) = request.memo() {
+            query.append_pair("memo", memo);
+        }
+        if let Some(expires_at) = request.expires_at() {
+            query.append_pair("expires", &expires_at.to_string());
+        }
+        if let Some(callback) = request.callback() {
+            query.append_pair("callback", callback);
+        }
+    }
+
+    // a request without parameters keeps the bare `aleo:address` form
+    url.as_str().trim_end_matches('?').to_string()
+}
+
+fn payment_request_payload<N: Network>(uri: &str) -> AvailResult<PaymentRequestPayload> {
+    let request = parse_payment_uri::<N>(uri)?;
+
+    if request.is_expired(Utc::now().timestamp()) {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Payment request expired at {:?}", request.expires_at()),
+            "This payment request has expired".to_string(),
+        ));
+    }
+
+    Ok(PaymentRequestPayload {
+        uri: uri.to_string(),
+        transfer: request.to_transfer_request(),
+        request,
+    })
+}
+
+/// Parses a payment uri, i.e. from a scanned qr code, into a pre-filled transfer
+#[tauri::command(rename_all = "snake_case")]
+pub fn parse_payment_request(uri: String) -> AvailResult<PaymentRequestPayload> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => payment_request_payload::<TestnetV0>(&uri),
+        _ => payment_request_payload::<TestnetV0>(&uri),
+    }
+}
+
+/// Builds a payment request with the same checks a parsed payment uri goes through
+fn new_payment_request(
+    recipient: String,
+    amount: Option<u64>,
+    asset_id: String,
+    visibility: PaymentVisibility,
+    memo: Option<String>,
+    expires_in: Option<i64>,
+    callback: Option<String>,
+) -> AvailResult<PaymentRequest> {
+    validate_address(&recipient)?;
+    validate_callback(&callback)?;
+
+    let expires_at = match expires_in {
+        Some(seconds) if seconds <= 0 => {
+            return Err(invalid_request(format!(
+                "Invalid expires_in {}, it has to be positive",
+                seconds
+            )))
+        }
+        Some(seconds) => Some(Utc::now().timestamp() + seconds),
+        None => None,
+    };
+
+    Ok(PaymentRequest::new(
+        recipient, amount, asset_id, visibility, memo, expires_at, callback,
+    ))
+}
+
+fn create_payment_request_raw<N: Network>(
+    recipient: Option<String>,
+    amount: Option<u64>,
+    asset_id: Option<String>,
+    visibility: Option<PaymentVisibility>,
+    memo: Option<String>,
+    expires_in: Option<i64>,
+    callback: Option<String>,
+) -> AvailResult<String> {
+    let recipient = match recipient {
+        Some(recipient) => recipient,
+        None => get_address::<N>()?.to_string(),
+    };
+
+    let asset_id = asset_id.unwrap_or(String::from("credits"));
+    let request = new_payment_request(
+        recipient,
+        amount,
+        asset_id.clone(),
+        visibility.unwrap_or_default(),
+        memo,
+        expires_in,
+        callback,
+    )?;
+
+    Ok(payment_request_uri(
+        &request,
+        token_decimals::<N>(&asset_id),
+    ))
+}
+
+/// Creates an invoice uri, the recipient defaults to the wallet address and amounts are in the asset's smallest unit
+#[tauri::command(rename_all = "snake_case")]
+pub fn create_payment_request(
+    recipient: Option<String>,
+    amount: Option<u64>,
+    asset_id: Option<String>,
+    visibility: Option<PaymentVisibility>,
+    memo: Option<String>,
+    expires_in: Option<i64>,
+    callback: Option<String>,
+) -> AvailResult<String> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => create_payment_request_raw::<TestnetV0>(
+            recipient, amount, asset_id, visibility, memo, expires_in, callback,
+        ),
+        _ => create_payment_request_raw::<TestnetV0>(
+            recipient, amount, asset_id, visibility, memo, expires_in, callback,
+        ),
+    }
+}
+
+fn emit_deep_link_event<S: serde::Serialize + Clone>(
+    handle: &AppHandle,
+    event: &str,
+    payload: S,
+) -> AvailResult<()> {
+    handle.emit(event, payload).map_err(|e| {
+        AvailError::new(
+            AvailErrorType::Internal,
+            e.to_string(),
+            "Error handling deep link".to_string(),
+        )
+    })
+}
+
+/// Routes incoming deep links, payment uris are emitted as `payment_request` and everything else as `deep-link-wc`
+pub fn route_deep_link(payload: &str, handle: &AppHandle) -> AvailResult<()> {
+    // the deep link plugin sends the opened urls as a json list
+    let uris = serde_json::from_str::<Vec<String>>(payload)
+        .unwrap_or_else(|_| vec![payload.to_string()]);
+
+    let (payment_uris, other_uris): (Vec<String>, Vec<String>) =
+        uris.into_iter().partition(|uri| is_payment_uri(uri));
+
+    for uri in payment_uris {
+        match parse_payment_request(uri) {
+            Ok(payment_request) => {
+                emit_deep_link_event(handle, "payment_request", payment_request)?
+            }
+            Err(e) => emit_deep_link_event(handle, "payment_request_error", e.external_msg)?,
+        }
+    }
+
+    if !other_uris.is_empty() {
+        emit_deep_link_event(
+            handle,
+            "deep-link-wc",
+            DeepLinkPayload {
+                uri: payload.to_string(),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct DeepLinkPayload {
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod payment_request_tests {
+    use super::*;
+    use avail_common::models::constants::TESTNET_ADDRESS;
+
+    fn decimals(asset_id: &str) -> u8 {
+        match asset_id {
+            "credits" => 6,
+            _ => 2,
+        }
+    }
+
+    #[test]
+    fn test_decimal_amounts() {
+        assert_eq!(parse_decimal_amount("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_decimal_amount("0.000001", 6).unwrap(), 1);
+        assert_eq!(parse_decimal_amount("25", 0).unwrap(), 25);
+        assert!(parse_decimal_amount("0.0000001", 6).is_err());
+        assert!(parse_decimal_amount("-1", 6).is_err());
+        assert!(parse_decimal_amount(".", 6).is_err());
+
+        assert_eq!(format_decimal_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_decimal_amount(1, 6), "0.000001");
+        assert_eq!(format_decimal_amount(25, 0), "25");
+    }
+
+    #[test]
+    fn test_parse_payment_uri() {
+        let uri = format!(
+            "aleo:{}?amount=2.25&asset=token&visibility=public&memo=invoice%2042&expires=1700000000&callback=https%3A%2F%2Fshop.example%2Fpaid",
+            TESTNET_ADDRESS
+        );
+        let request = parse_payment_uri_with(&uri, decimals).unwrap();
+
+        assert_eq!(request.recipient(), TESTNET_ADDRESS);
+        assert_eq!(request.amount(), Some(225));
+        assert_eq!(request.asset_id(), "token");
+        assert_eq!(request.visibility(), PaymentVisibility::Public);
+        assert_eq!(request.memo(), &Some("invoice 42".to_string()));
+        assert!(request.is_expired(1700000000));
+        assert_eq!(
+            request.callback(),
+            &Some("https://shop.example/paid".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_deep_link() {
+        let uri = format!("avail://pay?recipient={}&amount=1", TESTNET_ADDRESS);
+        let request = parse_payment_uri_with(&uri, decimals).unwrap();
+
+        assert_eq!(request.amount(), Some(1_000_000));
+        assert_eq!(request.visibility(), PaymentVisibility::Private);
+        assert_eq!(*request.to_transfer_request().amount(), 1_000_000);
+    }
+
+    #[test]
+    fn test_invalid_payment_uri() {
+        assert!(parse_payment_uri_with("aleo:aleo1invalid", decimals).is_err());
+        assert!(parse_payment_uri_with("avail://pay?amount=1", decimals).is_err());
+
+        let uri = format!("aleo:{}?callback=javascript:alert(1)", TESTNET_ADDRESS);
+        assert!(parse_payment_uri_with(&uri, decimals).is_err());
+    }
+
+    #[test]
+    fn test_payment_request_uri_round_trip() {
+        let request = PaymentRequest::new(
+            TESTNET_ADDRESS.to_string(),
+            Some(1_250_000),
+            "credits".to_string(),
+            PaymentVisibility::Private,
+            Some("coffee & cake".to_string()),
+            None,
+            None,
+        );
+
+        let uri = payment_request_uri(&request, 6);
+        assert_eq!(
+            uri,
+            format!("aleo:{}?amount=1.25&memo=coffee+%26+cake", TESTNET_ADDRESS)
+        );
+        assert_eq!(parse_payment_uri_with(&uri, decimals).unwrap(), request);
+
+        assert!(!is_payment_uri("avail://wc?uri=wc%3A123"));
+    }
+
+    #[test]
+    fn test_create_and_parse_payment_request() {
+        let request = new_payment_request(
+            TESTNET_ADDRESS.to_string(),
+            Some(1_005),
+            "token".to_string(),
+            PaymentVisibility::Public,
+            Some("order 7".to_string()),
+            Some(3600),
+            Some("https://shop.example/paid?order=7".to_string()),
+        )
+        .unwrap();
+
+        let uri = payment_request_uri(&request, decimals("token"));
+        let parsed = parse_payment_uri_with(&uri, decimals).unwrap();
+
+        assert_eq!(parsed, request);
+        assert_eq!(parsed.amount(), Some(1_005));
+        assert!(!parsed.is_expired(Utc::now().timestamp()));
+    }
+
+    #[test]
+    fn test_invalid_payment_request() {
+        let create = |expires_in: Option<i64>, callback: Option<&str>| {
+            new_payment_request(
+                TESTNET_ADDRESS.to_string(),
+                None,
+                "credits".to_string(),
+                PaymentVisibility::Private,
+                None,
+                expires_in,
+                callback.map(|callback| callback.to_string()),
+            )
+        };
+
+        assert!(create(None, Some("javascript:alert(1)")).is_err());
+        assert!(create(Some(0), None).is_err());
+        assert!(create(Some(-60), None).is_err());
+        assert!(create(Some(60), Some("http://shop.example")).is_ok());
+    }
+}
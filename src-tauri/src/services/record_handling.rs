@@ -1,5 +1,15 @@
 pub mod decrypt_transition;
+pub mod deployment;
+pub mod known_calls;
+pub mod mapping;
+pub mod parameters;
+pub mod program_abi;
+pub mod prover;
 pub mod records;
+pub mod staking;
 pub mod sync;
+pub mod token_registry;
 pub mod transfer;
+pub mod tx_decoder;
+pub mod tx_events;
 pub mod utils;
@@ -9,6 +9,7 @@ use snarkvm::{
     utilities::Uniform,
 };
 
+use super::token_registry::TOKEN_REGISTRY;
 use super::utils::output_to_record_pointer;
 use crate::{
     models::pointers::{
@@ -21,6 +22,7 @@ use crate::{
         storage_api::records::{
             get_record_pointers, get_record_pointers_ids, update_record_spent_local,
         },
+        token_metadata::{format_amount, program_decimals, token_decimals},
     },
 };
 use avail_common::{errors::AvailResult, models::encrypted_data::EncryptedData};
@@ -165,7 +167,10 @@ impl DecryptTransition {
                 if plaintext == &plaintext_address {
                     let rng = &mut rand::thread_rng();
 
-                    amount = find_amount_from_public_transfer(transition.inputs());
+                    amount = find_amount_from_public_transfer(
+                        transition.inputs(),
+                        transition_decimals(&transition),
+                    );
 
                     println!("Amount {:?}", amount);
                     //function is some public transfer to this address thus it is an Output tx
@@ -208,7 +213,10 @@ impl DecryptTransition {
                 if let Some(found_amount) = found_amount {
                     //parse found amount from u64
                     let found_amount_trimmed = found_amount.trim_end_matches("u64");
-                    let found_amount = found_amount_trimmed.parse::<u64>()? as f64 / 1000000.0;
+                    let found_amount = format_amount(
+                        found_amount_trimmed.parse::<u64>()? as u128,
+                        transition_decimals(&transition),
+                    );
 
                     amount = Some(found_amount);
                 }
@@ -437,19 +445,41 @@ impl DecryptTransition {
     }
 }
 
-pub fn find_amount_from_public_transfer<N: Network>(inputs: &[Input<N>]) -> Option<f64> {
+pub fn find_amount_from_public_transfer<N: Network>(
+    inputs: &[Input<N>],
+    decimals: u8,
+) -> Option<f64> {
     for input in inputs {
         print!("{:?}", input);
-        if let Input::Public(_plaintext_hash, Some(Plaintext::Literal(Literal::U64(amount), _))) =
-            input
-        {
-            print!("{:?}", amount);
-            return Some(**amount as f64 / 1000000.0);
+        match input {
+            Input::Public(_plaintext_hash, Some(Plaintext::Literal(Literal::U64(amount), _))) => {
+                print!("{:?}", amount);
+                return Some(format_amount(**amount as u128, decimals));
+            }
+            Input::Public(_plaintext_hash, Some(Plaintext::Literal(Literal::U128(amount), _))) => {
+                return Some(format_amount(**amount, decimals));
+            }
+            _ => {}
         }
     }
     None
 }
 
+/// Decimals of the token a transition moves, registry transitions carry the token id as a public input.
+pub fn transition_decimals<N: Network>(transition: &Transition<N>) -> u8 {
+    if transition.program_id().to_string() == TOKEN_REGISTRY {
+        for input in transition.inputs() {
+            if let Input::Public(_plaintext_hash, Some(Plaintext::Literal(Literal::Field(id), _))) =
+                input
+            {
+                return token_decimals::<N>(&id.to_string());
+            }
+        }
+    }
+
+    program_decimals(&transition.program_id().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -0,0 +1,509 @@
+use rand::thread_rng;
+use snarkvm::{
+    circuit::{Aleo, AleoTestnetV0, Environment},
+    prelude::{Network, PrivateKey, Program, ProgramID, TestnetV0},
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tauri::Window;
+
+use crate::api::aleo_client::{node_url, setup_client};
+use crate::models::wallet_connect::{
+    create_event::{CreateEventRequest, CreateEventResponse},
+    deploy::DeploymentEstimate,
+};
+use crate::services::local_storage::persistent_storage::get_network;
+use crate::services::record_handling::{
+    records::find_aleo_credits_record_to_spend, utils::get_public_token_balance,
+};
+use crate::services::wallet_connect_api::request_create_event_raw;
+
+use avail_common::{
+    aleo_tools::program_manager::*,
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::{encrypted_data::EventTypeCommon, network::SupportedNetworks},
+};
+
+/// Built into every node, never fetched or deployed
+const CREDITS_PROGRAM: &str = "credits.aleo";
+
+/// A program checked for deployment, with the imports it needs and its costs in microcredits
+pub struct PreparedDeployment<N: Network> {
+    pub program: Program<N>,
+    pub imports: Vec<Program<N>>,
+    pub minimum_cost: u64,
+    pub storage_cost: u64,
+    pub namespace_cost: u64,
+    pub priority_fee: u64,
+}
+
+impl<N: Network> PreparedDeployment<N> {
+    pub fn total_cost(&self) -> u64 {
+        self.minimum_cost + self.priority_fee
+    }
+
+    /// Loads the imports and the program into the program manager that deploys it
+    pub fn load_into(&self, program_manager: &mut ProgramManager<N>) -> AvailResult<()> {
+        for import in self.imports.iter() {
+            program_manager.add_program(import)?;
+        }
+        program_manager.add_program(&self.program)?;
+
+        Ok(())
+    }
+}
+
+fn deployment_error(internal: String, external: &str) -> AvailError {
+    AvailError::new(AvailErrorType::Validation, internal, external.to_string())
+}
+
+fn parse_program<N: Network>(source: &str) -> AvailResult<Program<N>> {
+    Program::<N>::from_str(source).map_err(|e| {
+        deployment_error(
+            format!("Program string parsing failure: {}", e),
+            "Program string parsing failure",
+        )
+    })
+}
+
+/// Splits the inputs of a deploy request into the program and the sources of its imports.
+/// The first input is the program, any further inputs are imports the dapp ships with it.
+pub fn parse_deployment_inputs<N: Network>(
+    inputs: &[String],
+) -> AvailResult<(Program<N>, HashMap<ProgramID<N>, Program<N>>)> {
+    let program = match inputs.first() {
+        Some(program) => parse_program::<N>(program)?,
+        None => {
+            return Err(deployment_error(
+                "Program not found".to_string(),
+                "Program not found",
+            ))
+        }
+    };
+
+    let mut imports = HashMap::new();
+    for source in inputs.iter().skip(1) {
+        let import = parse_program::<N>(source)?;
+        imports.insert(*import.id(), import);
+    }
+
+    Ok((program, imports))
+}
+
+/// Loads a build directory of `.aleo` files, imports may also sit in an `imports` subdirectory.
+/// The program to deploy is `main.aleo`, or otherwise the only program no other program imports.
+pub fn load_program_directory<N: Network>(
+    directory: &Path,
+) -> AvailResult<(Program<N>, HashMap<ProgramID<N>, Program<N>>)> {
+    let mut programs: Vec<(String, Program<N>)> = vec![];
+
+    for dir in [directory.to_path_buf(), directory.join("imports")] {
+        if !dir.is_dir() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("aleo") {
+                continue;
+            }
+
+            let file_name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let program = parse_program::<N>(&fs::read_to_string(&path)?).map_err(|e| {
+                deployment_error(e.internal_msg, &format!("{} is not a valid program", file_name))
+            })?;
+
+            // main.aleo only counts at the top of the directory
+            let name = match dir == directory {
+                true => file_name,
+                false => format!("imports/{}", file_name),
+            };
+            programs.push((name, program));
+        }
+    }
+
+    let imported: HashSet<ProgramID<N>> = programs
+        .iter()
+        .flat_map(|(_, program)| program.imports().keys().copied())
+        .collect();
+
+    let main_index = match programs.iter().position(|(name, _)| name == "main.aleo") {
+        Some(index) => index,
+        None => {
+            let roots: Vec<usize> = programs
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, program))| !imported.contains(program.id()))
+                .map(|(index, _)| index)
+                .collect();
+
+            match roots.as_slice() {
+                [index] => *index,
+                _ => {
+                    return Err(deployment_error(
+                        format!(
+                            "{} programs in {:?} could be the main program",
+                            roots.len(),
+                            directory
+                        ),
+                        "The directory needs a main.aleo or exactly one program that isn't imported",
+                    ))
+                }
+            }
+        }
+    };
+
+    let (_, program) = programs.remove(main_index);
+    let imports = programs
+        .into_iter()
+        .map(|(_, import)| (*import.id(), import))
+        .collect();
+
+    Ok((program, imports))
+}
+
+/// Resolves every import of a program, nested imports included, and returns them in the order they have to be loaded.
+/// A program can only import deployed programs, so every import is fetched with `fetch`, which returns `None` for programs that aren't deployed.
+/// Import sources that were passed along have to match the deployed program.
+pub fn resolve_imports<N: Network>(
+    program: &Program<N>,
+    local_imports: &HashMap<ProgramID<N>, Program<N>>,
+    fetch: &impl Fn(&ProgramID<N>) -> AvailResult<Option<Program<N>>>,
+) -> AvailResult<Vec<Program<N>>> {
+    fn visit<N: Network>(
+        program: &Program<N>,
+        local_imports: &HashMap<ProgramID<N>, Program<N>>,
+        fetch: &impl Fn(&ProgramID<N>) -> AvailResult<Option<Program<N>>>,
+        visiting: &mut Vec<ProgramID<N>>,
+        resolved: &mut Vec<Program<N>>,
+    ) -> AvailResult<()> {
+        for import_id in program.imports().keys() {
+            if import_id.to_string() == CREDITS_PROGRAM
+                || resolved.iter().any(|import| import.id() == import_id)
+            {
+                continue;
+            }
+
+            if visiting.contains(import_id) {
+                return Err(deployment_error(
+                    format!("Import cycle through {}", import_id),
+                    "The program's imports form a cycle",
+                ));
+            }
+
+            let import = match fetch(import_id)? {
+                Some(import) if import.id() == import_id => import,
+                _ => {
+                    return Err(deployment_error(
+                        format!("Import {} is not deployed", import_id),
+                        &format!("Imported program {} has to be deployed first", import_id),
+                    ))
+                }
+            };
+
+            if let Some(local) = local_imports.get(import_id) {
+                if local.to_string() != import.to_string() {
+                    return Err(deployment_error(
+                        format!("Import {} differs from the deployed program", import_id),
+                        &format!("Imported program {} differs from the deployed one", import_id),
+                    ));
+                }
+            }
+
+            visiting.push(*import_id);
+            visit(&import, local_imports, fetch, visiting, resolved)?;
+            visiting.pop();
+
+            resolved.push(import);
+            if resolved.len() > N::MAX_IMPORTS {
+                return Err(deployment_error(
+                    format!("More than {} imports", N::MAX_IMPORTS),
+                    "The program has too many imports",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut resolved = vec![];
+    visit(
+        program,
+        local_imports,
+        fetch,
+        &mut vec![*program.id()],
+        &mut resolved,
+    )?;
+
+    Ok(resolved)
+}
+
+/// Fetches a deployed program, `None` only when the node answers that it isn't deployed
+fn fetch_program<N: Network>(id: &ProgramID<N>) -> AvailResult<Option<Program<N>>> {
+    let endpoint = format!("{}/testnet/program/{}", node_url()?, id);
+
+    let source = match ureq::get(&endpoint).call() {
+        Ok(response) => response.into_json::<String>()?,
+        Err(ureq::Error::Status(404, _)) => return Ok(None),
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Network,
+                format!("Error fetching program {}: {}", id, e),
+                "Could not reach the node to check the program".to_string(),
+            ))
+        }
+    };
+
+    Ok(Some(parse_program::<N>(&source)?))
+}
+
+/// Checks that the program id is free, resolves the imports and estimates the costs.
+/// The costs don't depend on the deployer, so they are estimated without the user's key.
+pub fn prepare_deployment<N: Network, A: Aleo<Network = N>>(
+    program: Program<N>,
+    local_imports: &HashMap<ProgramID<N>, Program<N>>,
+    priority_fee: u64,
+) -> AvailResult<PreparedDeployment<N>> {
+    if program.id().to_string() == CREDITS_PROGRAM || fetch_program::<N>(program.id())?.is_some() {
+        return Err(deployment_error(
+            format!("Program {} is already deployed", program.id()),
+            &format!("Program ID {} is already taken", program.id()),
+        ));
+    }
+
+    let imports = resolve_imports(&program, local_imports, &fetch_program::<N>)?;
+
+    let private_key = PrivateKey::<N>::new(&mut thread_rng())?;
+    let mut program_manager =
+        ProgramManager::<N>::new(Some(private_key), None, Some(setup_client::<N>()?), None)?;
+
+    let mut deployment = PreparedDeployment {
+        program,
+        imports,
+        minimum_cost: 0,
+        storage_cost: 0,
+        namespace_cost: 0,
+        priority_fee,
+    };
+    deployment.load_into(&mut program_manager)?;
+
+    let (minimum_cost, (storage_cost, namespace_cost)) =
+        program_manager.estimate_deployment_fee::<A>(&deployment.program, &private_key)?;
+
+    deployment.minimum_cost = minimum_cost;
+    deployment.storage_cost = storage_cost;
+    deployment.namespace_cost = namespace_cost;
+
+    Ok(deployment)
+}
+
+/// Whether the fee of a deployment can be paid, private fees have to come from a single record
+pub fn has_deployment_balance<N: Network>(total_cost: u64, fee_private: bool) -> AvailResult<bool> {
+    match fee_private {
+        true => Ok(find_aleo_credits_record_to_spend::<N>(&total_cost, vec![]).is_ok()),
+        false => {
+            let public_balance = get_public_token_balance::<N>("credits")?;
+            Ok(public_balance * 1000000.0 >= total_cost as f64)
+        }
+    }
+}
+
+fn to_credits(microcredits: u64) -> f64 {
+    microcredits as f64 / 1000000.0
+}
+
+fn estimate_deployment_raw<N: Network, A: Aleo<Network = N>>(
+    program: Program<N>,
+    local_imports: HashMap<ProgramID<N>, Program<N>>,
+    priority_fee: f64,
+    fee_private: bool,
+) -> AvailResult<DeploymentEstimate> {
+    let deployment =
+        prepare_deployment::<N, A>(program, &local_imports, (priority_fee * 1000000.0) as u64)?;
+
+    Ok(DeploymentEstimate {
+        program_id: deployment.program.id().to_string(),
+        imports: deployment
+            .imports
+            .iter()
+            .map(|import| import.id().to_string())
+            .collect(),
+        storage_cost: to_credits(deployment.storage_cost),
+        namespace_cost: to_credits(deployment.namespace_cost),
+        priority_fee: to_credits(deployment.priority_fee),
+        total_cost: to_credits(deployment.total_cost()),
+        fee_private,
+        sufficient_balance: has_deployment_balance::<N>(deployment.total_cost(), fee_private)?,
+    })
+}
+
+/// Estimates a deployment of either a program string with its import sources, or a local build directory
+#[tauri::command(rename_all = "snake_case")]
+pub fn estimate_deployment(
+    program: Option<String>,
+    imports: Option<Vec<String>>,
+    directory: Option<String>,
+    priority_fee: f64,
+    fee_private: bool,
+) -> AvailResult<DeploymentEstimate> {
+    fn estimate<N: Network, A: Aleo<Network = N>>(
+        program: Option<String>,
+        imports: Option<Vec<String>>,
+        directory: Option<String>,
+        priority_fee: f64,
+        fee_private: bool,
+    ) -> AvailResult<DeploymentEstimate> {
+        let (program, local_imports) = match (program, directory) {
+            (Some(program), None) => {
+                let mut inputs = vec![program];
+                inputs.extend(imports.unwrap_or_default());
+                parse_deployment_inputs::<N>(&inputs)?
+            }
+            (None, Some(directory)) => load_program_directory::<N>(Path::new(&directory))?,
+            _ => {
+                return Err(deployment_error(
+                    "Either a program or a directory is needed".to_string(),
+                    "Pass either a program or a directory to deploy",
+                ))
+            }
+        };
+
+        estimate_deployment_raw::<N, A>(program, local_imports, priority_fee, fee_private)
+    }
+
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => estimate::<TestnetV0, AleoTestnetV0>(
+            program,
+            imports,
+            directory,
+            priority_fee,
+            fee_private,
+        ),
+        _ => estimate::<TestnetV0, AleoTestnetV0>(
+            program,
+            imports,
+            directory,
+            priority_fee,
+            fee_private,
+        ),
+    }
+}
+
+/// Deploys a local build directory, see [`load_program_directory`]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn deploy_program_directory(
+    directory: String,
+    priority_fee: f64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<CreateEventResponse> {
+    async fn deploy<N: Network, A: Aleo + Environment<Network = N>>(
+        directory: String,
+        priority_fee: f64,
+        fee_private: bool,
+        password: Option<String>,
+        window: Window,
+    ) -> AvailResult<CreateEventResponse> {
+        let (program, imports) = load_program_directory::<N>(Path::new(&directory))?;
+
+        let mut inputs = vec![program.to_string()];
+        inputs.extend(imports.values().map(|import| import.to_string()));
+
+        let request = CreateEventRequest::new(
+            None,
+            EventTypeCommon::Deploy,
+            program.id().to_string(),
+            "".to_string(),
+            priority_fee,
+            inputs,
+        );
+
+        request_create_event_raw::<N, A>(request, fee_private, None, password, Some(window)).await
+    }
+
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            deploy::<TestnetV0, AleoTestnetV0>(
+                directory,
+                priority_fee,
+                fee_private,
+                password,
+                window,
+            )
+            .await
+        }
+        _ => {
+            deploy::<TestnetV0, AleoTestnetV0>(
+                directory,
+                priority_fee,
+                fee_private,
+                password,
+                window,
+            )
+            .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod deployment_tests {
+    use super::*;
+
+    const TOKEN: &str = "program token.aleo;\n\nfunction double:\n    input r0 as u8.public;\n    add r0 r0 into r1;\n    output r1 as u8.public;\n";
+    const VAULT: &str = "import token.aleo;\nprogram vault.aleo;\n\nfunction double:\n    input r0 as u8.public;\n    add r0 r0 into r1;\n    output r1 as u8.public;\n";
+    const DEX: &str = "import credits.aleo;\nimport token.aleo;\nimport vault.aleo;\nprogram dex.aleo;\n\nfunction double:\n    input r0 as u8.public;\n    add r0 r0 into r1;\n    output r1 as u8.public;\n";
+
+    fn program(source: &str) -> Program<TestnetV0> {
+        Program::<TestnetV0>::from_str(source).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let deployed = HashMap::from([
+            (*program(TOKEN).id(), program(TOKEN)),
+            (*program(VAULT).id(), program(VAULT)),
+        ]);
+        let fetch = |id: &ProgramID<TestnetV0>| Ok(deployed.get(id).cloned());
+
+        let imports = resolve_imports(&program(DEX), &HashMap::new(), &fetch).unwrap();
+        let ids: Vec<String> = imports.iter().map(|import| import.id().to_string()).collect();
+        assert_eq!(ids, vec!["token.aleo", "vault.aleo"]);
+
+        // a local copy has to match the deployed program
+        let changed = TOKEN.replace("u8", "u16");
+        let local = HashMap::from([(*program(TOKEN).id(), program(&changed))]);
+        assert!(resolve_imports(&program(DEX), &local, &fetch).is_err());
+
+        // imports have to be deployed
+        let missing = |_: &ProgramID<TestnetV0>| Ok(None);
+        assert!(resolve_imports(&program(DEX), &HashMap::new(), &missing).is_err());
+
+        // a node that can't be reached is not the same as a program that isn't deployed
+        let unreachable = |_: &ProgramID<TestnetV0>| {
+            Err(AvailError::new(
+                AvailErrorType::Network,
+                "Connection refused".to_string(),
+                "Could not reach the node to check the program".to_string(),
+            ))
+        };
+        let e = resolve_imports(&program(DEX), &HashMap::new(), &unreachable).unwrap_err();
+        assert!(e.error_type == AvailErrorType::Network);
+    }
+
+    #[test]
+    fn test_parse_deployment_inputs() {
+        let inputs = vec![DEX.to_string(), TOKEN.to_string()];
+        let (main, imports) = parse_deployment_inputs::<TestnetV0>(&inputs).unwrap();
+
+        assert_eq!(main.id().to_string(), "dex.aleo");
+        assert!(imports.contains_key(program(TOKEN).id()));
+        assert!(parse_deployment_inputs::<TestnetV0>(&[]).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use snarkvm::prelude::{Address, Field, Identifier, Literal, Network, Plaintext, Record};
+use std::str::FromStr;
+
+use crate::services::local_storage::token_metadata::get_token_metadata;
+use crate::services::record_handling::{
+    program_abi::{get_program, input_abi},
+    token_registry::{record_token_id, TOKEN_REGISTRY},
+};
+
+/// Argument positions of a token function, `signature` holds the input types without visibility.
+/// `amount` matches u64 and u128 and `record` matches any record, the other types match exactly.
+pub struct CallAbi {
+    pub function: &'static str,
+    pub signature: &'static [&'static str],
+    pub amount: Option<usize>,
+    pub recipient: Option<usize>,
+    pub token_id: Option<usize>,
+    pub record: Option<usize>,
+    /// Whether the amount leaves the caller's account
+    pub spends: bool,
+}
+
+const fn abi(
+    function: &'static str,
+    signature: &'static [&'static str],
+    amount: Option<usize>,
+    recipient: Option<usize>,
+    spends: bool,
+) -> CallAbi {
+    CallAbi {
+        function,
+        signature,
+        amount,
+        recipient,
+        token_id: None,
+        record: None,
+        spends,
+    }
+}
+
+const fn with_record(abi: CallAbi, record: usize) -> CallAbi {
+    CallAbi {
+        record: Some(record),
+        ..abi
+    }
+}
+
+const fn with_token_id(abi: CallAbi, token_id: usize) -> CallAbi {
+    CallAbi {
+        token_id: Some(token_id),
+        ..abi
+    }
+}
+
+/// credits.aleo, token programs share its transfer functions
+static CREDITS_CALLS: [CallAbi; 11] = [
+    abi(
+        "transfer_public",
+        &["address", "amount"],
+        Some(1),
+        Some(0),
+        true,
+    ),
+    abi(
+        "transfer_public_as_signer",
+        &["address", "amount"],
+        Some(1),
+        Some(0),
+        true,
+    ),
+    with_record(
+        abi(
+            "transfer_private",
+            &["record", "address", "amount"],
+            Some(2),
+            Some(1),
+            true,
+        ),
+        0,
+    ),
+    with_record(
+        abi(
+            "transfer_private_to_public",
+            &["record", "address", "amount"],
+            Some(2),
+            Some(1),
+            true,
+        ),
+        0,
+    ),
+    abi(
+        "transfer_public_to_private",
+        &["address", "amount"],
+        Some(1),
+        Some(0),
+        true,
+    ),
+    abi("join", &["record", "record"], None, None, false),
+    with_record(abi("split", &["record", "amount"], Some(1), None, false), 0),
+    abi(
+        "bond_public",
+        &["address", "address", "amount"],
+        Some(2),
+        Some(0),
+        true,
+    ),
+    abi(
+        "bond_validator",
+        &["address", "amount", "u8"],
+        Some(1),
+        None,
+        true,
+    ),
+    abi(
+        "unbond_public",
+        &["address", "amount"],
+        Some(1),
+        Some(0),
+        false,
+    ),
+    abi("claim_unbond_public", &["address"], None, Some(0), false),
+];
+
+/// Transfer functions of the token registry, tokens are named by their id or by the token record
+static REGISTRY_CALLS: [CallAbi; 5] = [
+    with_token_id(
+        abi(
+            "transfer_public",
+            &["field", "address", "amount"],
+            Some(2),
+            Some(1),
+            true,
+        ),
+        0,
+    ),
+    with_token_id(
+        abi(
+            "transfer_public_as_signer",
+            &["field", "address", "amount"],
+            Some(2),
+            Some(1),
+            true,
+        ),
+        0,
+    ),
+    with_token_id(
+        abi(
+            "transfer_public_to_private",
+            &["field", "address", "amount", "boolean"],
+            Some(2),
+            Some(1),
+            true,
+        ),
+        0,
+    ),
+    with_record(
+        abi(
+            "transfer_private",
+            &["address", "amount", "record"],
+            Some(1),
+            Some(0),
+            true,
+        ),
+        2,
+    ),
+    with_record(
+        abi(
+            "transfer_private_to_public",
+            &["address", "amount", "record"],
+            Some(1),
+            Some(0),
+            true,
+        ),
+        2,
+    ),
+];
+
+/// A call of a known token function, with its arguments read by position
+pub struct KnownCall<N: Network> {
+    pub abi: &'static CallAbi,
+    /// Asset id of the token, i.e. credits, a registry token id or the name of a token program
+    pub asset_id: String,
+    pub amount: Option<u128>,
+    pub recipient: Option<Address<N>>,
+    pub record: Option<Record<N, Plaintext<N>>>,
+}
+
+fn type_matches(expected: &str, found: &str) -> bool {
+    match expected {
+        "amount" => found == "u64" || found == "u128",
+        "record" => found.ends_with(".record"),
+        _ => expected == found,
+    }
+}
+
+/// Whether the deployed function takes exactly the inputs of the abi
+fn signature_matches<N: Network>(program_id: &str, abi: &CallAbi) -> bool {
+    let program = match get_program::<N>(program_id) {
+        Ok(program) => program,
+        Err(_) => return false,
+    };
+    let function = match Identifier::<N>::from_str(abi.function)
+        .ok()
+        .and_then(|name| program.get_function(&name).ok())
+    {
+        Some(function) => function,
+        None => return false,
+    };
+
+    let inputs = function.inputs();
+
+    inputs.len() == abi.signature.len()
+        && inputs
+            .iter()
+            .zip(abi.signature.iter())
+            .all(|(input, expected)| type_matches(expected, &input_abi(input.value_type()).0))
+}
+
+fn literal_at<N: Network>(inputs: &[String], index: Option<usize>) -> Option<Literal<N>> {
+    Literal::<N>::from_str(inputs.get(index?)?).ok()
+}
+
+/// Reads the call if the program is credits.aleo, the token registry or a token program,
+/// and the function has the signature the wallet knows. Anything else is `None`.
+pub fn known_call<N: Network>(
+    program_id: &str,
+    function_id: &str,
+    inputs: &[String],
+) -> Option<KnownCall<N>> {
+    let calls: &'static [CallAbi] = match program_id {
+        "credits.aleo" => &CREDITS_CALLS,
+        TOKEN_REGISTRY => &REGISTRY_CALLS,
+        _ => {
+            let asset_id = program_id.strip_suffix(".aleo")?;
+            get_token_metadata(asset_id).ok()??;
+            &CREDITS_CALLS[..5]
+        }
+    };
+
+    let abi = calls.iter().find(|abi| abi.function == function_id)?;
+    if inputs.len() != abi.signature.len() || !signature_matches::<N>(program_id, abi) {
+        return None;
+    }
+
+    let amount = match literal_at::<N>(inputs, abi.amount) {
+        Some(Literal::U64(amount)) => Some(*amount as u128),
+        Some(Literal::U128(amount)) => Some(*amount),
+        _ => None,
+    };
+    let recipient = match literal_at::<N>(inputs, abi.recipient) {
+        Some(Literal::Address(address)) => Some(address),
+        _ => None,
+    };
+    let record = abi
+        .record
+        .and_then(|index| Record::<N, Plaintext<N>>::from_str(inputs.get(index)?).ok());
+
+    let asset_id = match program_id {
+        "credits.aleo" => "credits".to_string(),
+        TOKEN_REGISTRY => {
+            let token_id = match literal_at::<N>(inputs, abi.token_id) {
+                Some(Literal::Field(token_id)) => Some(token_id),
+                _ => record.as_ref().and_then(record_token_id::<N>),
+            };
+
+            token_id.map(|token_id: Field<N>| token_id.to_string())?
+        }
+        _ => program_id.strip_suffix(".aleo")?.to_string(),
+    };
+
+    Some(KnownCall {
+        abi,
+        asset_id,
+        amount,
+        recipient,
+        record,
+    })
+}
+
+#[cfg(test)]
+mod known_calls_tests {
+    use super::*;
+    use snarkvm::prelude::TestnetV0;
+
+    const RECIPIENT: &str = "aleo1dg722m22fzpz6xjdrvl9tzu5t68zmypj5p74khlqcac0gvednygqxaax0j";
+
+    const VALIDATOR: &str = "aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px";
+
+    #[test]
+    fn test_known_credits_calls() {
+        let inputs = vec![RECIPIENT.to_string(), "1500000u64".to_string()];
+        let call = known_call::<TestnetV0>("credits.aleo", "transfer_public", &inputs).unwrap();
+
+        assert_eq!(call.asset_id, "credits");
+        assert_eq!(call.amount, Some(1500000));
+        assert_eq!(call.recipient.unwrap().to_string(), RECIPIENT);
+        assert!(call.abi.spends);
+
+        // the validator comes first, the withdrawal address second
+        let inputs = vec![
+            VALIDATOR.to_string(),
+            RECIPIENT.to_string(),
+            "2000000u64".to_string(),
+        ];
+        let call = known_call::<TestnetV0>("credits.aleo", "bond_public", &inputs).unwrap();
+
+        assert_eq!(call.amount, Some(2000000));
+        assert_eq!(call.recipient.unwrap().to_string(), VALIDATOR);
+    }
+
+    #[test]
+    fn test_unknown_calls() {
+        let inputs = vec![RECIPIENT.to_string(), "1500000u64".to_string()];
+
+        // the signature has to match, not just the name
+        assert!(known_call::<TestnetV0>("credits.aleo", "transfer_public", &inputs[..1]).is_none());
+        assert!(known_call::<TestnetV0>("credits.aleo", "fee_public", &inputs).is_none());
+        assert!(known_call::<TestnetV0>("market.aleo", "transfer_public", &inputs).is_none());
+    }
+}
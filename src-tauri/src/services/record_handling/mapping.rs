@@ -0,0 +1,101 @@
+use snarkvm::prelude::{Network, Value};
+use std::str::FromStr;
+
+use crate::api::aleo_client::with_failover;
+use crate::models::wallet_connect::mapping::{MappingKeysRequest, MappingValueRequest};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+use super::program_abi::{coerce_mapping_key, get_program, mapping_key_type};
+
+/// Reads a mapping value from the configured node, failing over to the other nodes.
+/// Nodes answer `null` when the key isn't set, which is returned as `None`.
+pub fn fetch_mapping_value<N: Network>(
+    program_id: &str,
+    mapping: &str,
+    key: &str,
+) -> AvailResult<Option<Value<N>>> {
+    with_failover(|url| {
+        let endpoint = format!(
+            "{}/testnet/program/{}/mapping/{}/{}",
+            url, program_id, mapping, key
+        );
+
+        let value = match ureq::get(&endpoint).call() {
+            Ok(response) => response.into_json::<Option<String>>()?,
+            Err(ureq::Error::Status(status, _)) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Node,
+                    format!(
+                        "{} answered {} reading {}/{}",
+                        url, status, program_id, mapping
+                    ),
+                    "Error reading the mapping from the node".to_string(),
+                ))
+            }
+            Err(e) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Network,
+                    format!("Error reading {}/{}: {}", program_id, mapping, e),
+                    "Could not reach the node".to_string(),
+                ))
+            }
+        };
+
+        match value {
+            Some(value) => Ok(Some(Value::<N>::from_str(&value)?)),
+            None => Ok(None),
+        }
+    })
+}
+
+/// Lists the keys of a mapping on nodes that serve them, most nodes only answer lookups of single keys
+pub fn fetch_mapping_keys(program_id: &str, mapping: &str) -> AvailResult<Vec<String>> {
+    with_failover(|url| {
+        let endpoint = format!(
+            "{}/testnet/program/{}/mapping/{}/keys",
+            url, program_id, mapping
+        );
+
+        match ureq::get(&endpoint).call() {
+            Ok(response) => Ok(response.into_json::<Vec<String>>()?),
+            Err(ureq::Error::Status(404, _)) | Err(ureq::Error::Status(405, _)) => {
+                Err(AvailError::new(
+                    AvailErrorType::Node,
+                    format!("{} doesn't list mapping keys", url),
+                    "The node doesn't support listing mapping keys".to_string(),
+                ))
+            }
+            Err(e) => Err(AvailError::new(
+                AvailErrorType::Network,
+                format!("Error listing keys of {}/{}: {}", program_id, mapping, e),
+                "Could not reach the node".to_string(),
+            )),
+        }
+    })
+}
+
+/// Value of a mapping key, the key is checked against the key type of the mapping first
+pub fn get_mapping_value_raw<N: Network>(
+    request: &MappingValueRequest,
+) -> AvailResult<Option<String>> {
+    let program = get_program::<N>(request.program_id())?;
+    let key = coerce_mapping_key(&program, request.mapping(), request.key())?;
+
+    let value = fetch_mapping_value::<N>(&program.id().to_string(), request.mapping(), &key)?;
+
+    Ok(value.map(|value| value.to_string()))
+}
+
+/// Keys of a mapping in canonical form, keys that don't match the key type are left out
+pub fn get_mapping_keys_raw<N: Network>(request: &MappingKeysRequest) -> AvailResult<Vec<String>> {
+    let program = get_program::<N>(request.program_id())?;
+    mapping_key_type(&program, request.mapping())?;
+
+    let keys = fetch_mapping_keys(&program.id().to_string(), request.mapping())?;
+
+    Ok(keys
+        .iter()
+        .filter_map(|key| coerce_mapping_key(&program, request.mapping(), key).ok())
+        .collect())
+}
@@ -0,0 +1,747 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Window};
+use tauri_plugin_http::reqwest;
+
+use avail_common::{
+    aleo_tools::program_manager::TransferType,
+    errors::{AvailError, AvailErrorType, AvailResult},
+};
+
+use crate::services::local_storage::persistent_storage::get_prover_endpoint;
+
+/// Where snarkVM downloads its testnet proving keys from.
+const PARAMETERS_REMOTE_URL: &str = "https://parameters.aleo.org/testnet";
+
+/// Checksums and sizes of the parameter files for the snarkVM revision the wallet is built with,
+/// only needed for names snarkVM does not compile in.
+const PARAMETERS_METADATA_URL: &str = "https://raw.githubusercontent.com/AleoNet/snarkVM/be171ce0720544c2bc51e27e1674a62fff585adc/parameters/src/testnet/resources";
+
+/// Every credits.aleo function that needs a proving key, plus the inclusion circuit used for private inputs.
+pub const CREDITS_FUNCTIONS: [&str; 15] = [
+    "bond_public",
+    "bond_validator",
+    "unbond_public",
+    "claim_unbond_public",
+    "set_validator_state",
+    "transfer_public",
+    "transfer_public_as_signer",
+    "transfer_private",
+    "transfer_private_to_public",
+    "transfer_public_to_private",
+    "join",
+    "split",
+    "fee_private",
+    "fee_public",
+    "upgrade",
+];
+
+pub const INCLUSION: &str = "inclusion";
+
+/// Emitted while a parameter file downloads, throttled to roughly one event per megabyte.
+const PROGRESS_INTERVAL: u64 = 1_000_000;
+
+/// Metadata file format used by snarkVM for its parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterMetadata {
+    pub prover_checksum: String,
+    pub prover_size: u64,
+    pub verifier_checksum: String,
+    pub verifier_size: u64,
+}
+
+/// A proving key file, verifying keys ship inside snarkVM and never need to be downloaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterFile {
+    pub name: String,
+    pub checksum: String,
+    pub size: u64,
+}
+
+impl ParameterFile {
+    pub fn from_metadata(name: &str, metadata: &ParameterMetadata) -> Self {
+        Self {
+            name: name.to_string(),
+            checksum: metadata.prover_checksum.clone(),
+            size: metadata.prover_size,
+        }
+    }
+
+    /// File name snarkVM looks for, i.e. transfer_public.prover.1a2b3c4
+    pub fn filename(&self) -> String {
+        let checksum = self.checksum.get(0..7).unwrap_or(&self.checksum);
+        format!("{}.prover.{}", self.name, checksum)
+    }
+
+    pub fn path(&self, resources_dir: &Path) -> PathBuf {
+        resources_dir.join(self.filename())
+    }
+
+    fn partial_path(&self, resources_dir: &Path) -> PathBuf {
+        resources_dir.join(format!("{}.partial", self.filename()))
+    }
+
+    /// Cheap check used before transfers, checksums are verified when files are written.
+    pub fn is_installed(&self, resources_dir: &Path) -> bool {
+        match fs::metadata(self.path(resources_dir)) {
+            Ok(metadata) => metadata.len() == self.size,
+            Err(_) => false,
+        }
+    }
+
+    pub fn verify(&self, path: &Path) -> AvailResult<()> {
+        let checksum = sha256_file(path)?;
+
+        if checksum != self.checksum {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!(
+                    "Checksum mismatch for {}, expected {} found {}",
+                    self.filename(),
+                    self.checksum,
+                    checksum
+                ),
+                format!("Aleo resource {} is corrupted", self.name),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingParameter {
+    pub name: String,
+    pub filename: String,
+    pub size: u64,
+    pub downloaded: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterReport {
+    pub missing: Vec<MissingParameter>,
+    pub missing_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterProgress {
+    pub name: String,
+    pub downloaded: u64,
+    pub total: u64,
+}
+
+/// Directory snarkVM loads its parameters from.
+pub fn resources_dir() -> AvailResult<PathBuf> {
+    match dirs::home_dir() {
+        Some(home_dir) => Ok(home_dir.join(".aleo").join("resources")),
+        None => Err(AvailError::new(
+            AvailErrorType::Internal,
+            "Error getting home directory".to_string(),
+            "Error getting home directory".to_string(),
+        )),
+    }
+}
+
+fn sha256_file(path: &Path) -> AvailResult<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Metadata snarkVM compiles into the wallet, the only checksums an offline bundle is checked against.
+fn compiled_metadata(name: &str) -> Option<&'static str> {
+    use snarkvm::parameters::testnet::*;
+
+    match name {
+        "bond_public" => Some(BondPublicProver::METADATA),
+        "bond_validator" => Some(BondValidatorProver::METADATA),
+        "unbond_public" => Some(UnbondPublicProver::METADATA),
+        "claim_unbond_public" => Some(ClaimUnbondPublicProver::METADATA),
+        "set_validator_state" => Some(SetValidatorStateProver::METADATA),
+        "transfer_public" => Some(TransferPublicProver::METADATA),
+        "transfer_public_as_signer" => Some(TransferPublicAsSignerProver::METADATA),
+        "transfer_private" => Some(TransferPrivateProver::METADATA),
+        "transfer_private_to_public" => Some(TransferPrivateToPublicProver::METADATA),
+        "transfer_public_to_private" => Some(TransferPublicToPrivateProver::METADATA),
+        "join" => Some(JoinProver::METADATA),
+        "split" => Some(SplitProver::METADATA),
+        "fee_private" => Some(FeePrivateProver::METADATA),
+        "fee_public" => Some(FeePublicProver::METADATA),
+        "upgrade" => Some(UpgradeProver::METADATA),
+        INCLUSION => Some(InclusionProver::METADATA),
+        _ => None,
+    }
+}
+
+pub fn trusted_metadata(name: &str) -> Option<ParameterMetadata> {
+    serde_json::from_str::<ParameterMetadata>(compiled_metadata(name)?).ok()
+}
+
+fn metadata_path(resources_dir: &Path, name: &str) -> PathBuf {
+    resources_dir.join(format!("{}.metadata", name))
+}
+
+fn read_cached_metadata(resources_dir: &Path, name: &str) -> Option<ParameterMetadata> {
+    let metadata = fs::read_to_string(metadata_path(resources_dir, name)).ok()?;
+    serde_json::from_str::<ParameterMetadata>(&metadata).ok()
+}
+
+async fn fetch_metadata(name: &str) -> AvailResult<ParameterMetadata> {
+    let url = format!("{}/{}.metadata", PARAMETERS_METADATA_URL, name);
+
+    let res = match reqwest::get(&url).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Network,
+                format!("Error fetching {}.metadata: {:?}", name, e),
+                "Error fetching Aleo resources metadata".to_string(),
+            ))
+        }
+    };
+
+    if !res.status().is_success() {
+        return Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("{}.metadata not found: {}", name, res.status()),
+            format!("Unknown Aleo resource {}", name),
+        ));
+    }
+
+    match res.json::<ParameterMetadata>().await {
+        Ok(metadata) => Ok(metadata),
+        Err(e) => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            format!("Error parsing {}.metadata: {:?}", name, e),
+            "Error reading Aleo resources metadata".to_string(),
+        )),
+    }
+}
+
+/// Loads the parameter files for the given functions, fetching and caching metadata that is not known yet.
+pub async fn get_manifest(resources_dir: &Path, names: &[String]) -> AvailResult<Vec<ParameterFile>> {
+    fs::create_dir_all(resources_dir)?;
+
+    let mut manifest = vec![];
+
+    for name in names {
+        let cached = trusted_metadata(name).or_else(|| read_cached_metadata(resources_dir, name));
+
+        let metadata = match cached {
+            Some(metadata) => metadata,
+            None => {
+                let metadata = fetch_metadata(name).await?;
+                fs::write(
+                    metadata_path(resources_dir, name),
+                    serde_json::to_string(&metadata)?,
+                )?;
+                metadata
+            }
+        };
+
+        manifest.push(ParameterFile::from_metadata(name, &metadata));
+    }
+
+    Ok(manifest)
+}
+
+pub fn missing_parameters(resources_dir: &Path, manifest: &[ParameterFile]) -> ParameterReport {
+    let missing = manifest
+        .iter()
+        .filter(|file| !file.is_installed(resources_dir))
+        .map(|file| MissingParameter {
+            name: file.name.clone(),
+            filename: file.filename(),
+            size: file.size,
+            downloaded: fs::metadata(file.partial_path(resources_dir))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0),
+        })
+        .collect::<Vec<MissingParameter>>();
+
+    let missing_bytes = missing
+        .iter()
+        .map(|file| file.size - file.downloaded.min(file.size))
+        .sum();
+
+    ParameterReport {
+        missing,
+        missing_bytes,
+    }
+}
+
+/// Downloads a parameter file, resuming from a previous partial download when possible.
+pub async fn download_parameter(
+    resources_dir: &Path,
+    file: &ParameterFile,
+    window: Option<Window>,
+) -> AvailResult<()> {
+    let partial_path = file.partial_path(resources_dir);
+    let mut downloaded = fs::metadata(&partial_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+
+    // a partial file that is already complete only needs to be verified
+    if downloaded < file.size {
+        let url = format!("{}/{}", PARAMETERS_REMOTE_URL, file.filename());
+        let mut request = reqwest::Client::new().get(&url);
+
+        if downloaded > 0 {
+            request = request.header("Range", format!("bytes={}-", downloaded));
+        }
+
+        let mut res = match request.send().await {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Network,
+                    format!("Error downloading {}: {:?}", file.filename(), e),
+                    "Error downloading Aleo resources".to_string(),
+                ))
+            }
+        };
+
+        // servers that ignore the range header send the whole file again
+        match res.status().as_u16() {
+            206 => {}
+            200 => downloaded = 0,
+            status => {
+                return Err(AvailError::new(
+                    AvailErrorType::Network,
+                    format!("Error downloading {}: status {}", file.filename(), status),
+                    "Error downloading Aleo resources".to_string(),
+                ))
+            }
+        }
+
+        let mut partial = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(downloaded > 0)
+            .truncate(downloaded == 0)
+            .open(&partial_path)?;
+
+        let mut last_progress = downloaded;
+
+        loop {
+            let chunk = match res.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    return Err(AvailError::new(
+                        AvailErrorType::Network,
+                        format!("Error downloading {}: {:?}", file.filename(), e),
+                        "Download of Aleo resources was interrupted".to_string(),
+                    ))
+                }
+            };
+
+            partial.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+
+            if downloaded - last_progress >= PROGRESS_INTERVAL || downloaded >= file.size {
+                last_progress = downloaded;
+                emit_progress(&window, file, downloaded)?;
+            }
+        }
+
+        partial.flush()?;
+    }
+
+    if let Err(e) = file.verify(&partial_path) {
+        fs::remove_file(&partial_path)?;
+        return Err(e);
+    }
+
+    fs::rename(&partial_path, file.path(resources_dir))?;
+
+    Ok(())
+}
+
+fn emit_progress(window: &Option<Window>, file: &ParameterFile, downloaded: u64) -> AvailResult<()> {
+    if let Some(window) = window {
+        let progress = ParameterProgress {
+            name: file.name.clone(),
+            downloaded,
+            total: file.size,
+        };
+
+        match window.emit("parameter_download_progress", &progress) {
+            Ok(_) => {}
+            Err(_) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    "Error emitting parameter_download_progress event".to_string(),
+                    "Error emitting Aleo resources download progress".to_string(),
+                ));
+            }
+        };
+    }
+
+    Ok(())
+}
+
+/// Copies parameter files from an offline bundle directory into the resources directory.
+/// Files are checked against the trusted checksums, metadata shipped inside the bundle is never used.
+pub fn import_bundle(
+    bundle_dir: &Path,
+    resources_dir: &Path,
+    names: &[String],
+    trusted: impl Fn(&str) -> Option<ParameterMetadata>,
+) -> AvailResult<Vec<String>> {
+    fs::create_dir_all(resources_dir)?;
+
+    let mut imported = vec![];
+
+    for name in names {
+        let metadata = match trusted(name) {
+            Some(metadata) => metadata,
+            None => {
+                return Err(AvailError::new(
+                    AvailErrorType::Validation,
+                    format!("No trusted checksum for {}", name),
+                    format!("Aleo resource {} can't be imported from a bundle", name),
+                ))
+            }
+        };
+
+        let file = ParameterFile::from_metadata(name, &metadata);
+        let bundle_path = file.path(bundle_dir);
+
+        if file.is_installed(resources_dir) || !bundle_path.exists() {
+            continue;
+        }
+
+        file.verify(&bundle_path)?;
+        fs::copy(&bundle_path, file.path(resources_dir))?;
+
+        imported.push(file.filename());
+    }
+
+    Ok(imported)
+}
+
+/// Parameters needed to prove a transfer and its fee, other token programs only need the fee keys.
+pub fn transfer_parameters(
+    asset_id: &str,
+    transfer_type: &TransferType,
+    fee_private: bool,
+) -> Vec<String> {
+    let function = match transfer_type {
+        TransferType::Private => "transfer_private",
+        TransferType::PrivateToPublic => "transfer_private_to_public",
+        TransferType::PublicToPrivate => "transfer_public_to_private",
+        TransferType::Public => "transfer_public",
+    };
+
+    let mut names = vec![];
+
+    if asset_id == "credits" {
+        names.push(function.to_string());
+    }
+
+    match fee_private {
+        true => names.push("fee_private".to_string()),
+        false => names.push("fee_public".to_string()),
+    }
+
+    let private_inputs = matches!(
+        transfer_type,
+        TransferType::Private | TransferType::PrivateToPublic
+    );
+
+    if private_inputs || fee_private {
+        names.push(INCLUSION.to_string());
+    }
+
+    names
+}
+
+/// Fails before any records are touched when the proving keys for a transfer are not installed.
+pub async fn ensure_transfer_parameters(
+    asset_id: &str,
+    transfer_type: &TransferType,
+    fee_private: bool,
+) -> AvailResult<()> {
+    ensure_parameters(&transfer_parameters(asset_id, transfer_type, fee_private)).await
+}
+
+/// Fails when any of the given proving keys is not installed.
+pub async fn ensure_parameters(names: &[String]) -> AvailResult<()> {
+    // a delegated prover holds its own keys
+    if get_prover_endpoint()?.is_some() {
+        return Ok(());
+    }
+
+    let resources_dir = resources_dir()?;
+    let manifest = get_manifest(&resources_dir, names).await?;
+    let report = missing_parameters(&resources_dir, &manifest);
+
+    if !report.missing.is_empty() {
+        let names = report
+            .missing
+            .iter()
+            .map(|file| file.name.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        return Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("Missing proving keys: {}", names),
+            format!(
+                "Aleo resources for {} need to be downloaded before this transaction",
+                names
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn all_parameters() -> Vec<String> {
+    CREDITS_FUNCTIONS
+        .iter()
+        .map(|name| name.to_string())
+        .chain(std::iter::once(INCLUSION.to_string()))
+        .collect()
+}
+
+/// Reports which proving keys are missing, defaults to every credits.aleo function
+#[tauri::command(rename_all = "snake_case")]
+pub async fn check_parameters(functions: Option<Vec<String>>) -> AvailResult<ParameterReport> {
+    let resources_dir = resources_dir()?;
+    let names = functions.unwrap_or_else(all_parameters);
+    let manifest = get_manifest(&resources_dir, &names).await?;
+
+    Ok(missing_parameters(&resources_dir, &manifest))
+}
+
+/// Downloads missing proving keys, defaults to every credits.aleo function
+#[tauri::command(rename_all = "snake_case")]
+pub async fn download_parameters(
+    functions: Option<Vec<String>>,
+    window: Window,
+) -> AvailResult<()> {
+    let resources_dir = resources_dir()?;
+    let names = functions.unwrap_or_else(all_parameters);
+    let manifest = get_manifest(&resources_dir, &names).await?;
+
+    for file in manifest {
+        if !file.is_installed(&resources_dir) {
+            download_parameter(&resources_dir, &file, Some(window.clone())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Imports proving keys from a directory holding the .prover files
+#[tauri::command(rename_all = "snake_case")]
+pub fn import_parameter_bundle(path: String) -> AvailResult<Vec<String>> {
+    let bundle_dir = PathBuf::from(path);
+
+    if !bundle_dir.is_dir() {
+        return Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("Bundle directory {:?} not found", bundle_dir),
+            "Bundle directory not found".to_string(),
+        ));
+    }
+
+    import_bundle(
+        &bundle_dir,
+        &resources_dir()?,
+        &all_parameters(),
+        trusted_metadata,
+    )
+}
+
+#[cfg(test)]
+mod parameters_tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("avail_parameters_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn metadata_of(bytes: &[u8]) -> ParameterMetadata {
+        ParameterMetadata {
+            prover_checksum: hex::encode(Sha256::digest(bytes)),
+            prover_size: bytes.len() as u64,
+            verifier_checksum: String::new(),
+            verifier_size: 0,
+        }
+    }
+
+    fn write_parameter(dir: &Path, name: &str, bytes: &[u8]) -> ParameterFile {
+        let metadata = metadata_of(bytes);
+
+        fs::write(
+            metadata_path(dir, name),
+            serde_json::to_string(&metadata).unwrap(),
+        )
+        .unwrap();
+
+        let file = ParameterFile::from_metadata(name, &metadata);
+        fs::write(file.path(dir), bytes).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_filename() {
+        let file = ParameterFile {
+            name: "transfer_public".to_string(),
+            checksum: "1a2b3c4d5e6f".to_string(),
+            size: 1,
+        };
+
+        assert_eq!(file.filename(), "transfer_public.prover.1a2b3c4");
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let dir = test_dir("verify");
+        let file = write_parameter(&dir, "transfer_public", b"proving key");
+
+        file.verify(&file.path(&dir)).unwrap();
+
+        fs::write(file.path(&dir), b"proving kez").unwrap();
+        assert!(file.verify(&file.path(&dir)).is_err());
+    }
+
+    #[test]
+    fn test_missing_report() {
+        let dir = test_dir("report");
+        let installed = write_parameter(&dir, "transfer_public", b"proving key");
+
+        let missing = ParameterFile {
+            name: "fee_public".to_string(),
+            checksum: "abcdef0123".to_string(),
+            size: 100,
+        };
+        fs::write(missing.partial_path(&dir), [0u8; 40]).unwrap();
+
+        let report = missing_parameters(&dir, &[installed, missing]);
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].name, "fee_public");
+        assert_eq!(report.missing[0].downloaded, 40);
+        assert_eq!(report.missing_bytes, 60);
+    }
+
+    #[test]
+    fn test_import_bundle() {
+        let bundle_dir = test_dir("bundle");
+        let resources_dir = test_dir("bundle_resources");
+
+        let file = write_parameter(&bundle_dir, "transfer_private", b"private proving key");
+        let names = vec!["transfer_private".to_string(), "join".to_string()];
+
+        let imported = import_bundle(&bundle_dir, &resources_dir, &names, |name| match name {
+            "transfer_private" => Some(metadata_of(b"private proving key")),
+            "join" => Some(metadata_of(b"join proving key")),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(imported, vec![file.filename()]);
+        assert!(file.is_installed(&resources_dir));
+    }
+
+    #[test]
+    fn test_import_rejects_corrupted_bundle() {
+        let bundle_dir = test_dir("corrupted_bundle");
+        let resources_dir = test_dir("corrupted_resources");
+
+        let file = write_parameter(&bundle_dir, "transfer_private", b"private proving key");
+        fs::write(file.path(&bundle_dir), b"private proving kez").unwrap();
+
+        let res = import_bundle(
+            &bundle_dir,
+            &resources_dir,
+            &["transfer_private".to_string()],
+            |_| Some(metadata_of(b"private proving key")),
+        );
+
+        assert!(res.is_err());
+        assert!(!file.path(&resources_dir).exists());
+    }
+
+    #[test]
+    fn test_import_ignores_bundle_metadata() {
+        let bundle_dir = test_dir("forged_bundle");
+        let resources_dir = test_dir("forged_resources");
+
+        // the bundle's own metadata matches its file, but not the trusted checksum
+        let forged = write_parameter(&bundle_dir, "transfer_private", b"forged proving key");
+        let names = vec!["transfer_private".to_string()];
+
+        let imported = import_bundle(&bundle_dir, &resources_dir, &names, |_| {
+            Some(metadata_of(b"private proving key"))
+        })
+        .unwrap();
+
+        assert!(imported.is_empty());
+        assert!(!forged.path(&resources_dir).exists());
+        assert!(!metadata_path(&resources_dir, "transfer_private").exists());
+
+        let res = import_bundle(&bundle_dir, &resources_dir, &names, |_| None);
+        assert!(res.is_err());
+        assert!(!forged.path(&resources_dir).exists());
+    }
+
+    #[test]
+    fn test_trusted_metadata() {
+        for name in all_parameters() {
+            let metadata = trusted_metadata(&name).unwrap();
+            assert_eq!(metadata.prover_checksum.len(), 64);
+            assert!(metadata.prover_size > 0);
+        }
+
+        assert!(trusted_metadata("diamond_token").is_none());
+    }
+
+    #[test]
+    fn test_transfer_parameters() {
+        let names = transfer_parameters("credits", &TransferType::Public, false);
+        assert_eq!(names, vec!["transfer_public", "fee_public"]);
+
+        let names = transfer_parameters("credits", &TransferType::Private, true);
+        assert_eq!(names, vec!["transfer_private", "fee_private", INCLUSION]);
+
+        let names = transfer_parameters("diamond_token", &TransferType::Public, false);
+        assert_eq!(names, vec!["fee_public"]);
+    }
+
+    #[tokio::test]
+    async fn test_check_parameters() {
+        let report = check_parameters(Some(vec!["transfer_public".to_string()]))
+            .await
+            .unwrap();
+
+        let file = ParameterFile::from_metadata(
+            "transfer_public",
+            &trusted_metadata("transfer_public").unwrap(),
+        );
+
+        assert!(report.missing.len() <= 1);
+        assert!(report.missing_bytes <= file.size);
+
+        if let Some(missing) = report.missing.first() {
+            assert_eq!(missing.name, "transfer_public");
+            assert_eq!(missing.filename, file.filename());
+            assert_eq!(missing.size, file.size);
+            assert_eq!(report.missing_bytes, file.size - missing.downloaded);
+        } else {
+            assert_eq!(report.missing_bytes, 0);
+        }
+    }
+}
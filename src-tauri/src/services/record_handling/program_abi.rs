@@ -0,0 +1,482 @@
+use snarkvm::prelude::{
+    Address, Identifier, Literal, LiteralType, Network, Plaintext, PlaintextType, Program, Record,
+    TestnetV0, ValueType,
+};
+use std::str::FromStr;
+
+use crate::api::aleo_client::setup_client;
+use crate::models::wallet_connect::{
+    abi::{FunctionAbi, InputAbi, MappingAbi, ProgramAbi},
+    create_event::CreateEventRequest,
+};
+use crate::services::local_storage::{
+    persistent_storage::{get_address, get_network},
+    program_cache::{get_cached_program, store_cached_program},
+    storage_api::records::get_record_spent_local,
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::network::SupportedNetworks,
+};
+
+/// Fetches a program, sources are cached in `program_cache`
+pub fn get_program<N: Network>(program_id: &str) -> AvailResult<Program<N>> {
+    if program_id == "credits.aleo" {
+        return Ok(Program::<N>::credits()?);
+    }
+
+    if let Some(source) = get_cached_program(program_id)? {
+        if let Ok(program) = Program::<N>::from_str(&source) {
+            return Ok(program);
+        }
+    }
+
+    let program = match setup_client::<N>()?.get_program(program_id) {
+        Ok(program) => program,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Node,
+                format!("Error fetching program {}: {}", program_id, e),
+                format!("Program {} could not be found", program_id),
+            ))
+        }
+    };
+    store_cached_program(program_id, &program.to_string())?;
+
+    Ok(program)
+}
+
+pub fn input_abi<N: Network>(value_type: &ValueType<N>) -> (String, &'static str) {
+    match value_type {
+        ValueType::Constant(plaintext_type) => (plaintext_type.to_string(), "constant"),
+        ValueType::Public(plaintext_type) => (plaintext_type.to_string(), "public"),
+        ValueType::Private(plaintext_type) => (plaintext_type.to_string(), "private"),
+        ValueType::Record(name) => (format!("{}.record", name), "record"),
+        ValueType::ExternalRecord(locator) => (format!("{}.record", locator), "external_record"),
+        ValueType::Future(locator) => (format!("{}.future", locator), "future"),
+    }
+}
+
+pub fn program_abi<N: Network>(program: &Program<N>) -> ProgramAbi {
+    ProgramAbi {
+        program_id: program.id().to_string(),
+        imports: program.imports().keys().map(|id| id.to_string()).collect(),
+        functions: program
+            .functions()
+            .values()
+            .map(|function| FunctionAbi {
+                name: function.name().to_string(),
+                inputs: function
+                    .inputs()
+                    .iter()
+                    .map(|input| {
+                        let (value_type, visibility) = input_abi(input.value_type());
+
+                        InputAbi {
+                            register: input.register().to_string(),
+                            value_type,
+                            visibility: visibility.to_string(),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect(),
+        mappings: program
+            .mappings()
+            .values()
+            .map(|mapping| MappingAbi {
+                name: mapping.name().to_string(),
+                key_type: mapping.key().plaintext_type().to_string(),
+                value_type: mapping.value().plaintext_type().to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Parses a literal of the expected type, integers, fields, scalars and groups may leave out their type suffix
+fn coerce_literal<N: Network>(literal_type: &LiteralType, input: &str) -> Option<Literal<N>> {
+    let literal = match Literal::<N>::from_str(input) {
+        Ok(literal) => literal,
+        Err(_) => Literal::<N>::from_str(&format!("{}{}", input, literal_type)).ok()?,
+    };
+
+    match &literal.to_type() == literal_type {
+        true => Some(literal),
+        false => None,
+    }
+}
+
+/// Checks a plaintext against a type of the program, returns where and why it doesn't match
+fn check_plaintext<N: Network>(
+    program: &Program<N>,
+    plaintext: &Plaintext<N>,
+    plaintext_type: &PlaintextType<N>,
+) -> Result<(), String> {
+    match (plaintext_type, plaintext) {
+        (PlaintextType::Literal(literal_type), Plaintext::Literal(literal, _)) => {
+            match &literal.to_type() == literal_type {
+                true => Ok(()),
+                false => Err(format!("expected {}, got {}", literal_type, literal)),
+            }
+        }
+        (PlaintextType::Struct(name), Plaintext::Struct(members, _)) => {
+            let struct_type = program
+                .get_struct(name)
+                .map_err(|_| format!("unknown struct {}", name))?;
+
+            if let Some(member) = members
+                .keys()
+                .find(|member| !struct_type.members().contains_key(*member))
+            {
+                return Err(format!("{} has no member {}", name, member));
+            }
+
+            for (member_name, member_type) in struct_type.members() {
+                let member = members
+                    .get(member_name)
+                    .ok_or_else(|| format!("{} is missing {}", name, member_name))?;
+
+                check_plaintext(program, member, member_type)
+                    .map_err(|e| format!("{}.{}: {}", name, member_name, e))?;
+            }
+
+            Ok(())
+        }
+        (PlaintextType::Array(array_type), Plaintext::Array(elements, _)) => {
+            if elements.len() != **array_type.length() as usize {
+                return Err(format!(
+                    "expected {} elements, got {}",
+                    **array_type.length(),
+                    elements.len()
+                ));
+            }
+
+            for (index, element) in elements.iter().enumerate() {
+                check_plaintext(program, element, array_type.next_element_type())
+                    .map_err(|e| format!("[{}]: {}", index, e))?;
+            }
+
+            Ok(())
+        }
+        (plaintext_type, _) => Err(format!("expected {}", plaintext_type)),
+    }
+}
+
+/// Records have to belong to the wallet and must not be spent
+fn check_record<N: Network>(
+    record: &Record<N, Plaintext<N>>,
+    owner: &Address<N>,
+) -> Result<(), String> {
+    if &**record.owner() != owner {
+        return Err("the record isn't owned by this wallet".to_string());
+    }
+
+    match get_record_spent_local::<N>(&record.nonce().to_string()) {
+        Ok(Some(true)) => Err("the record is already spent".to_string()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.external_msg),
+    }
+}
+
+/// Parses a plaintext of the expected type and returns it in canonical form
+fn coerce_plaintext<N: Network>(
+    program: &Program<N>,
+    plaintext_type: &PlaintextType<N>,
+    input: &str,
+) -> Result<String, String> {
+    if let PlaintextType::Literal(literal_type) = plaintext_type {
+        return coerce_literal::<N>(literal_type, input)
+            .map(|literal| literal.to_string())
+            .ok_or_else(|| format!("expected {}, got '{}'", literal_type, input));
+    }
+
+    let plaintext = Plaintext::<N>::from_str(input)
+        .map_err(|_| format!("expected {}, got '{}'", plaintext_type, input))?;
+    check_plaintext(program, &plaintext, plaintext_type)?;
+
+    Ok(plaintext.to_string())
+}
+
+/// Validates a single input against its type and returns it in canonical form
+fn coerce_input<N: Network>(
+    program: &Program<N>,
+    value_type: &ValueType<N>,
+    input: &str,
+    owner: &Address<N>,
+) -> Result<String, String> {
+    let input = input.trim();
+
+    match value_type {
+        ValueType::Constant(plaintext_type)
+        | ValueType::Public(plaintext_type)
+        | ValueType::Private(plaintext_type) => coerce_plaintext(program, plaintext_type, input),
+        ValueType::Record(name) => {
+            let record = Record::<N, Plaintext<N>>::from_str(input)
+                .map_err(|_| format!("expected a plaintext {} record", name))?;
+
+            let record_type = program
+                .get_record(name)
+                .map_err(|_| format!("unknown record {}", name))?;
+            if record.data().len() != record_type.entries().len()
+                || record_type
+                    .entries()
+                    .keys()
+                    .any(|entry| !record.data().contains_key(entry))
+            {
+                return Err(format!("the record is not a {} record", name));
+            }
+
+            check_record(&record, owner)?;
+
+            Ok(record.to_string())
+        }
+        ValueType::ExternalRecord(locator) => {
+            let record = Record::<N, Plaintext<N>>::from_str(input)
+                .map_err(|_| format!("expected a plaintext {} record", locator))?;
+            check_record(&record, owner)?;
+
+            Ok(record.to_string())
+        }
+        ValueType::Future(_) => Err("futures can't be passed as inputs".to_string()),
+    }
+}
+
+/// Checks every input of a function call before anything is proven.
+/// Returns the inputs in canonical form, or one error naming every input that doesn't match.
+pub fn validate_inputs<N: Network>(
+    program: &Program<N>,
+    function_id: &str,
+    inputs: &[String],
+    owner: &Address<N>,
+) -> AvailResult<Vec<String>> {
+    let invalid = |internal: String| {
+        AvailError::new(
+            AvailErrorType::Validation,
+            internal.clone(),
+            format!("Invalid inputs for {}/{}: {}", program.id(), function_id, internal),
+        )
+    };
+
+    let function = Identifier::<N>::from_str(function_id)
+        .ok()
+        .and_then(|function_name| program.get_function(&function_name).ok())
+        .ok_or_else(|| invalid(format!("function {} doesn't exist", function_id)))?;
+
+    if function.inputs().len() != inputs.len() {
+        return Err(invalid(format!(
+            "expected {} inputs, got {}",
+            function.inputs().len(),
+            inputs.len()
+        )));
+    }
+
+    let mut coerced = vec![];
+    let mut errors = vec![];
+    for (index, (expected, input)) in function.inputs().iter().zip(inputs).enumerate() {
+        match coerce_input(program, expected.value_type(), input, owner) {
+            Ok(input) => coerced.push(input),
+            Err(e) => errors.push(format!(
+                "input {} ({} as {}): {}",
+                index + 1,
+                expected.register(),
+                expected.value_type(),
+                e
+            )),
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(coerced),
+        false => Err(invalid(errors.join("; "))),
+    }
+}
+
+/// Key type of a mapping the program declares
+pub fn mapping_key_type<N: Network>(
+    program: &Program<N>,
+    mapping: &str,
+) -> AvailResult<PlaintextType<N>> {
+    Identifier::<N>::from_str(mapping)
+        .ok()
+        .and_then(|mapping_name| program.get_mapping(&mapping_name).ok())
+        .map(|mapping| mapping.key().plaintext_type().clone())
+        .ok_or_else(|| {
+            AvailError::new(
+                AvailErrorType::Validation,
+                format!("Mapping {}/{} doesn't exist", program.id(), mapping),
+                format!("{} has no mapping {}", program.id(), mapping),
+            )
+        })
+}
+
+/// Checks a key against the key type of a mapping and returns it in canonical form
+pub fn coerce_mapping_key<N: Network>(
+    program: &Program<N>,
+    mapping: &str,
+    key: &str,
+) -> AvailResult<String> {
+    let key_type = mapping_key_type(program, mapping)?;
+
+    coerce_plaintext(program, &key_type, key.trim()).map_err(|e| {
+        AvailError::new(
+            AvailErrorType::Validation,
+            e.clone(),
+            format!("Invalid key for {}/{}: {}", program.id(), mapping, e),
+        )
+    })
+}
+
+/// Validates the inputs of an execution request against the program and coerces them
+pub fn validate_request_inputs<N: Network>(
+    request: &CreateEventRequest,
+) -> AvailResult<CreateEventRequest> {
+    let program = get_program::<N>(request.program_id())?;
+    let inputs = validate_inputs(
+        &program,
+        request.function_id(),
+        request.inputs(),
+        &get_address::<N>()?,
+    )?;
+
+    Ok(CreateEventRequest::new(
+        request.address().cloned(),
+        request.event_type().to_owned(),
+        request.program_id().clone(),
+        request.function_id().clone(),
+        request.fee(),
+        inputs,
+    ))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_program_abi(program_id: String) -> AvailResult<ProgramAbi> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => Ok(program_abi(&get_program::<TestnetV0>(&program_id)?)),
+        _ => Ok(program_abi(&get_program::<TestnetV0>(&program_id)?)),
+    }
+}
+
+/// Lets dapps and the wallet screens check inputs before requesting an execution
+#[tauri::command(rename_all = "snake_case")]
+pub fn validate_program_inputs(
+    program_id: String,
+    function_id: String,
+    inputs: Vec<String>,
+) -> AvailResult<Vec<String>> {
+    fn validate<N: Network>(
+        program_id: &str,
+        function_id: &str,
+        inputs: &[String],
+    ) -> AvailResult<Vec<String>> {
+        validate_inputs(
+            &get_program::<N>(program_id)?,
+            function_id,
+            inputs,
+            &get_address::<N>()?,
+        )
+    }
+
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => validate::<TestnetV0>(&program_id, &function_id, &inputs),
+        _ => validate::<TestnetV0>(&program_id, &function_id, &inputs),
+    }
+}
+
+#[cfg(test)]
+mod program_abi_tests {
+    use super::*;
+    use snarkvm::prelude::PrivateKey;
+
+    const GAME: &str = r"program game.aleo;
+
+struct Move:
+    x as u8;
+    y as u8;
+
+mapping scores:
+    key as address.public;
+    value as u64.public;
+
+function play:
+    input r0 as address.public;
+    input r1 as u64.private;
+    input r2 as Move.private;
+    input r3 as [u8; 2u32].public;
+    add r1 r1 into r4;
+    output r4 as u64.private;
+";
+
+    fn owner() -> Address<TestnetV0> {
+        let private_key = PrivateKey::<TestnetV0>::new(&mut rand::thread_rng()).unwrap();
+        Address::try_from(&private_key).unwrap()
+    }
+
+    #[test]
+    fn test_program_abi() {
+        let program = Program::<TestnetV0>::from_str(GAME).unwrap();
+        let abi = program_abi(&program);
+
+        assert_eq!(abi.program_id, "game.aleo");
+        assert_eq!(abi.functions.len(), 1);
+        assert_eq!(
+            abi.functions[0].inputs[1],
+            InputAbi {
+                register: "r1".to_string(),
+                value_type: "u64".to_string(),
+                visibility: "private".to_string(),
+            }
+        );
+        assert_eq!(abi.functions[0].inputs[2].value_type, "Move");
+        assert_eq!(
+            abi.mappings,
+            vec![MappingAbi {
+                name: "scores".to_string(),
+                key_type: "address".to_string(),
+                value_type: "u64".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_coerce_mapping_key() {
+        let program = Program::<TestnetV0>::from_str(GAME).unwrap();
+        let owner = owner();
+
+        assert_eq!(
+            coerce_mapping_key(&program, "scores", &format!(" {} ", owner)).unwrap(),
+            owner.to_string()
+        );
+        assert!(coerce_mapping_key(&program, "scores", "25u64").is_err());
+        assert!(coerce_mapping_key(&program, "players", &owner.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_validate_inputs() {
+        let program = Program::<TestnetV0>::from_str(GAME).unwrap();
+        let owner = owner();
+
+        let inputs = vec![
+            owner.to_string(),
+            "25".to_string(),
+            "{ x: 1u8, y: 2u8 }".to_string(),
+            "[3u8, 4u8]".to_string(),
+        ];
+        let coerced = validate_inputs(&program, "play", &inputs, &owner).unwrap();
+        assert_eq!(coerced[1], "25u64");
+
+        let inputs = vec![
+            "25u64".to_string(),
+            "25u32".to_string(),
+            "{ x: 1u8 }".to_string(),
+            "[3u8]".to_string(),
+        ];
+        let error = validate_inputs(&program, "play", &inputs, &owner).unwrap_err();
+        for index in ["input 1 (r0", "input 2 (r1", "input 3 (r2", "input 4 (r3"] {
+            assert!(error.external_msg.contains(index));
+        }
+
+        assert!(validate_inputs(&program, "play", &inputs[..1], &owner).is_err());
+        assert!(validate_inputs(&program, "missing", &inputs, &owner).is_err());
+    }
+}
@@ -0,0 +1,743 @@
+use serde::{Deserialize, Serialize};
+use snarkvm::circuit::Aleo;
+use snarkvm::ledger::query::Query;
+use snarkvm::ledger::store::helpers::memory::{BlockMemory, ConsensusMemory};
+use snarkvm::ledger::store::ConsensusStore;
+use snarkvm::prelude::*;
+use snarkvm::synthesizer::{process::execution_cost, VM};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::program_abi::get_program;
+use super::token_registry::{parse_token_id, registry_transfer_inputs, TOKEN_REGISTRY};
+use crate::api::aleo_client::{node_url, setup_client};
+use crate::services::account::signer::Signer;
+use crate::services::local_storage::persistent_storage::{
+    get_network, get_prover_endpoint, get_prover_fallback, update_prover_settings,
+};
+
+use avail_common::{
+    aleo_tools::{
+        api::AleoAPIClient,
+        program_manager::{ProgramManager, TransferType},
+    },
+    errors::{AvailError, AvailErrorType, AvailResult},
+};
+
+/// Turns locally signed authorizations into proofs.
+/// The execution and its fee are proved separately, the fee can only be signed once the cost of the proved execution is known.
+pub trait Prover<N: Network>: Send + Sync {
+    fn prove_execution(&self, authorization: Authorization<N>) -> AvailResult<Execution<N>>;
+
+    fn prove_fee(&self, fee_authorization: Authorization<N>) -> AvailResult<Fee<N>>;
+}
+
+/// Proves authorizations in process using a fresh VM.
+pub struct LocalProver;
+
+impl<N: Network> Prover<N> for LocalProver {
+    fn prove_execution(&self, authorization: Authorization<N>) -> AvailResult<Execution<N>> {
+        let program_ids = authorization
+            .transitions()
+            .values()
+            .map(|transition| *transition.program_id())
+            .collect::<Vec<ProgramID<N>>>();
+
+        let vm = setup_vm::<N>(&program_ids)?;
+        let query = Query::<N, BlockMemory<N>>::from(node_url()?);
+
+        let transaction =
+            vm.execute_authorization(authorization, None, Some(query), &mut rand::thread_rng())?;
+
+        match transaction.execution() {
+            Some(execution) => Ok(execution.clone()),
+            None => Err(AvailError::new(
+                AvailErrorType::Internal,
+                "Proved authorization is not an execution".to_string(),
+                "Error proving the transaction".to_string(),
+            )),
+        }
+    }
+
+    fn prove_fee(&self, fee_authorization: Authorization<N>) -> AvailResult<Fee<N>> {
+        // credits.aleo is part of every process
+        let vm = setup_vm::<N>(&[])?;
+        let query = Query::<N, BlockMemory<N>>::from(node_url()?);
+
+        let fee =
+            vm.execute_fee_authorization(fee_authorization, Some(query), &mut rand::thread_rng())?;
+
+        Ok(fee)
+    }
+}
+
+/// Request body sent to a delegated prover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProveRequest {
+    pub network: String,
+    pub authorization: String,
+}
+
+/// Response body expected from a delegated prover.
+/// Fee authorizations are answered with the proved `fee`, any other authorization with the proved `execution`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProveResponse {
+    pub execution: Option<String>,
+    pub fee: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Sends authorizations to a remote proving service over http.
+pub struct DelegatedProver {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl DelegatedProver {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    fn request<N: Network>(&self, authorization: &Authorization<N>) -> AvailResult<ProveResponse> {
+        let request = ProveRequest {
+            network: get_network()?,
+            authorization: authorization.to_string(),
+        };
+
+        let response = ureq::post(&self.endpoint)
+            .timeout(self.timeout)
+            .send_json(serde_json::to_value(&request)?)
+            .map_err(|e| {
+                AvailError::new(
+                    AvailErrorType::Network,
+                    format!("Error reaching prover at {}: {}", self.endpoint, e),
+                    "Could not reach the delegated prover".to_string(),
+                )
+            })?
+            .into_json::<ProveResponse>()?;
+
+        Ok(response)
+    }
+}
+
+fn missing_proof(error: Option<String>) -> AvailError {
+    AvailError::new(
+        AvailErrorType::External,
+        format!("Prover returned no proof: {:?}", error),
+        "The delegated prover failed to prove the transaction".to_string(),
+    )
+}
+
+impl<N: Network> Prover<N> for DelegatedProver {
+    fn prove_execution(&self, authorization: Authorization<N>) -> AvailResult<Execution<N>> {
+        let response = self.request(&authorization)?;
+
+        let execution = match (response.execution, response.error) {
+            (Some(execution), _) => Execution::<N>::from_str(&execution)?,
+            (None, error) => return Err(missing_proof(error)),
+        };
+
+        // the prover only adds proofs, the transitions must be the ones we signed
+        check_transitions_match(execution.transitions(), &authorization)?;
+
+        Ok(execution)
+    }
+
+    fn prove_fee(&self, fee_authorization: Authorization<N>) -> AvailResult<Fee<N>> {
+        let response = self.request(&fee_authorization)?;
+
+        let fee = match (response.fee, response.error) {
+            (Some(fee), _) => Fee::<N>::from_str(&fee)?,
+            (None, error) => return Err(missing_proof(error)),
+        };
+
+        check_transitions_match(std::iter::once(fee.transition()), &fee_authorization)?;
+
+        Ok(fee)
+    }
+}
+
+/// Checks that proved transitions are exactly the transitions that were authorized.
+pub fn check_transitions_match<'a, N: Network>(
+    transitions: impl Iterator<Item = &'a Transition<N>>,
+    authorization: &Authorization<N>,
+) -> AvailResult<()> {
+    let authorized = authorization.transitions();
+    let found = transitions
+        .map(|transition| transition.id())
+        .collect::<Vec<&N::TransitionID>>();
+
+    if authorized.len() != found.len() || !found.iter().all(|id| authorized.contains_key(*id)) {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "Proved transaction does not match the signed authorization".to_string(),
+            "The delegated prover returned an invalid transaction".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Signs the fee of a proved execution, given its id and base fee.
+pub type FeeAuthorizer<'a, N> = dyn Fn(Field<N>, u64) -> AvailResult<Authorization<N>> + 'a;
+
+/// Proves the execution, then has the fee for its cost authorized and proves it.
+/// The execution is proved once, its size decides the base fee.
+pub fn prove_transaction<N: Network>(
+    prover: &dyn Prover<N>,
+    authorization: Authorization<N>,
+    authorize_fee: &FeeAuthorizer<N>,
+) -> AvailResult<Transaction<N>> {
+    let execution = prover.prove_execution(authorization)?;
+    let base_fee = execution_base_fee::<N>(&execution)?;
+
+    let fee_authorization = authorize_fee(execution.to_execution_id()?, base_fee)?;
+    let fee = prover.prove_fee(fee_authorization)?;
+
+    Ok(Transaction::from_execution(execution, Some(fee))?)
+}
+
+/// Proves with the primary prover, retrying with the fallback prover if one is given.
+pub fn prove_with_fallback<N: Network>(
+    prover: &dyn Prover<N>,
+    fallback: Option<&dyn Prover<N>>,
+    authorization: Authorization<N>,
+    authorize_fee: &FeeAuthorizer<N>,
+) -> AvailResult<Transaction<N>> {
+    match prove_transaction(prover, authorization.clone(), authorize_fee) {
+        Ok(transaction) => Ok(transaction),
+        Err(e) => match fallback {
+            Some(fallback) => {
+                println!("Delegated proving failed, proving locally: {:?}", e);
+                prove_transaction(fallback, authorization, authorize_fee)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Loads the programs and their imports into a memory backed VM.
+fn setup_vm<N: Network>(program_ids: &[ProgramID<N>]) -> AvailResult<VM<N, ConsensusMemory<N>>> {
+    let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None)?;
+    let vm = VM::from(store)?;
+    let api_client = setup_client::<N>()?;
+
+    for program_id in program_ids {
+        add_program_with_imports::<N>(&vm, &api_client, program_id)?;
+    }
+
+    Ok(vm)
+}
+
+fn add_program_with_imports<N: Network>(
+    vm: &VM<N, ConsensusMemory<N>>,
+    api_client: &AleoAPIClient<N>,
+    program_id: &ProgramID<N>,
+) -> AvailResult<()> {
+    if vm.process().read().contains_program(program_id) {
+        return Ok(());
+    }
+
+    let program = api_client.get_program(program_id)?;
+
+    for import_id in program.imports().keys() {
+        add_program_with_imports::<N>(vm, api_client, import_id)?;
+    }
+
+    vm.process().write().add_program(&program)?;
+
+    Ok(())
+}
+
+/// Base fee the network charges for an execution, found by executing it in process.
+/// Records among the inputs have to belong to the key.
+pub fn estimate_base_fee<N: Network, A: Aleo<Network = N>>(
+    private_key: &PrivateKey<N>,
+    program_id: &str,
+    function_name: &str,
+    inputs: &[Value<N>],
+) -> AvailResult<u64> {
+    let program = get_program::<N>(program_id)?;
+    let program_manager =
+        ProgramManager::<N>::new(Some(*private_key), None, Some(setup_client::<N>()?), None)?;
+
+    let (base_fee, (_, _), _) =
+        program_manager.estimate_execution_fee::<A>(&program, function_name, inputs.iter())?;
+
+    Ok(base_fee)
+}
+
+/// Base fee the network charges for a proved execution.
+fn execution_base_fee<N: Network>(execution: &Execution<N>) -> AvailResult<u64> {
+    let program_ids = execution
+        .transitions()
+        .map(|transition| *transition.program_id())
+        .collect::<Vec<ProgramID<N>>>();
+
+    // only loads the programs, nothing is proved again
+    let vm = setup_vm::<N>(&program_ids)?;
+    let (base_fee, (_, _)) = execution_cost(&vm.process().read(), execution)?;
+
+    Ok(base_fee)
+}
+
+/// Signs the authorization of a program call with the local private key.
+pub fn authorize_execution<N: Network>(
+    private_key: &PrivateKey<N>,
+    program_id: &str,
+    function_name: &str,
+    inputs: Vec<Value<N>>,
+) -> AvailResult<Authorization<N>> {
+    let program_id = ProgramID::<N>::from_str(program_id)?;
+    let vm = setup_vm::<N>(&[program_id])?;
+
+    let authorization = vm.authorize(
+        private_key,
+        program_id,
+        function_name,
+        inputs.into_iter(),
+        &mut rand::thread_rng(),
+    )?;
+
+    Ok(authorization)
+}
+
+/// Signs the fee of a proved execution with the local private key.
+/// The fee pays `base_fee` plus `priority_fee`, the same way as a transaction proved by the program manager.
+pub fn authorize_execution_fee<N: Network>(
+    private_key: &PrivateKey<N>,
+    execution_id: Field<N>,
+    base_fee: u64,
+    priority_fee: u64,
+    fee_record: Option<Record<N, Plaintext<N>>>,
+) -> AvailResult<Authorization<N>> {
+    let vm = setup_vm::<N>(&[])?;
+    let rng = &mut rand::thread_rng();
+
+    let fee_authorization = match fee_record {
+        Some(fee_record) => vm.authorize_fee_private(
+            private_key,
+            fee_record,
+            base_fee,
+            priority_fee,
+            execution_id,
+            rng,
+        )?,
+        None => vm.authorize_fee_public(private_key, base_fee, priority_fee, execution_id, rng)?,
+    };
+
+    Ok(fee_authorization)
+}
+
+/// Proves through the configured prover, in process when none is set, and broadcasts the transaction.
+pub fn prove_and_broadcast<N: Network>(
+    authorization: Authorization<N>,
+    authorize_fee: &FeeAuthorizer<N>,
+) -> AvailResult<N::TransactionID> {
+    let local_prover = LocalProver;
+
+    let transaction = match get_prover_endpoint()? {
+        Some(endpoint) => {
+            let delegated_prover = DelegatedProver::new(&endpoint);
+            let fallback: Option<&dyn Prover<N>> = match get_prover_fallback()? {
+                true => Some(&local_prover),
+                false => None,
+            };
+
+            prove_with_fallback::<N>(&delegated_prover, fallback, authorization, authorize_fee)?
+        }
+        None => prove_transaction::<N>(&local_prover, authorization, authorize_fee)?,
+    };
+    let transaction_id = transaction.id();
+
+    let api_client = setup_client::<N>()?;
+    api_client.transaction_broadcast(transaction)?;
+
+    Ok(transaction_id)
+}
+
+/// Runs a transfer through the signer and the configured prover.
+/// Registry tokens are transferred with the token_registry.aleo transfer functions.
+pub fn transfer_with_prover<N: Network>(
+    signer: &dyn Signer<N>,
+    asset_id: &str,
+    amount: u64,
+    priority_fee: u64,
+    recipient: Address<N>,
+    transfer_type: TransferType,
+    amount_record: Option<Record<N, Plaintext<N>>>,
+    fee_record: Option<Record<N, Plaintext<N>>>,
+    program_id: &str,
+) -> AvailResult<N::TransactionID> {
+    let (program_id, function_name, inputs) = match parse_token_id::<N>(asset_id) {
+        Some(token_id) => {
+            let (function_name, inputs) = registry_transfer_inputs::<N>(
+                &token_id,
+                &transfer_type,
+                amount,
+                recipient,
+                amount_record,
+            )?;
+
+            (TOKEN_REGISTRY, function_name, inputs)
+        }
+        None => {
+            let (function_name, inputs) =
+                transfer_inputs::<N>(&transfer_type, amount, recipient, amount_record)?;
+
+            (program_id, function_name, inputs)
+        }
+    };
+
+    execute_with_prover::<N>(
+        signer,
+        program_id,
+        function_name,
+        inputs,
+        priority_fee,
+        fee_record,
+    )
+}
+
+/// Runs a program function through the signer and the configured prover, the private key is never needed here.
+/// `priority_fee` is paid on top of the base fee of the execution.
+pub fn execute_with_prover<N: Network>(
+    signer: &dyn Signer<N>,
+    program_id: &str,
+    function_name: &str,
+    inputs: Vec<Value<N>>,
+    priority_fee: u64,
+    fee_record: Option<Record<N, Plaintext<N>>>,
+) -> AvailResult<N::TransactionID> {
+    let authorization = signer.authorize(program_id, function_name, inputs)?;
+    let authorize_fee = |execution_id: Field<N>, base_fee: u64| {
+        signer.authorize_fee(execution_id, base_fee, priority_fee, fee_record.clone())
+    };
+
+    prove_and_broadcast::<N>(authorization, &authorize_fee)
+}
+
+/// Function name and inputs of a transfer, matching the credits.aleo interface.
+fn transfer_inputs<N: Network>(
+    transfer_type: &TransferType,
+    amount: u64,
+    recipient: Address<N>,
+    amount_record: Option<Record<N, Plaintext<N>>>,
+) -> AvailResult<(&'static str, Vec<Value<N>>)> {
+    let recipient = Value::<N>::from_str(&recipient.to_string())?;
+    let amount = Value::<N>::from_str(&format!("{}u64", amount))?;
+
+    let record = || match amount_record.clone() {
+        Some(record) => Ok(Value::<N>::Record(record)),
+        None => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            "Private transfer without an amount record".to_string(),
+            "No record found to transfer from".to_string(),
+        )),
+    };
+
+    let res = match transfer_type {
+        TransferType::Private => ("transfer_private", vec![record()?, recipient, amount]),
+        TransferType::PrivateToPublic => {
+            ("transfer_private_to_public", vec![record()?, recipient, amount])
+        }
+        TransferType::PublicToPrivate => ("transfer_public_to_private", vec![recipient, amount]),
+        TransferType::Public => ("transfer_public", vec![recipient, amount]),
+    };
+
+    Ok(res)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProverSettings {
+    pub endpoint: Option<String>,
+    pub fallback: bool,
+}
+
+/// Sets the delegated prover, or clears it to prove in process
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_delegated_prover(endpoint: Option<String>, fallback: bool) -> AvailResult<()> {
+    if let Some(endpoint) = &endpoint {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(AvailError::new(
+                AvailErrorType::Validation,
+                format!("Invalid prover endpoint {}", endpoint),
+                "Prover endpoint must be an http or https url".to_string(),
+            ));
+        }
+    }
+
+    update_prover_settings(endpoint.as_deref(), fallback)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_delegated_prover() -> AvailResult<ProverSettings> {
+    Ok(ProverSettings {
+        endpoint: get_prover_endpoint()?,
+        fallback: get_prover_fallback()?,
+    })
+}
+
+#[cfg(test)]
+mod prover_tests {
+    use super::*;
+    use avail_common::models::constants::TESTNET_PRIVATE_KEY;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{channel, Receiver};
+
+    /// Stand-in prover that counts calls and always fails.
+    struct StandInProver {
+        calls: AtomicUsize,
+    }
+
+    impl StandInProver {
+        fn fail<T>(&self) -> AvailResult<T> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            Err(AvailError::new(
+                AvailErrorType::Internal,
+                "stand-in prover".to_string(),
+                "stand-in prover".to_string(),
+            ))
+        }
+    }
+
+    impl<N: Network> Prover<N> for StandInProver {
+        fn prove_execution(&self, _authorization: Authorization<N>) -> AvailResult<Execution<N>> {
+            self.fail()
+        }
+
+        fn prove_fee(&self, _fee_authorization: Authorization<N>) -> AvailResult<Fee<N>> {
+            self.fail()
+        }
+    }
+
+    /// Serves one prove request over http per response, returns the endpoint and the requests it received.
+    fn spawn_stand_in_endpoint(responses: Vec<ProveResponse>) -> (String, Receiver<ProveRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/prove", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+
+        std::thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+
+                    let line = line.trim();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse::<usize>().unwrap();
+                        }
+                    }
+                }
+
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                sender
+                    .send(serde_json::from_slice::<ProveRequest>(&body).unwrap())
+                    .unwrap();
+
+                let body = serde_json::to_string(&response).unwrap();
+                let mut writer = stream;
+                write!(
+                    writer,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (endpoint, receiver)
+    }
+
+    fn test_private_key() -> PrivateKey<TestnetV0> {
+        PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap()
+    }
+
+    fn test_authorization() -> Authorization<TestnetV0> {
+        let private_key = test_private_key();
+        let recipient = Address::<TestnetV0>::try_from(&private_key).unwrap();
+
+        let (function_name, inputs) =
+            transfer_inputs::<TestnetV0>(&TransferType::Public, 1000, recipient, None).unwrap();
+
+        authorize_execution::<TestnetV0>(&private_key, "credits.aleo", function_name, inputs)
+            .unwrap()
+    }
+
+    fn authorize_test_fee(
+        execution_id: Field<TestnetV0>,
+        base_fee: u64,
+    ) -> AvailResult<Authorization<TestnetV0>> {
+        authorize_execution_fee::<TestnetV0>(
+            &test_private_key(),
+            execution_id,
+            base_fee,
+            10000,
+            None,
+        )
+    }
+
+    /// Proving consumes the requests of an authorization, clones share them
+    fn copy(authorization: &Authorization<TestnetV0>) -> Authorization<TestnetV0> {
+        Authorization::from_str(&authorization.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_fallback_after_delegated_failure() {
+        let authorization = test_authorization();
+
+        let (endpoint, received) = spawn_stand_in_endpoint(vec![ProveResponse {
+            error: Some("out of capacity".to_string()),
+            ..Default::default()
+        }]);
+        let delegated_prover = DelegatedProver::new(&endpoint);
+        let stand_in = StandInProver {
+            calls: AtomicUsize::new(0),
+        };
+
+        let res = prove_with_fallback::<TestnetV0>(
+            &delegated_prover,
+            Some(&stand_in),
+            authorization.clone(),
+            &authorize_test_fee,
+        );
+
+        assert!(res.is_err());
+        assert_eq!(stand_in.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            received.recv().unwrap().authorization,
+            authorization.to_string()
+        );
+    }
+
+    #[test]
+    fn test_delegated_prover() {
+        let authorization = test_authorization();
+
+        let execution = LocalProver.prove_execution(copy(&authorization)).unwrap();
+        let execution_id = execution.to_execution_id().unwrap();
+        let fee_authorization =
+            authorize_test_fee(execution_id, execution_base_fee(&execution).unwrap()).unwrap();
+        let fee = LocalProver.prove_fee(copy(&fee_authorization)).unwrap();
+
+        let (endpoint, received) = spawn_stand_in_endpoint(vec![
+            ProveResponse {
+                execution: Some(execution.to_string()),
+                ..Default::default()
+            },
+            ProveResponse {
+                fee: Some(fee.to_string()),
+                ..Default::default()
+            },
+        ]);
+
+        // the fee is only signed once the delegated prover returned the execution
+        let transaction = prove_transaction::<TestnetV0>(
+            &DelegatedProver::new(&endpoint),
+            authorization.clone(),
+            &|id, _| {
+                assert_eq!(id, execution_id);
+                Ok(fee_authorization.clone())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            transaction.execution().unwrap().to_execution_id().unwrap(),
+            execution_id
+        );
+        assert_eq!(
+            received.recv().unwrap().authorization,
+            authorization.to_string()
+        );
+        assert_eq!(
+            received.recv().unwrap().authorization,
+            fee_authorization.to_string()
+        );
+    }
+
+    #[test]
+    fn test_delegated_prover_rejects_other_execution() {
+        let authorization = test_authorization();
+        let other = LocalProver.prove_execution(test_authorization()).unwrap();
+
+        let (endpoint, _received) = spawn_stand_in_endpoint(vec![ProveResponse {
+            execution: Some(other.to_string()),
+            ..Default::default()
+        }]);
+
+        let res =
+            Prover::<TestnetV0>::prove_execution(&DelegatedProver::new(&endpoint), authorization);
+
+        assert!(matches!(
+            res.map_err(|e| e.error_type),
+            Err(AvailErrorType::Validation)
+        ));
+    }
+
+    #[test]
+    fn test_fee_pays_base_and_priority() {
+        let transaction =
+            prove_transaction::<TestnetV0>(&LocalProver, test_authorization(), &authorize_test_fee)
+                .unwrap();
+
+        let base_fee = execution_base_fee(transaction.execution().unwrap()).unwrap();
+        let fee = transaction.fee_transition().unwrap();
+
+        assert_ne!(base_fee, 0);
+        assert_eq!(*fee.base_amount().unwrap(), base_fee);
+        assert_eq!(*fee.priority_amount().unwrap(), 10000);
+    }
+
+    #[test]
+    fn test_no_fallback() {
+        let stand_in = StandInProver {
+            calls: AtomicUsize::new(0),
+        };
+
+        let res = prove_with_fallback::<TestnetV0>(
+            &stand_in,
+            None,
+            test_authorization(),
+            &authorize_test_fee,
+        );
+
+        assert!(res.is_err());
+        assert_eq!(stand_in.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_local_prover_matches_authorization() {
+        let authorization = test_authorization();
+
+        let execution = LocalProver.prove_execution(copy(&authorization)).unwrap();
+
+        check_transitions_match(execution.transitions(), &authorization).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_inputs_require_record() {
+        let private_key = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let recipient = Address::<TestnetV0>::try_from(&private_key).unwrap();
+
+        let res = transfer_inputs::<TestnetV0>(&TransferType::Private, 1000, recipient, None);
+
+        assert!(res.is_err());
+    }
+}
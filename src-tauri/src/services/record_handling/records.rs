@@ -40,10 +40,11 @@ use crate::{
         },
     },
 };
+use super::token_registry::{find_registry_tokens_to_spend, parse_token_id};
 use rayon::prelude::*;
 use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
-    Arc, Mutex,
+    Arc, Mutex, RwLock, RwLockWriteGuard,
 };
 use std::time::Duration;
 use tauri::Emitter;
@@ -54,12 +55,28 @@ use avail_common::{
     models::encrypted_data::{EncryptedData, RecordTypeCommon, TransactionState},
 };
 
+/// Generation of the running scans, a scan stops at the next block once it changes
+static SCAN_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Held by every running scan, so stopping can wait for the scans to return
+static SCANS: RwLock<()> = RwLock::new(());
+
+/// Stops the running scans and waits for them to return, no scan starts until the guard is dropped.
+/// Scans stop before a block, so the block they stopped at is scanned again by the next scan.
+pub fn stop_scans() -> RwLockWriteGuard<'static, ()> {
+    SCAN_GENERATION.fetch_add(1, Ordering::SeqCst);
+    SCANS.write().unwrap_or_else(|e| e.into_inner())
+}
+
 /// Scans the blockchain for new records, distills record pointers, transition pointer and tags, and returns them
 pub fn get_records<N: Network>(
     last_sync: u32,
     height: u32,
     window: Option<Window>,
 ) -> AvailResult<bool> {
+    let _scan = SCANS.read().unwrap_or_else(|e| e.into_inner());
+    let generation = SCAN_GENERATION.load(Ordering::SeqCst);
+
     let view_key = VIEWSESSION.get_instance::<N>()?;
     let address = view_key.to_address();
 
@@ -181,6 +198,14 @@ pub fn get_records<N: Network>(
         };
 
         for block in blocks {
+            if SCAN_GENERATION.load(Ordering::SeqCst) != generation {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    format!("Scan stopped at block {}", block.height()),
+                    "Scan stopped".to_string(),
+                ));
+            }
+
             // Check for deployment transactions
             let transactions = block.transactions();
             let timestamp = get_timestamp_from_i64(block.clone().timestamp())?;
@@ -678,6 +703,10 @@ pub fn find_tokens_to_spend<N: Network>(
     amount: &u64,
     previous: Vec<String>,
 ) -> AvailResult<(Record<N, Plaintext<N>>, String, String)> {
+    if let Some(token_id) = parse_token_id::<N>(asset_id) {
+        return find_registry_tokens_to_spend::<N>(&token_id, amount, previous);
+    }
+
     let _address = get_address_string()?;
     let program_id = format!("{}{}", asset_id, ".aleo");
     let record_name = format!("{}{}", asset_id, ".record");
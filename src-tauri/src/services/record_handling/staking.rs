@@ -0,0 +1,599 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use snarkvm::prelude::*;
+use std::str::FromStr;
+use tauri::Window;
+
+use crate::helpers::validation::validate_address;
+use crate::models::event::StakingEventType;
+use crate::models::pointers::transaction::TransactionPointer;
+use crate::services::account::signer::get_signer;
+use crate::services::authentication::session::get_session_with_signer;
+use crate::services::local_storage::{
+    encrypted_data::update_encrypted_transaction_state_by_id,
+    persistent_storage::{get_address, get_network},
+    session::password::PASS,
+    storage_api::records::update_record_spent_local,
+    token_metadata::{format_amount, DEFAULT_DECIMALS},
+};
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::{
+        encrypted_data::{EventTypeCommon, TransactionState},
+        network::SupportedNetworks,
+    },
+};
+
+use super::mapping::fetch_mapping_value;
+use super::parameters::{ensure_parameters, INCLUSION};
+use super::prover::execute_with_prover;
+use super::records::find_aleo_credits_record_to_spend;
+use super::tx_events::publish_tx_state;
+use super::utils::handle_transaction_update_and_encrypted_storage;
+
+/* --Staking-- */
+
+/// Bonded, unbonding and withdrawal state of an address in credits.aleo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakingBalance {
+    pub validator: Option<String>,
+    pub bonded: f64,
+    pub unbonding: f64,
+    /// Block height from which the unbonding amount can be claimed
+    pub unbonding_height: Option<u32>,
+    pub withdrawal_address: Option<String>,
+}
+
+fn get_mapping<N: Network>(mapping: &str, address: &Address<N>) -> AvailResult<Option<Value<N>>> {
+    fetch_mapping_value::<N>("credits.aleo", mapping, &address.to_string())
+}
+
+fn struct_member<N: Network>(value: &Value<N>, member: &str) -> AvailResult<Literal<N>> {
+    let key = Identifier::<N>::from_str(member)?;
+
+    match value {
+        Value::Plaintext(Plaintext::Struct(members, _)) => match members.get(&key) {
+            Some(Plaintext::Literal(literal, _)) => Ok(literal.clone()),
+            _ => Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                format!("Missing {} in {}", member, value),
+                "Error reading staking state".to_string(),
+            )),
+        },
+        _ => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            format!("Unexpected staking value {}", value),
+            "Error reading staking state".to_string(),
+        )),
+    }
+}
+
+fn microcredits<N: Network>(value: &Value<N>) -> AvailResult<u64> {
+    match struct_member(value, "microcredits")? {
+        Literal::U64(amount) => Ok(*amount),
+        literal => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            format!("Unexpected microcredits {}", literal),
+            "Error reading staking state".to_string(),
+        )),
+    }
+}
+
+/// Reads the bonded, unbonding and withdraw mappings of an address
+pub fn get_staking_balance<N: Network>(address: &Address<N>) -> AvailResult<StakingBalance> {
+    let (validator, bonded) = match get_mapping::<N>("bonded", address)? {
+        Some(bond_state) => {
+            let validator = struct_member(&bond_state, "validator")?.to_string();
+            (Some(validator), microcredits(&bond_state)?)
+        }
+        None => (None, 0),
+    };
+
+    let (unbonding, unbonding_height) = match get_mapping::<N>("unbonding", address)? {
+        Some(unbond_state) => {
+            let height = match struct_member(&unbond_state, "height")? {
+                Literal::U32(height) => Some(*height),
+                _ => None,
+            };
+            (microcredits(&unbond_state)?, height)
+        }
+        None => (0, None),
+    };
+
+    let withdrawal_address = get_mapping::<N>("withdraw", address)?.map(|value| value.to_string());
+
+    Ok(StakingBalance {
+        validator,
+        bonded: format_amount(bonded as u128, DEFAULT_DECIMALS),
+        unbonding: format_amount(unbonding as u128, DEFAULT_DECIMALS),
+        unbonding_height,
+        withdrawal_address,
+    })
+}
+
+/// Executes a credits.aleo staking function and tracks it like any other transaction
+async fn stake_raw<N: Network>(
+    function_id: &str,
+    inputs: Vec<Value<N>>,
+    amount: Option<u64>,
+    to: Option<String>,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<String> {
+    let staking = match StakingEventType::from_function(function_id) {
+        Some(staking) => staking,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::Internal,
+                format!("{} is not a staking function", function_id),
+                "Error staking".to_string(),
+            ))
+        }
+    };
+
+    let mut parameters = vec![function_id.to_string()];
+    match fee_private {
+        true => parameters.extend([String::from("fee_private"), INCLUSION.to_string()]),
+        false => parameters.push(String::from("fee_public")),
+    }
+    ensure_parameters(&parameters).await?;
+
+    let sender_address = get_address::<N>()?;
+    let signer = get_signer::<N>(password)?;
+
+    //extend session auth
+    get_session_with_signer::<N>(signer.as_ref()).await?;
+
+    let mut record_nonces: Vec<String> = vec![];
+
+    // get required records if private fee
+    let (fee_record, fee_id) = match fee_private {
+        true => {
+            let (fee_record, _fee_commitment, fee_id) =
+                find_aleo_credits_record_to_spend::<N>(&fee, vec![])?;
+
+            record_nonces.push(fee_record.nonce().to_string());
+            (Some(fee_record), Some(fee_id))
+        }
+        false => (None, None),
+    };
+
+    let mut pending_transaction = TransactionPointer::<N>::new(
+        to,
+        None,
+        TransactionState::Processing,
+        None,
+        Some("credits.aleo".to_string()),
+        Some(function_id.to_string()),
+        vec![],
+        record_nonces,
+        Local::now(),
+        None,
+        None,
+        EventTypeCommon::Execute,
+        amount.map(|amount| format_amount(amount as u128, DEFAULT_DECIMALS)),
+        Some(fee as f64 / 1000000.0),
+        None,
+    )
+    .with_staking(staking);
+
+    let pending_tx_id = pending_transaction.encrypt_and_store(sender_address)?;
+
+    publish_tx_state(
+        window.as_ref(),
+        &pending_tx_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
+
+    if let Some(fee_id) = fee_id.clone() {
+        update_record_spent_local::<N>(&fee_id, true)?;
+    }
+
+    let transaction_id = match execute_with_prover::<N>(
+        signer.as_ref(),
+        "credits.aleo",
+        function_id,
+        inputs,
+        fee,
+        fee_record,
+    ) {
+        Ok(tx_id) => tx_id,
+        Err(e) => {
+            if let Some(fee_id) = fee_id {
+                update_record_spent_local::<N>(&fee_id, false)?;
+            }
+
+            pending_transaction.update_failed_transaction(
+                "Transaction execution failed, no records were spent.".to_string(),
+                None,
+            );
+
+            let encrypted_failed_transaction =
+                pending_transaction.to_encrypted_data(sender_address)?;
+
+            update_encrypted_transaction_state_by_id(
+                &pending_tx_id,
+                &encrypted_failed_transaction.ciphertext,
+                &encrypted_failed_transaction.nonce,
+                TransactionState::Failed,
+            )?;
+
+            publish_tx_state(
+                window.as_ref(),
+                &pending_tx_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
+
+            return Err(AvailError::new(
+                AvailErrorType::Internal,
+                format!("Error executing {}", function_id),
+                format!("Error executing {}: {:?}", function_id, e),
+            ));
+        }
+    };
+
+    handle_transaction_update_and_encrypted_storage::<N>(
+        transaction_id,
+        &pending_tx_id,
+        fee_id,
+        window,
+    )
+    .await?;
+
+    PASS.extend_session()?;
+
+    Ok(format!("Transaction Id {}", transaction_id))
+}
+
+fn address_value<N: Network>(address: &str) -> AvailResult<Value<N>> {
+    validate_address(address)?;
+    Ok(Value::<N>::from_str(address)?)
+}
+
+fn bond_public_inputs<N: Network>(
+    validator: &str,
+    withdrawal: &str,
+    amount: u64,
+) -> AvailResult<Vec<Value<N>>> {
+    Ok(vec![
+        address_value::<N>(validator)?,
+        address_value::<N>(withdrawal)?,
+        Value::<N>::from_str(&format!("{}u64", amount))?,
+    ])
+}
+
+fn bond_validator_inputs<N: Network>(
+    withdrawal: &str,
+    amount: u64,
+    commission: u8,
+) -> AvailResult<Vec<Value<N>>> {
+    if commission > 100 {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Invalid commission {}", commission),
+            "Commission must be between 0 and 100".to_string(),
+        ));
+    }
+
+    Ok(vec![
+        address_value::<N>(withdrawal)?,
+        Value::<N>::from_str(&format!("{}u64", amount))?,
+        Value::<N>::from_str(&format!("{}u8", commission))?,
+    ])
+}
+
+fn staker_or_self<N: Network>(staker: Option<String>) -> AvailResult<String> {
+    match staker {
+        Some(staker) => Ok(staker),
+        None => Ok(get_address::<N>()?.to_string()),
+    }
+}
+
+pub async fn bond_public_raw<N: Network>(
+    validator: String,
+    withdrawal: String,
+    amount: u64,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<String> {
+    let inputs = bond_public_inputs::<N>(&validator, &withdrawal, amount)?;
+
+    stake_raw::<N>(
+        "bond_public",
+        inputs,
+        Some(amount),
+        Some(validator),
+        fee,
+        fee_private,
+        password,
+        window,
+    )
+    .await
+}
+
+pub async fn bond_validator_raw<N: Network>(
+    withdrawal: String,
+    amount: u64,
+    commission: u8,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<String> {
+    let inputs = bond_validator_inputs::<N>(&withdrawal, amount, commission)?;
+
+    stake_raw::<N>(
+        "bond_validator",
+        inputs,
+        Some(amount),
+        None,
+        fee,
+        fee_private,
+        password,
+        window,
+    )
+    .await
+}
+
+pub async fn unbond_public_raw<N: Network>(
+    staker: Option<String>,
+    amount: u64,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<String> {
+    let staker = staker_or_self::<N>(staker)?;
+    let inputs = vec![
+        address_value::<N>(&staker)?,
+        Value::<N>::from_str(&format!("{}u64", amount))?,
+    ];
+
+    stake_raw::<N>(
+        "unbond_public",
+        inputs,
+        Some(amount),
+        Some(staker),
+        fee,
+        fee_private,
+        password,
+        window,
+    )
+    .await
+}
+
+pub async fn claim_unbond_public_raw<N: Network>(
+    staker: Option<String>,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<String> {
+    let staker = staker_or_self::<N>(staker)?;
+    let inputs = vec![address_value::<N>(&staker)?];
+
+    stake_raw::<N>(
+        "claim_unbond_public",
+        inputs,
+        None,
+        Some(staker),
+        fee,
+        fee_private,
+        password,
+        window,
+    )
+    .await
+}
+
+/// Bonds public credits to a validator, rewards and unbonded credits go to the withdrawal address
+#[tauri::command(rename_all = "snake_case")]
+pub async fn bond_public(
+    validator: String,
+    withdrawal: String,
+    amount: u64,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<String> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            bond_public_raw::<TestnetV0>(
+                validator,
+                withdrawal,
+                amount,
+                fee,
+                fee_private,
+                password,
+                Some(window),
+            )
+            .await
+        }
+        _ => {
+            bond_public_raw::<TestnetV0>(
+                validator,
+                withdrawal,
+                amount,
+                fee,
+                fee_private,
+                password,
+                Some(window),
+            )
+            .await
+        }
+    }
+}
+
+/// Bonds the validator's own stake, only used by validator operators
+#[tauri::command(rename_all = "snake_case")]
+pub async fn bond_validator(
+    withdrawal: String,
+    amount: u64,
+    commission: u8,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<String> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            bond_validator_raw::<TestnetV0>(
+                withdrawal,
+                amount,
+                commission,
+                fee,
+                fee_private,
+                password,
+                Some(window),
+            )
+            .await
+        }
+        _ => {
+            bond_validator_raw::<TestnetV0>(
+                withdrawal,
+                amount,
+                commission,
+                fee,
+                fee_private,
+                password,
+                Some(window),
+            )
+            .await
+        }
+    }
+}
+
+/// Starts unbonding credits of a staker, defaults to the wallet address
+#[tauri::command(rename_all = "snake_case")]
+pub async fn unbond_public(
+    staker: Option<String>,
+    amount: u64,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<String> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            unbond_public_raw::<TestnetV0>(staker, amount, fee, fee_private, password, Some(window))
+                .await
+        }
+        _ => {
+            unbond_public_raw::<TestnetV0>(staker, amount, fee, fee_private, password, Some(window))
+                .await
+        }
+    }
+}
+
+/// Claims unbonded credits once the unbonding height is reached, they are sent to the withdrawal address
+#[tauri::command(rename_all = "snake_case")]
+pub async fn claim_unbond_public(
+    staker: Option<String>,
+    fee: u64,
+    fee_private: bool,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<String> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            claim_unbond_public_raw::<TestnetV0>(staker, fee, fee_private, password, Some(window))
+                .await
+        }
+        _ => {
+            claim_unbond_public_raw::<TestnetV0>(staker, fee, fee_private, password, Some(window))
+                .await
+        }
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_staking_info() -> AvailResult<StakingBalance> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => get_staking_balance::<TestnetV0>(&get_address::<TestnetV0>()?),
+        _ => get_staking_balance::<TestnetV0>(&get_address::<TestnetV0>()?),
+    }
+}
+
+#[cfg(test)]
+mod staking_tests {
+    use super::*;
+    use avail_common::models::constants::TESTNET_ADDRESS;
+
+    #[test]
+    fn test_bond_public_inputs() {
+        let inputs = bond_public_inputs::<TestnetV0>(TESTNET_ADDRESS, TESTNET_ADDRESS, 10).unwrap();
+
+        assert_eq!(inputs.len(), 3);
+        assert_eq!(inputs[2].to_string(), "10u64");
+    }
+
+    #[test]
+    fn test_bond_public_invalid_validator() {
+        let res = bond_public_inputs::<TestnetV0>("aleo1invalid", TESTNET_ADDRESS, 10);
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_bond_validator_commission() {
+        assert!(bond_validator_inputs::<TestnetV0>(TESTNET_ADDRESS, 10, 101).is_err());
+
+        let inputs = bond_validator_inputs::<TestnetV0>(TESTNET_ADDRESS, 10, 5).unwrap();
+        assert_eq!(inputs[2].to_string(), "5u8");
+    }
+
+    #[test]
+    fn test_get_staking_balance() {
+        let address = Address::<TestnetV0>::from_str(TESTNET_ADDRESS).unwrap();
+        let staking = get_staking_balance::<TestnetV0>(&address).unwrap();
+
+        // the mappings only hold entries while something is bonded or unbonding
+        if staking.validator.is_none() {
+            assert_eq!(staking.bonded, 0.0);
+        }
+        assert_eq!(staking.unbonding_height.is_some(), staking.unbonding > 0.0);
+        if let Some(withdrawal_address) = staking.withdrawal_address {
+            assert!(Address::<TestnetV0>::from_str(&withdrawal_address).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_read_bond_state() {
+        let bond_state = Value::<TestnetV0>::from_str(&format!(
+            "{{ validator: {}, microcredits: 2500000u64 }}",
+            TESTNET_ADDRESS
+        ))
+        .unwrap();
+
+        assert_eq!(microcredits(&bond_state).unwrap(), 2500000);
+        assert_eq!(
+            struct_member(&bond_state, "validator").unwrap().to_string(),
+            TESTNET_ADDRESS
+        );
+        assert!(struct_member(&bond_state, "height").is_err());
+    }
+
+    #[test]
+    fn test_staking_event_type() {
+        assert_eq!(
+            StakingEventType::from_function("bond_public"),
+            Some(StakingEventType::Bond)
+        );
+        assert_eq!(
+            StakingEventType::from_function("claim_unbond_public"),
+            Some(StakingEventType::ClaimUnbond)
+        );
+        assert_eq!(StakingEventType::from_function("transfer_public"), None);
+    }
+}
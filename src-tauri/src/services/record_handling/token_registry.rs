@@ -0,0 +1,358 @@
+use snarkvm::prelude::*;
+use std::str::FromStr;
+
+use crate::models::token::TokenMetadata;
+use crate::models::wallet_connect::records::{GetRecordsRequest, RecordFilterType, RecordsFilter};
+use crate::services::local_storage::{
+    persistent_storage::get_address_string, storage_api::records::get_record_pointers,
+    token_metadata::DEFAULT_DECIMALS,
+};
+
+use avail_common::{
+    aleo_tools::program_manager::TransferType,
+    errors::{AvailError, AvailErrorType, AvailResult},
+};
+
+use super::mapping::fetch_mapping_value;
+
+/// Shared ARC-21 program holding every registered token, tokens are identified by a field id.
+pub const TOKEN_REGISTRY: &str = "token_registry.aleo";
+
+/// Record name of registry tokens as stored on record pointers.
+pub const TOKEN_RECORD: &str = "Token.record";
+
+/// Registry token ids are field literals, every other asset id names its own program.
+pub fn parse_token_id<N: Network>(asset_id: &str) -> Option<Field<N>> {
+    Field::<N>::from_str(asset_id).ok()
+}
+
+/// Program that holds the asset, i.e. token_registry.aleo for 1field and credits.aleo for credits
+pub fn token_program_id<N: Network>(asset_id: &str) -> String {
+    match parse_token_id::<N>(asset_id) {
+        Some(_) => TOKEN_REGISTRY.to_string(),
+        None => format!("{}.aleo", asset_id),
+    }
+}
+
+pub fn record_token_id<N: Network>(record: &Record<N, Plaintext<N>>) -> Option<Field<N>> {
+    let key = Identifier::<N>::from_str("token_id").ok()?;
+
+    match record.data().get(&key)? {
+        Entry::Private(Plaintext::Literal(Literal::Field(token_id), _))
+        | Entry::Public(Plaintext::Literal(Literal::Field(token_id), _))
+        | Entry::Constant(Plaintext::Literal(Literal::Field(token_id), _)) => Some(*token_id),
+        _ => None,
+    }
+}
+
+pub fn record_amount<N: Network>(record: &Record<N, Plaintext<N>>) -> u128 {
+    let key = match Identifier::<N>::from_str("amount") {
+        Ok(key) => key,
+        Err(_) => return 0,
+    };
+
+    match record.data().get(&key) {
+        Some(Entry::Private(Plaintext::Literal(Literal::U128(amount), _)))
+        | Some(Entry::Public(Plaintext::Literal(Literal::U128(amount), _)))
+        | Some(Entry::Constant(Plaintext::Literal(Literal::U128(amount), _))) => **amount,
+        _ => 0,
+    }
+}
+
+/// Name a token balance is kept under in the ARC20_tokens table.
+/// Registry tokens all share the Token record so they are kept under their token id instead.
+pub fn token_balance_name<N: Network>(
+    record_name: &str,
+    program_id: &str,
+    record: &Record<N, Plaintext<N>>,
+) -> String {
+    if program_id == TOKEN_REGISTRY {
+        if let Some(token_id) = record_token_id::<N>(record) {
+            return token_id.to_string();
+        }
+    }
+
+    record_name.to_string()
+}
+
+/// Key of the registry balance mappings, BHP256::hash_to_field(TokenOwner { account, token_id })
+pub fn balance_key<N: Network>(address: &Address<N>, token_id: &Field<N>) -> AvailResult<Field<N>> {
+    let token_owner = Plaintext::<N>::from_str(&format!(
+        "{{ account: {}, token_id: {} }}",
+        address, token_id
+    ))?;
+
+    Ok(N::hash_bhp256(&token_owner.to_bits_le())?)
+}
+
+fn get_balance_mapping<N: Network>(mapping: &str, key: &Field<N>) -> AvailResult<u128> {
+    let value = match fetch_mapping_value::<N>(TOKEN_REGISTRY, mapping, &key.to_string())? {
+        Some(value) => value,
+        None => return Ok(0),
+    };
+
+    let balance = Identifier::<N>::from_str("balance")?;
+
+    match value {
+        Value::Plaintext(Plaintext::Struct(members, _)) => match members.get(&balance) {
+            Some(Plaintext::Literal(Literal::U128(amount), _)) => Ok(**amount),
+            _ => Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                format!("Unexpected {} value {}", mapping, value),
+                "Error reading token balance".to_string(),
+            )),
+        },
+        _ => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            format!("Unexpected {} value {}", mapping, value),
+            "Error reading token balance".to_string(),
+        )),
+    }
+}
+
+/// Public registry balance, tokens that still need external authorization count towards it.
+pub fn get_registry_public_balance<N: Network>(token_id: &Field<N>) -> AvailResult<u128> {
+    let address = Address::<N>::from_str(&get_address_string()?)?;
+    let key = balance_key::<N>(&address, token_id)?;
+
+    let authorized = get_balance_mapping::<N>("authorized_balances", &key)?;
+    let unauthorized = get_balance_mapping::<N>("balances", &key)?;
+
+    Ok(authorized.saturating_add(unauthorized))
+}
+
+fn token_not_registered<N: Network>(token_id: &Field<N>) -> AvailError {
+    AvailError::new(
+        AvailErrorType::NotFound,
+        format!("Token {} is not registered", token_id),
+        "Token not found in the token registry".to_string(),
+    )
+}
+
+/// Entry of the token in the registry's `registered_tokens` mapping.
+fn get_registered_token<N: Network>(token_id: &Field<N>) -> AvailResult<Value<N>> {
+    match fetch_mapping_value::<N>(TOKEN_REGISTRY, "registered_tokens", &token_id.to_string())? {
+        Some(value) => Ok(value),
+        None => Err(token_not_registered(token_id)),
+    }
+}
+
+/// Whether transfers of the token into private records have to be authorized by the token admin.
+pub fn external_authorization_required<N: Network>(token_id: &Field<N>) -> AvailResult<bool> {
+    let value = get_registered_token::<N>(token_id)?;
+    let key = Identifier::<N>::from_str("external_authorization_required")?;
+
+    match value {
+        Value::Plaintext(Plaintext::Struct(members, _)) => match members.get(&key) {
+            Some(Plaintext::Literal(Literal::Boolean(required), _)) => Ok(**required),
+            _ => Ok(false),
+        },
+        _ => Err(token_not_registered(token_id)),
+    }
+}
+
+/// Metadata a token was registered with, names and symbols are ascii packed into a u128.
+pub fn get_registry_token_metadata<N: Network>(token_id: &Field<N>) -> AvailResult<TokenMetadata> {
+    let members = match get_registered_token::<N>(token_id)? {
+        Value::Plaintext(Plaintext::Struct(members, _)) => members,
+        _ => return Err(token_not_registered(token_id)),
+    };
+
+    let member = |name: &str| -> AvailResult<Literal<N>> {
+        let key = Identifier::<N>::from_str(name)?;
+
+        match members.get(&key) {
+            Some(Plaintext::Literal(literal, _)) => Ok(literal.clone()),
+            _ => Err(AvailError::new(
+                AvailErrorType::InvalidData,
+                format!("Token {} has no {}", token_id, name),
+                "Error reading token metadata".to_string(),
+            )),
+        }
+    };
+
+    let name = match member("name")? {
+        Literal::U128(name) => u128_to_ascii(**name),
+        _ => String::new(),
+    };
+    let symbol = match member("symbol")? {
+        Literal::U128(symbol) => u128_to_ascii(**symbol),
+        _ => String::new(),
+    };
+    let decimals = match member("decimals")? {
+        Literal::U8(decimals) => **decimals,
+        _ => DEFAULT_DECIMALS,
+    };
+
+    Ok(TokenMetadata::new(
+        token_id.to_string(),
+        TOKEN_REGISTRY.to_string(),
+        name,
+        symbol,
+        decimals,
+        None,
+        false,
+    ))
+}
+
+fn u128_to_ascii(value: u128) -> String {
+    value
+        .to_be_bytes()
+        .iter()
+        .filter(|byte| **byte != 0)
+        .map(|byte| *byte as char)
+        .collect()
+}
+
+/// Fetches an unspent registry Token record of the given token id to spend
+pub fn find_registry_tokens_to_spend<N: Network>(
+    token_id: &Field<N>,
+    amount: &u64,
+    previous: Vec<String>,
+) -> AvailResult<(Record<N, Plaintext<N>>, String, String)> {
+    let filter = RecordsFilter::new(
+        vec![TOKEN_REGISTRY.to_string()],
+        None,
+        RecordFilterType::Unspent,
+        Some(TOKEN_RECORD.to_string()),
+    );
+    let get_records_request = GetRecordsRequest::new(None, Some(filter), None);
+    let (record_pointers, ids) = get_record_pointers::<N>(get_records_request)?;
+
+    let mut balance_counter = 0u128;
+
+    for (record, id) in record_pointers.iter().zip(ids.iter()) {
+        if record.metadata.spent || previous.contains(&record.metadata.nonce) {
+            continue;
+        }
+
+        let token_record = record.to_record()?;
+
+        if record_token_id::<N>(&token_record).as_ref() != Some(token_id) {
+            continue;
+        }
+
+        let record_amount = record_amount::<N>(&token_record);
+
+        if record_amount >= *amount as u128 {
+            return Ok((token_record, record.pointer.commitment.clone(), id.clone()));
+        }
+
+        balance_counter += record_amount;
+    }
+
+    if balance_counter >= *amount as u128 {
+        return Err(AvailError::new(
+            AvailErrorType::Internal,
+            "Join token records to obtain a sufficient balance.".to_string(),
+            "Join token records to obtain a sufficient balance.".to_string(),
+        ));
+    }
+
+    Err(AvailError::new(
+        AvailErrorType::Internal,
+        "Not enough balance".to_string(),
+        "Not enough balance".to_string(),
+    ))
+}
+
+/// Function name and inputs of the registry transfer variants
+pub fn registry_transfer_inputs<N: Network>(
+    token_id: &Field<N>,
+    transfer_type: &TransferType,
+    amount: u64,
+    recipient: Address<N>,
+    amount_record: Option<Record<N, Plaintext<N>>>,
+) -> AvailResult<(&'static str, Vec<Value<N>>)> {
+    let token = Value::<N>::from_str(&token_id.to_string())?;
+    let recipient = Value::<N>::from_str(&recipient.to_string())?;
+    let amount = Value::<N>::from_str(&format!("{}u128", amount))?;
+
+    let record = || match amount_record.clone() {
+        Some(record) => Ok(Value::<N>::Record(record)),
+        None => Err(AvailError::new(
+            AvailErrorType::InvalidData,
+            "Private transfer without a token record".to_string(),
+            "No record found to transfer from".to_string(),
+        )),
+    };
+
+    let res = match transfer_type {
+        TransferType::Private => ("transfer_private", vec![recipient, amount, record()?]),
+        TransferType::PrivateToPublic => (
+            "transfer_private_to_public",
+            vec![recipient, amount, record()?],
+        ),
+        TransferType::PublicToPrivate => {
+            let required = external_authorization_required::<N>(token_id)?;
+            let required = Value::<N>::from_str(&required.to_string())?;
+
+            (
+                "transfer_public_to_private",
+                vec![token, recipient, amount, required],
+            )
+        }
+        TransferType::Public => ("transfer_public", vec![token, recipient, amount]),
+    };
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod token_registry_tests {
+    use super::*;
+    use avail_common::models::constants::TESTNET_ADDRESS;
+
+    #[test]
+    fn test_token_program_id() {
+        assert_eq!(token_program_id::<TestnetV0>("credits"), "credits.aleo");
+        assert_eq!(token_program_id::<TestnetV0>("12345field"), TOKEN_REGISTRY);
+    }
+
+    #[test]
+    fn test_registry_transfer_inputs() {
+        let token_id = Field::<TestnetV0>::from_str("12345field").unwrap();
+        let recipient = Address::<TestnetV0>::from_str(TESTNET_ADDRESS).unwrap();
+
+        let (function, inputs) = registry_transfer_inputs::<TestnetV0>(
+            &token_id,
+            &TransferType::Public,
+            100,
+            recipient,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(function, "transfer_public");
+        assert_eq!(inputs[0].to_string(), "12345field");
+        assert_eq!(inputs[2].to_string(), "100u128");
+
+        let res = registry_transfer_inputs::<TestnetV0>(
+            &token_id,
+            &TransferType::Private,
+            100,
+            recipient,
+            None,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_u128_to_ascii() {
+        // "ALEO" packed big endian
+        assert_eq!(u128_to_ascii(0x414c454f), "ALEO");
+        assert_eq!(u128_to_ascii(0), "");
+    }
+
+    #[test]
+    fn test_balance_key_is_deterministic() {
+        let token_id = Field::<TestnetV0>::from_str("12345field").unwrap();
+        let other_token_id = Field::<TestnetV0>::from_str("54321field").unwrap();
+        let address = Address::<TestnetV0>::from_str(TESTNET_ADDRESS).unwrap();
+
+        let key = balance_key::<TestnetV0>(&address, &token_id).unwrap();
+
+        assert_eq!(key, balance_key::<TestnetV0>(&address, &token_id).unwrap());
+        assert_ne!(key, balance_key::<TestnetV0>(&address, &other_token_id).unwrap());
+    }
+}
@@ -1,11 +1,9 @@
 use chrono::{DateTime, Local};
 
-use dirs;
 use snarkvm::{ledger::transactions::ConfirmedTransaction, prelude::*};
 use tauri::{Emitter, Manager, Window};
-use tauri_plugin_http::reqwest;
 
-use std::{fs, path::PathBuf};
+use std::fs;
 use std::{ops::Add, str::FromStr};
 use tokio::time::{Duration, Instant};
 
@@ -13,14 +11,17 @@ use crate::api::aleo_client::{setup_client, setup_local_client};
 use crate::services::local_storage::encrypted_data::update_encrypted_transaction_state_by_id;
 use crate::{
     helpers::utils::get_timestamp_from_i64,
-    services::authentication::session::get_session_after_creation,
+    services::authentication::session::get_session_with_signer,
     services::local_storage::storage_api::records::update_record_spent_local,
 };
 
-use crate::models::{pointers::transaction::TransactionPointer, transfer::TransferRequest};
+use crate::models::{
+    pointers::transaction::TransactionPointer,
+    transfer::{TransferRequest, TransferResponse},
+};
 
 use avail_common::{
-    aleo_tools::program_manager::{ProgramManager, TransferType},
+    aleo_tools::program_manager::TransferType,
     errors::{AvailError, AvailErrorType, AvailResult},
     models::{
         encrypted_data::{EventTypeCommon, TransactionState},
@@ -28,18 +29,26 @@ use avail_common::{
     },
 };
 
+use crate::services::account::signer::get_signer;
 use crate::services::local_storage::{
     persistent_storage::{get_address, get_network},
+    reputation::find_blocked,
     session::password::PASS,
-    utils::get_private_key,
+    token_metadata::{format_amount, token_decimals},
 };
 
+use super::parameters::{
+    download_parameter, ensure_transfer_parameters, get_manifest, resources_dir, INCLUSION,
+};
+use super::prover::transfer_with_prover;
 use super::records::*;
+use super::token_registry::token_program_id;
+use super::tx_events::publish_tx_state;
 use super::utils::{get_address_from_recipient, handle_encrypted_storage_and_message};
 
-/// Generic ARC20 token transfer function
+/// Generic ARC20 token transfer function, transfers to a blocked recipient or of a blocked token are refused
 #[tauri::command(rename_all = "snake_case")]
-pub async fn transfer(request: TransferRequest, window: Window) -> AvailResult<String> {
+pub async fn transfer(request: TransferRequest, window: Window) -> AvailResult<TransferResponse> {
     let network = get_network()?;
 
     let transfer_task_res = match SupportedNetworks::from_str(&network)? {
@@ -56,14 +65,33 @@ pub async fn transfer(request: TransferRequest, window: Window) -> AvailResult<S
 
     PASS.extend_session()?;
 
-    Ok(format!("Transaction '{}' Successful", transfer_task_res))
+    Ok(transfer_task_res)
 }
 
 pub async fn transfer_raw<N: Network>(
     request: TransferRequest,
     window: Option<Window>,
-) -> AvailResult<String> {
-    match request.transfer_type() {
+) -> AvailResult<TransferResponse> {
+    // names are checked as well as the address they resolve to
+    let recipient = get_address_from_recipient::<N>(request.recipient()).await?;
+    let targets = vec![
+        recipient.to_string(),
+        request.recipient().clone(),
+        token_program_id::<N>(request.asset_id()),
+    ];
+    if let Some(entry) = find_blocked(&targets)? {
+        return Ok(TransferResponse::blocked(entry));
+    }
+
+    // fail before any records are marked as spent if proving keys are missing
+    ensure_transfer_parameters(
+        request.asset_id().as_str(),
+        request.transfer_type(),
+        *request.fee_private(),
+    )
+    .await?;
+
+    let transaction_id = match request.transfer_type() {
         TransferType::Private => {
             transfer_private_util::<N>(
                 request.asset_id().as_str(),
@@ -116,7 +144,12 @@ pub async fn transfer_raw<N: Network>(
             )
             .await
         }
-    }
+    }?;
+
+    Ok(TransferResponse::new(format!(
+        "Transaction '{}' Successful",
+        transaction_id
+    )))
 }
 
 /// Transfer tokens privately
@@ -130,21 +163,16 @@ async fn transfer_private_util<N: Network>(
     password: Option<String>,
     window: Option<Window>,
 ) -> AvailResult<String> {
-    let api_client = setup_client::<N>()?;
-
     let sender_address = get_address::<N>()?;
 
-    let private_key = get_private_key::<N>(password)?;
+    let signer = get_signer::<N>(password)?;
 
     //extend session auth
-    let _session_task = get_session_after_creation::<N>(&private_key).await?;
+    let _session_task = get_session_with_signer::<N>(signer.as_ref()).await?;
 
     let recipient = get_address_from_recipient::<N>(to).await?;
     let mut record_nonces: Vec<String> = vec![];
 
-    let program_manager =
-        ProgramManager::<N>::new(Some(private_key), None, Some(api_client.clone()), None).unwrap();
-
     // get required records if private tx
     let (token_record, _token_commitment, token_id) =
         find_tokens_to_spend::<N>(asset_id, amount, vec![])?;
@@ -162,7 +190,7 @@ async fn transfer_private_util<N: Network>(
         false => (None, None, None),
     };
 
-    let program_id = format!("{}.aleo", asset_id);
+    let program_id = token_program_id::<N>(asset_id);
 
     let mut pending_transaction = TransactionPointer::<N>::new(
         Some(to.to_string()),
@@ -177,25 +205,21 @@ async fn transfer_private_util<N: Network>(
         None,
         message,
         EventTypeCommon::Send,
-        Some(*amount as f64 / 1000000.0),
+        Some(format_amount(*amount as u128, token_decimals::<N>(asset_id))),
         Some(*fee as f64 / 1000000.0),
         None,
     );
 
     let pending_tx_id = pending_transaction.encrypt_and_store(sender_address)?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &pending_tx_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        &pending_tx_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
 
     let amount = amount.to_owned();
     let fee = fee.to_owned();
@@ -219,12 +243,13 @@ async fn transfer_private_util<N: Network>(
         };
     };
 
-    let transaction_id = match program_manager.transfer(
+    let transaction_id = match transfer_with_prover::<N>(
+        signer.as_ref(),
+        asset_id,
         amount,
         fee,
         recipient,
         TransferType::Private,
-        None,
         Some(token_record),
         fee_record.clone(),
         &program_id,
@@ -253,18 +278,14 @@ async fn transfer_private_util<N: Network>(
                 TransactionState::Failed,
             )?;
 
-            if let Some(window) = window.clone() {
-                match window.emit("tx_state_change", &pending_tx_id) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(AvailError::new(
-                            AvailErrorType::Internal,
-                            "Error emitting tx_state_change event".to_string(),
-                            "Error emitting transaction state".to_string(),
-                        ));
-                    }
-                };
-            };
+            publish_tx_state(
+                window.as_ref(),
+                &pending_tx_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
 
             return Err(AvailError::new(
                 AvailErrorType::Internal,
@@ -306,19 +327,15 @@ async fn transfer_public_to_private_util<N: Network>(
     password: Option<String>,
     window: Option<Window>,
 ) -> AvailResult<String> {
-    let api_client = setup_client::<N>()?;
     let sender_address = get_address::<N>()?;
-    let private_key = get_private_key::<N>(password)?;
+    let signer = get_signer::<N>(password)?;
 
     //extend session auth
-    let _session_task = get_session_after_creation::<N>(&private_key).await?;
+    let _session_task = get_session_with_signer::<N>(signer.as_ref()).await?;
     let recipient = get_address_from_recipient::<N>(to).await?;
     let mut record_nonces: Vec<String> = vec![];
 
-    let program_manager =
-        ProgramManager::<N>::new(Some(private_key), None, Some(api_client.clone()), None)?;
-
-    let program_id = format!("{}.aleo", asset_id);
+    let program_id = token_program_id::<N>(asset_id);
 
     //get required records if private fee
     let (fee_record, _fee_commitment, fee_id) = match fee_private {
@@ -348,25 +365,21 @@ async fn transfer_public_to_private_util<N: Network>(
         None,
         message,
         EventTypeCommon::Send,
-        Some(*amount as f64 / 1000000.0),
+        Some(format_amount(*amount as u128, token_decimals::<N>(asset_id))),
         Some(*fee as f64 / 1000000.0),
         None,
     );
 
     let pending_tx_id = pending_transaction.encrypt_and_store(sender_address)?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &pending_tx_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        }
-    };
+    publish_tx_state(
+        window.as_ref(),
+        &pending_tx_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
 
     // update spent states
     if let Some(fee_id) = fee_id.clone() {
@@ -386,13 +399,14 @@ async fn transfer_public_to_private_util<N: Network>(
         };
     };
 
-    let transaction_id = match program_manager.transfer(
+    let transaction_id = match transfer_with_prover::<N>(
+        signer.as_ref(),
+        asset_id,
         amount.to_owned(),
         fee.to_owned(),
         recipient,
         TransferType::PublicToPrivate,
         None,
-        None,
         fee_record.clone(),
         &program_id,
     ) {
@@ -417,18 +431,14 @@ async fn transfer_public_to_private_util<N: Network>(
                 TransactionState::Failed,
             )?;
 
-            if let Some(window) = window.clone() {
-                match window.emit("tx_state_change", &pending_tx_id) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(AvailError::new(
-                            AvailErrorType::Internal,
-                            "Error emitting tx_state_change event".to_string(),
-                            "Error emitting transaction state".to_string(),
-                        ));
-                    }
-                };
-            };
+            publish_tx_state(
+                window.as_ref(),
+                &pending_tx_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
 
             return Err(AvailError::new(
                 AvailErrorType::Internal,
@@ -463,18 +473,14 @@ async fn transfer_private_to_public_util<N: Network>(
     password: Option<String>,
     window: Option<Window>,
 ) -> AvailResult<String> {
-    let api_client = setup_client::<N>()?;
     let sender_address = get_address::<N>()?;
-    let private_key = get_private_key::<N>(password)?;
+    let signer = get_signer::<N>(password)?;
 
     //extend session auth
-    let _session_task = get_session_after_creation::<N>(&private_key).await?;
+    let _session_task = get_session_with_signer::<N>(signer.as_ref()).await?;
     let recipient = get_address_from_recipient::<N>(to).await?;
     let mut record_nonces: Vec<String> = vec![];
 
-    let program_manager =
-        ProgramManager::<N>::new(Some(private_key), None, Some(api_client.clone()), None).unwrap();
-
     // get required records if private tx
     let (token_record, _token_commitment, token_id) =
         find_tokens_to_spend::<N>(asset_id, amount, vec![])?;
@@ -495,7 +501,7 @@ async fn transfer_private_to_public_util<N: Network>(
         false => (None, None, None),
     };
 
-    let program_id = format!("{}.aleo", asset_id);
+    let program_id = token_program_id::<N>(asset_id);
 
     let mut pending_transaction = TransactionPointer::<N>::new(
         Some(to.to_string()),
@@ -510,25 +516,21 @@ async fn transfer_private_to_public_util<N: Network>(
         None,
         message,
         EventTypeCommon::Send,
-        Some(*amount as f64 / 1000000.0),
+        Some(format_amount(*amount as u128, token_decimals::<N>(asset_id))),
         Some(*fee as f64 / 1000000.0),
         None,
     );
 
     let pending_tx_id = pending_transaction.encrypt_and_store(sender_address)?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &pending_tx_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        &pending_tx_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
 
     // update spent states
     update_record_spent_local::<N>(&token_id, true)?;
@@ -549,12 +551,13 @@ async fn transfer_private_to_public_util<N: Network>(
         };
     };
 
-    let transfer_res = match program_manager.transfer(
+    let transfer_res = match transfer_with_prover::<N>(
+        signer.as_ref(),
+        asset_id,
         amount.to_owned(),
         fee.to_owned(),
         recipient,
         TransferType::PrivateToPublic,
-        None,
         Some(token_record.clone()),
         fee_record.clone(),
         &program_id,
@@ -582,18 +585,14 @@ async fn transfer_private_to_public_util<N: Network>(
                 TransactionState::Failed,
             )?;
 
-            if let Some(window) = window.clone() {
-                match window.emit("tx_state_change", &pending_tx_id) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(AvailError::new(
-                            AvailErrorType::Internal,
-                            "Error emitting tx_state_change event".to_string(),
-                            "Error emitting transaction state".to_string(),
-                        ));
-                    }
-                };
-            };
+            publish_tx_state(
+                window.as_ref(),
+                &pending_tx_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
 
             return Err(AvailError::new(
                 AvailErrorType::Internal,
@@ -628,17 +627,13 @@ async fn transfer_public<N: Network>(
     password: Option<String>,
     window: Option<Window>,
 ) -> AvailResult<String> {
-    let api_client = setup_client::<N>()?;
     let sender_address = get_address::<N>()?;
-    let private_key = get_private_key::<N>(password)?;
+    let signer = get_signer::<N>(password)?;
 
     //extend session auth
-    get_session_after_creation::<N>(&private_key).await?;
+    get_session_with_signer::<N>(signer.as_ref()).await?;
     let recipient = get_address_from_recipient::<N>(to).await?;
 
-    let program_manager =
-        ProgramManager::<N>::new(Some(private_key), None, Some(api_client.clone()), None)?;
-
     let mut record_nonces: Vec<String> = vec![];
 
     // get required records if private fee
@@ -656,7 +651,7 @@ async fn transfer_public<N: Network>(
         false => (None, None, None),
     };
 
-    let program_id = format!("{}.aleo", asset_id);
+    let program_id = token_program_id::<N>(asset_id);
 
     let mut pending_transaction = TransactionPointer::<N>::new(
         Some(to.to_string()),
@@ -671,25 +666,21 @@ async fn transfer_public<N: Network>(
         None,
         message,
         EventTypeCommon::Send,
-        Some(*amount as f64 / 1000000.0),
+        Some(format_amount(*amount as u128, token_decimals::<N>(asset_id))),
         Some(*fee as f64 / 1000000.0),
         None,
     );
 
     let pending_tx_id = pending_transaction.encrypt_and_store(sender_address)?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &pending_tx_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        &pending_tx_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
 
     // update spent states
     if let Some(fee_id) = fee_id.clone() {
@@ -709,13 +700,14 @@ async fn transfer_public<N: Network>(
         };
     };
 
-    let transfer_res = match program_manager.transfer(
+    let transfer_res = match transfer_with_prover::<N>(
+        signer.as_ref(),
+        asset_id,
         amount.to_owned(),
         fee.to_owned(),
         recipient,
         TransferType::Public,
         None,
-        None,
         fee_record.clone(),
         &program_id,
     ) {
@@ -740,18 +732,14 @@ async fn transfer_public<N: Network>(
                 TransactionState::Failed,
             )?;
 
-            if let Some(window) = window.clone() {
-                match window.emit("tx_state_change", &pending_tx_id) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        return Err(AvailError::new(
-                            AvailErrorType::Internal,
-                            "Error emitting tx_state_change event".to_string(),
-                            "Error emitting transaction state".to_string(),
-                        ));
-                    }
-                };
-            };
+            publish_tx_state(
+                window.as_ref(),
+                &pending_tx_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
 
             return Err(AvailError::new(
                 AvailErrorType::Internal,
@@ -966,120 +954,36 @@ pub fn find_confirmed_block_height<N: Network>(
 
 /* -- Inclusion Prover Handlers -- */
 
+/// Verifies the inclusion prover and downloads it when it is missing or corrupted
 #[tauri::command(rename_all = "snake_case")]
 pub async fn pre_install_inclusion_prover(window: Window) -> AvailResult<()> {
-    let path = match dirs::home_dir() {
-        Some(home_dir) => home_dir
-            .join(".aleo")
-            .join("resources")
-            .join("inclusion.prover.cd85cc5"),
-
-        None => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error getting home directory".to_string(),
-                "Error getting home directory".to_string(),
-            ))
-        }
-    };
-
-    // Check if file exists and if it meets size treshold
-    if path.as_path().exists() {
-        println!("inclusion.prover.cd85cc5 already exists");
-
-        // check size of file
-        let size = fs::metadata(path.clone())?.len();
-
-        if size < 20000000 {
-            delete_inclusion_prover()?;
-        }
-    }
-
-    Ok(())
-}
-
-async fn install_prover(path: PathBuf, window: Window) -> AvailResult<()> {
-    let client = reqwest::Client::new();
-
-    match window.emit("inclusion_prover_downloading", ()) {
-        Ok(_) => {}
-        Err(_) => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error emitting inclusion prover downloading alert.".to_string(),
-                "Error emitting Aleo resources downloading alert.".to_string(),
-            ));
-        }
-    };
-
-    println!("Downloading inclusion.prover.cd85cc5...");
-
-    let res = match client
-        .get("https://s3-us-west-1.amazonaws.com/testnet3.parameters/inclusion.prover.cd85cc5")
-        .send()
-        .await
-    {
-        Ok(res) => res,
-        Err(e) => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error downloading inclusion.prover.cd85cc5".to_string(),
-                format!("Error downloading inclusion.prover.cd85cc5: {:?}", e),
-            ));
-        }
-    };
+    let resources_dir = resources_dir()?;
+    let manifest = get_manifest(&resources_dir, &[INCLUSION.to_string()]).await?;
 
-    println!("Finished downloading inclusion.prover.cd85cc5...");
+    for file in manifest {
+        let path = file.path(&resources_dir);
 
-    let body = match res.bytes().await {
-        Ok(body) => body,
-        Err(e) => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error reading inclusion.prover.cd85cc5".to_string(),
-                format!("Error reading inclusion.prover.cd85cc5: {:?}", e),
-            ));
-        }
-    };
+        if path.as_path().exists() {
+            if file.verify(&path).is_ok() {
+                continue;
+            }
 
-    match fs::write(path.clone(), body) {
-        Ok(_) => {}
-        Err(e) => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error writing inclusion.prover.cd85cc5".to_string(),
-                format!("Error writing inclusion.prover.cd85cc5: {:?}", e),
-            ));
+            println!("{} is corrupted, downloading again", file.filename());
+            fs::remove_file(&path)?;
         }
-    };
-
-    let size = fs::metadata(path)?.len();
-
-    println!("SIZE '{}'", size);
-
-    println!("Finished writing inclusion.prover.cd85cc5...");
-
-    Ok(())
-}
-
-fn delete_inclusion_prover() -> AvailResult<()> {
-    let path = match dirs::home_dir() {
-        Some(home_dir) => home_dir
-            .join(".aleo")
-            .join("resources")
-            .join("inclusion.prover.cd85cc5"),
 
-        None => {
-            return Err(AvailError::new(
-                AvailErrorType::Internal,
-                "Error getting home directory".to_string(),
-                "Error getting home directory".to_string(),
-            ))
-        }
-    };
+        match window.emit("inclusion_prover_downloading", ()) {
+            Ok(_) => {}
+            Err(_) => {
+                return Err(AvailError::new(
+                    AvailErrorType::Internal,
+                    "Error emitting inclusion prover downloading alert.".to_string(),
+                    "Error emitting Aleo resources downloading alert.".to_string(),
+                ));
+            }
+        };
 
-    if path.as_path().exists() {
-        fs::remove_file(path)?;
+        download_parameter(&resources_dir, &file, Some(window.clone())).await?;
     }
 
     Ok(())
@@ -1116,6 +1020,7 @@ mod transfer_tests {
     use crate::services::account::key_management::key_controller::windowsKeyController;
 
     use super::*;
+    use avail_common::aleo_tools::program_manager::ProgramManager;
     async fn test_setup_prerequisites() {
         let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
         let ext = Identifier::<TestnetV0>::from_str("test").unwrap();
@@ -0,0 +1,593 @@
+use rand::thread_rng;
+use snarkvm::circuit::{Aleo, AleoTestnetV0};
+use snarkvm::prelude::{
+    Address, Entry, Identifier, Literal, Network, Plaintext, PrivateKey, Program, Record,
+    TestnetV0, Value, ValueType,
+};
+use snarkvm::synthesizer::program::Function;
+use std::str::FromStr;
+
+use crate::models::wallet_connect::{
+    create_event::CreateEventRequest,
+    explain::{
+        ConsumedRecord, CreatedRecord, ExplainedInput, PublicTransfer, TransactionExplanation,
+    },
+};
+use crate::services::local_storage::{
+    persistent_storage::{get_address, get_network},
+    reputation::{get_reputation, request_targets},
+    token_metadata::{format_amount, resolve_token_metadata, token_decimals},
+};
+use crate::services::record_handling::{
+    deployment::{parse_deployment_inputs, prepare_deployment},
+    known_calls::known_call,
+    program_abi::{get_program, input_abi, validate_request_inputs},
+    prover::estimate_base_fee,
+    token_registry::{record_token_id, TOKEN_REGISTRY},
+};
+
+use avail_common::{
+    errors::AvailResult,
+    models::{encrypted_data::EventTypeCommon, network::SupportedNetworks},
+};
+
+/// Token an amount is counted in, with its display symbol and decimals
+struct Asset {
+    id: String,
+    symbol: String,
+    decimals: u8,
+}
+
+impl Asset {
+    fn new<N: Network>(asset_id: &str) -> Self {
+        let symbol = match resolve_token_metadata::<N>(asset_id) {
+            Ok(Some(token)) => token.symbol,
+            _ => asset_id.to_string(),
+        };
+
+        Self {
+            id: asset_id.to_string(),
+            symbol,
+            decimals: token_decimals::<N>(asset_id),
+        }
+    }
+}
+
+fn label<N: Network>(address: Option<Address<N>>, self_address: &Address<N>) -> String {
+    match address {
+        Some(address) if &address == self_address => "yourself".to_string(),
+        Some(address) => address.to_string(),
+        None => "an unknown address".to_string(),
+    }
+}
+
+/// Templated description and public transfers of credits.aleo, the token registry and token programs
+fn describe_known<N: Network>(
+    program_id: &str,
+    function_id: &str,
+    inputs: &[String],
+    self_address: &Address<N>,
+) -> Option<(String, Vec<PublicTransfer>)> {
+    // arguments are read by their position in the known signature of the function
+    let call = known_call::<N>(program_id, function_id, inputs)?;
+    let asset = Asset::new::<N>(&call.asset_id);
+
+    let amount = call
+        .amount
+        .map(|amount| format_amount(amount, asset.decimals));
+    let amount_label = match amount {
+        Some(amount) => format!("{} {}", amount, asset.symbol),
+        None => asset.symbol.clone(),
+    };
+    let recipient = call.recipient;
+    let to = label(recipient, self_address);
+
+    let transfer = |from: Option<String>, to: Option<String>| PublicTransfer {
+        asset: asset.id.clone(),
+        from,
+        to,
+        amount: amount.unwrap_or_default(),
+    };
+    let own = Some(self_address.to_string());
+    let recipient = recipient.map(|address| address.to_string());
+
+    let described = match call.abi.function {
+        "transfer_public" | "transfer_public_as_signer" => (
+            format!("Send {} publicly to {}", amount_label, to),
+            vec![transfer(own, recipient)],
+        ),
+        "transfer_private" => (
+            format!("Send {} privately to {}", amount_label, to),
+            vec![],
+        ),
+        "transfer_private_to_public" => (
+            format!(
+                "Send {} from a private record to the public balance of {}",
+                amount_label, to
+            ),
+            vec![transfer(None, recipient)],
+        ),
+        "transfer_public_to_private" => (
+            format!(
+                "Send {} from your public balance privately to {}",
+                amount_label, to
+            ),
+            vec![transfer(own, None)],
+        ),
+        "join" => {
+            ("Join two credits records into one".to_string(), vec![])
+        }
+        "split" => (
+            format!("Split a credits record, {} go into a new record", amount_label),
+            vec![],
+        ),
+        "bond_public" => (
+            format!("Bond {} to validator {}", amount_label, to),
+            vec![transfer(own, None)],
+        ),
+        "bond_validator" => (
+            format!("Bond {} as a validator", amount_label),
+            vec![transfer(own, None)],
+        ),
+        "unbond_public" => (
+            format!("Unbond {} staked by {}", amount_label, to),
+            vec![],
+        ),
+        "claim_unbond_public" => {
+            (format!("Claim the unbonded credits of {}", to), vec![])
+        }
+        _ => return None,
+    };
+
+    Some(described)
+}
+
+/// Amount of a record in display units, with the asset it is counted in
+fn record_value<N: Network>(
+    program_id: &str,
+    record: &Record<N, Plaintext<N>>,
+) -> (Option<String>, Option<f64>) {
+    if program_id == "credits.aleo" {
+        let amount = record
+            .microcredits()
+            .ok()
+            .map(|amount| format_amount(amount as u128, 6));
+        return (Some("credits".to_string()), amount);
+    }
+
+    let asset = match program_id {
+        TOKEN_REGISTRY => record_token_id::<N>(record).map(|token_id| token_id.to_string()),
+        _ => program_id.strip_suffix(".aleo").map(|id| id.to_string()),
+    };
+
+    match asset {
+        Some(asset) => {
+            let amount = record_entry_amount::<N>(record)
+                .map(|amount| format_amount(amount, token_decimals::<N>(&asset)));
+            (Some(asset), amount)
+        }
+        None => (None, None),
+    }
+}
+
+/// The `amount` entry of a token record, token programs use either u64 or u128
+fn record_entry_amount<N: Network>(record: &Record<N, Plaintext<N>>) -> Option<u128> {
+    let key = Identifier::<N>::from_str("amount").ok()?;
+
+    match record.data().get(&key)? {
+        Entry::Private(Plaintext::Literal(Literal::U64(amount), _))
+        | Entry::Public(Plaintext::Literal(Literal::U64(amount), _))
+        | Entry::Constant(Plaintext::Literal(Literal::U64(amount), _)) => Some(**amount as u128),
+        Entry::Private(Plaintext::Literal(Literal::U128(amount), _))
+        | Entry::Public(Plaintext::Literal(Literal::U128(amount), _))
+        | Entry::Constant(Plaintext::Literal(Literal::U128(amount), _)) => Some(**amount),
+        _ => None,
+    }
+}
+
+/// Owner of a value created in the function, when it is an input address or the caller
+fn resolve_owner<N: Network>(
+    function: &Function<N>,
+    inputs: &[String],
+    operand: &str,
+    self_address: &Address<N>,
+) -> Option<Address<N>> {
+    if operand == "self.caller" || operand == "self.signer" {
+        return Some(*self_address);
+    }
+
+    let index = function
+        .inputs()
+        .iter()
+        .position(|input| input.register().to_string() == operand)?;
+
+    Address::<N>::from_str(inputs.get(index)?).ok()
+}
+
+/// Records a function outputs and who they go to, the owner is read from the `cast` creating the record
+fn created_records<N: Network>(
+    function: &Function<N>,
+    inputs: &[String],
+    self_address: &Address<N>,
+) -> Vec<CreatedRecord> {
+    function
+        .outputs()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, output)| {
+            let (record_name, is_local) = match output.value_type() {
+                ValueType::Record(name) => (format!("{}.record", name), true),
+                ValueType::ExternalRecord(locator) => (format!("{}.record", locator), false),
+                _ => return None,
+            };
+
+            let register = output.operand().to_string();
+            let recipient = match is_local {
+                true => function
+                    .instructions()
+                    .iter()
+                    .find(|instruction| {
+                        instruction.opcode().to_string() == "cast"
+                            && instruction
+                                .destinations()
+                                .iter()
+                                .any(|destination| destination.to_string() == register)
+                    })
+                    .and_then(|cast| cast.operands().first())
+                    .and_then(|owner| {
+                        resolve_owner(function, inputs, &owner.to_string(), self_address)
+                    }),
+                // records of other programs are created by a call, their owner isn't followed
+                false => None,
+            };
+
+            Some(CreatedRecord {
+                output: index,
+                record_name,
+                to_self: recipient.as_ref() == Some(self_address),
+                recipient: recipient.map(|address| address.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Explains an execution whose inputs were already validated against the program
+pub fn explain_execution<N: Network>(
+    program: &Program<N>,
+    request: &CreateEventRequest,
+    fee_private: bool,
+    self_address: &Address<N>,
+) -> AvailResult<TransactionExplanation> {
+    let program_id = program.id().to_string();
+    let function = program.get_function(&request.function_id().parse()?)?;
+    let inputs = request.inputs();
+
+    let mut explained_inputs = vec![];
+    let mut consumed_records = vec![];
+    for (index, (input, value)) in function.inputs().iter().zip(inputs).enumerate() {
+        let (value_type, visibility) = input_abi(input.value_type());
+
+        let record_program = match input.value_type() {
+            ValueType::Record(_) => Some(program_id.clone()),
+            ValueType::ExternalRecord(locator) => Some(locator.program_id().to_string()),
+            _ => None,
+        };
+
+        if let Some(record_program) = record_program {
+            if let Ok(record) = Record::<N, Plaintext<N>>::from_str(value) {
+                let (asset, amount) = record_value::<N>(&record_program, &record);
+                consumed_records.push(ConsumedRecord {
+                    input: index,
+                    nonce: record.nonce().to_string(),
+                    asset,
+                    amount,
+                });
+            }
+        }
+
+        explained_inputs.push(ExplainedInput {
+            register: input.register().to_string(),
+            value_type,
+            visibility: visibility.to_string(),
+            value: match visibility {
+                "record" | "external_record" => None,
+                _ => Some(value.clone()),
+            },
+        });
+    }
+
+    let created_records = created_records(&function, inputs, self_address);
+
+    let known = describe_known::<N>(&program_id, request.function_id(), inputs, self_address);
+
+    let mut warnings = vec![];
+    if known.is_none() {
+        for instruction in function.instructions() {
+            if instruction.opcode().to_string() == "call" {
+                if let Some(callee) = instruction.to_string().split_whitespace().nth(1) {
+                    warnings.push(format!("Calls {}, its effects are not decoded", callee));
+                }
+            }
+        }
+
+        if function.finalize_logic().is_some() {
+            warnings.push(format!(
+                "Changes public state of {} that is not decoded",
+                program_id
+            ));
+        }
+    }
+    for record in created_records.iter().filter(|record| record.recipient.is_none()) {
+        warnings.push(format!(
+            "The owner of output {} is only known after executing",
+            record.output + 1
+        ));
+    }
+
+    let (description, public_transfers) = match known {
+        Some((description, public_transfers)) => (Some(description), public_transfers),
+        None => (None, vec![]),
+    };
+
+    Ok(TransactionExplanation {
+        program_id,
+        function_id: request.function_id().clone(),
+        description,
+        inputs: explained_inputs,
+        consumed_records,
+        created_records,
+        public_transfers,
+        fee: request.fee(),
+        base_fee: None,
+        priority_fee: request.fee(),
+        fee_private,
+        warnings,
+        reputation: vec![],
+    })
+}
+
+/// Explanation without decoded inputs, the request fee is taken as the priority fee
+fn bare_explanation(
+    request: &CreateEventRequest,
+    fee_private: bool,
+    description: Option<String>,
+) -> TransactionExplanation {
+    TransactionExplanation {
+        program_id: request.program_id().clone(),
+        function_id: request.function_id().clone(),
+        description,
+        inputs: vec![],
+        consumed_records: vec![],
+        created_records: vec![],
+        public_transfers: vec![],
+        fee: request.fee(),
+        base_fee: None,
+        priority_fee: request.fee(),
+        fee_private,
+        warnings: vec![],
+        reputation: vec![],
+    }
+}
+
+fn to_credits(microcredits: u64) -> f64 {
+    microcredits as f64 / 1000000.0
+}
+
+/// Base fee of the request in microcredits. Executions are estimated with a throwaway key,
+/// so requests spending records of the wallet can't be estimated before signing.
+fn request_base_fee<N: Network, A: Aleo<Network = N>>(
+    request: &CreateEventRequest,
+) -> AvailResult<u64> {
+    match request.event_type() {
+        EventTypeCommon::Deploy => {
+            let (program, local_imports) = parse_deployment_inputs::<N>(request.inputs())?;
+            Ok(prepare_deployment::<N, A>(program, &local_imports, 0)?.minimum_cost)
+        }
+        _ => {
+            let inputs = request
+                .inputs()
+                .iter()
+                .map(|input| Value::<N>::from_str(input))
+                .collect::<Result<Vec<Value<N>>, _>>()?;
+
+            estimate_base_fee::<N, A>(
+                &PrivateKey::<N>::new(&mut thread_rng())?,
+                request.program_id(),
+                request.function_id(),
+                &inputs,
+            )
+        }
+    }
+}
+
+fn explain_create_event_raw<N: Network, A: Aleo<Network = N>>(
+    request: CreateEventRequest,
+    fee_private: bool,
+) -> AvailResult<TransactionExplanation> {
+    let (request, mut explanation) = match request.event_type() {
+        EventTypeCommon::Deploy => {
+            let description = Some(format!("Deploy program {}", request.program_id()));
+            let explanation = bare_explanation(&request, fee_private, description);
+
+            (request, explanation)
+        }
+        _ => {
+            let request = validate_request_inputs::<N>(&request)?;
+            let program = get_program::<N>(request.program_id())?;
+            let explanation =
+                explain_execution(&program, &request, fee_private, &get_address::<N>()?)?;
+
+            (request, explanation)
+        }
+    };
+
+    // the request fee is paid on top of what the network charges for the transaction
+    match request_base_fee::<N, A>(&request) {
+        Ok(base_fee) => {
+            explanation.base_fee = Some(to_credits(base_fee));
+            explanation.fee = to_credits(base_fee) + explanation.priority_fee;
+        }
+        Err(_) => explanation
+            .warnings
+            .push("The base fee could not be estimated, it is added to the fee shown".to_string()),
+    }
+
+    let mut targets = request_targets::<N>(&request);
+    targets.extend(
+        explanation
+            .created_records
+            .iter()
+            .filter_map(|record| record.recipient.clone()),
+    );
+    explanation.reputation = get_reputation(&targets)?;
+
+    for entry in explanation.reputation.iter().filter(|entry| entry.is_blocked()) {
+        explanation.warnings.insert(0, entry.blocked_message());
+    }
+
+    Ok(explanation)
+}
+
+/// Decodes a `request_create_event` request for the approval screen
+#[tauri::command(rename_all = "snake_case")]
+pub fn explain_create_event(
+    request: CreateEventRequest,
+    fee_private: bool,
+) -> AvailResult<TransactionExplanation> {
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            explain_create_event_raw::<TestnetV0, AleoTestnetV0>(request, fee_private)
+        }
+        _ => explain_create_event_raw::<TestnetV0, AleoTestnetV0>(request, fee_private),
+    }
+}
+
+/// Explains a request for the approval screen, a request that can't be decoded is still shown
+/// with the reason as a warning instead of failing the approval
+pub fn explain_for_approval(
+    request: &CreateEventRequest,
+    fee_private: bool,
+) -> TransactionExplanation {
+    match explain_create_event(request.clone(), fee_private) {
+        Ok(explanation) => explanation,
+        Err(e) => {
+            let mut explanation = bare_explanation(request, fee_private, None);
+            explanation.warnings.push(format!(
+                "The request could not be decoded: {}",
+                e.external_msg
+            ));
+
+            explanation
+        }
+    }
+}
+
+#[cfg(test)]
+mod tx_decoder_tests {
+    use super::*;
+    use snarkvm::prelude::PrivateKey;
+
+    const MARKET: &str = r"program market.aleo;
+
+record ticket:
+    owner as address.private;
+    seat as u32.private;
+
+function buy:
+    input r0 as address.private;
+    input r1 as u32.private;
+    cast r0 r1 into r2 as ticket.record;
+    cast self.caller r1 into r3 as ticket.record;
+    output r2 as ticket.record;
+    output r3 as ticket.record;
+";
+
+    fn address() -> Address<TestnetV0> {
+        let private_key = PrivateKey::<TestnetV0>::new(&mut rand::thread_rng()).unwrap();
+        Address::try_from(&private_key).unwrap()
+    }
+
+    #[test]
+    fn test_created_records() {
+        let program = Program::<TestnetV0>::from_str(MARKET).unwrap();
+        let function = program.get_function(&"buy".parse().unwrap()).unwrap();
+        let (buyer, friend) = (address(), address());
+
+        let inputs = vec![friend.to_string(), "7u32".to_string()];
+        let records = created_records(&function, &inputs, &buyer);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].recipient, Some(friend.to_string()));
+        assert!(!records[0].to_self);
+        assert_eq!(records[1].recipient, Some(buyer.to_string()));
+        assert!(records[1].to_self);
+    }
+
+    #[test]
+    fn test_explain_execution() {
+        let program = Program::<TestnetV0>::from_str(MARKET).unwrap();
+        let (buyer, friend) = (address(), address());
+
+        let request = CreateEventRequest::new(
+            None,
+            EventTypeCommon::Execute,
+            "market.aleo".to_string(),
+            "buy".to_string(),
+            0.25,
+            vec![friend.to_string(), "7u32".to_string()],
+        );
+        let explanation = explain_execution(&program, &request, false, &buyer).unwrap();
+
+        assert_eq!(explanation.description, None);
+        assert_eq!(explanation.inputs[1].value, Some("7u32".to_string()));
+        assert_eq!(explanation.inputs[0].visibility, "private");
+        assert!(explanation.consumed_records.is_empty());
+        assert_eq!(explanation.created_records.len(), 2);
+        assert_eq!(explanation.fee, 0.25);
+        assert!(explanation.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_describe_known_by_position() {
+        let (staker, validator) = (address(), address());
+
+        // the validator is the first address, the withdrawal address the second
+        let inputs = vec![
+            validator.to_string(),
+            staker.to_string(),
+            "2000000u64".to_string(),
+        ];
+        let (description, transfers) =
+            describe_known::<TestnetV0>("credits.aleo", "bond_public", &inputs, &staker).unwrap();
+
+        assert_eq!(
+            description,
+            format!("Bond 2 credits to validator {}", validator)
+        );
+        assert_eq!(transfers[0].amount, 2.0);
+
+        // same name, but not the signature of credits.aleo
+        let inputs = vec!["2000000u64".to_string(), validator.to_string()];
+        assert!(
+            describe_known::<TestnetV0>("credits.aleo", "transfer_public", &inputs, &staker)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_undecodable_request_is_explained() {
+        let request = CreateEventRequest::new(
+            None,
+            EventTypeCommon::Execute,
+            "credits.aleo".to_string(),
+            "transfer_public".to_string(),
+            0.25,
+            vec!["not an address".to_string()],
+        );
+        let explanation = explain_for_approval(&request, false);
+
+        assert_eq!(explanation.description, None);
+        assert_eq!(explanation.fee, 0.25);
+        assert!(!explanation.warnings.is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Window};
+use tokio::sync::mpsc;
+
+use crate::models::event_payloads::TxStateChangePayload;
+
+use avail_common::{
+    errors::{AvailError, AvailErrorType, AvailResult},
+    models::encrypted_data::TransactionState,
+};
+
+/// Which transaction state changes a subscriber receives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxSubscription {
+    /// Changes of a single event
+    Event(String),
+    /// Changes of every event created by the dapp at this origin
+    Origin(String),
+    /// Every change
+    All,
+}
+
+impl TxSubscription {
+    pub fn matches(&self, payload: &TxStateChangePayload) -> bool {
+        match self {
+            TxSubscription::Event(event_id) => &payload.event_id == event_id,
+            TxSubscription::Origin(origin) => payload.origin.as_ref() == Some(origin),
+            TxSubscription::All => true,
+        }
+    }
+}
+
+type Subscriber = (TxSubscription, mpsc::UnboundedSender<TxStateChangePayload>);
+
+/// Unfinished events that saw no change for this long are forgotten
+const EVENT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What the bus keeps about an event until it reaches a final state
+#[derive(Debug, Clone)]
+struct TrackedEvent {
+    state: Option<TransactionState>,
+    origin: Option<String>,
+    updated_at: Instant,
+}
+
+impl TrackedEvent {
+    fn new(now: Instant) -> Self {
+        Self {
+            state: None,
+            origin: None,
+            updated_at: now,
+        }
+    }
+}
+
+/// Central place transaction state changes are published to.
+/// Keeps the last state of every unfinished event so each change carries the state it came from.
+pub struct TxEventBus {
+    next_id: AtomicU64,
+    subscribers: Mutex<HashMap<u64, Subscriber>>,
+    events: Mutex<HashMap<String, TrackedEvent>>,
+}
+
+pub static TX_EVENTS: Lazy<TxEventBus> = Lazy::new(TxEventBus::new);
+
+fn lock_error(e: impl std::fmt::Display) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Internal,
+        format!("Error locking transaction event bus: {}", e),
+        "Error emitting transaction state".to_string(),
+    )
+}
+
+/// Drops events that never reached a final state, e.g. when the app was closed while they were pending
+fn expire(events: &mut HashMap<String, TrackedEvent>, now: Instant) {
+    events.retain(|_, event| now.duration_since(event.updated_at) < EVENT_TTL);
+}
+
+fn is_final(state: &TransactionState) -> bool {
+    matches!(
+        state,
+        TransactionState::Confirmed
+            | TransactionState::Rejected
+            | TransactionState::Aborted
+            | TransactionState::Failed
+            | TransactionState::Cancelled
+    )
+}
+
+impl TxEventBus {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            subscribers: Mutex::new(HashMap::new()),
+            events: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a subscriber, changes arrive on the returned receiver until [`TxEventBus::unsubscribe`]
+    pub fn subscribe(
+        &self,
+        subscription: TxSubscription,
+    ) -> AvailResult<(u64, mpsc::UnboundedReceiver<TxStateChangePayload>)> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        self.subscribers
+            .lock()
+            .map_err(lock_error)?
+            .insert(id, (subscription, sender));
+
+        Ok((id, receiver))
+    }
+
+    pub fn unsubscribe(&self, id: u64) -> AvailResult<()> {
+        self.subscribers.lock().map_err(lock_error)?.remove(&id);
+
+        Ok(())
+    }
+
+    /// Ties an event to the dapp that requested it, so origin subscribers receive its changes
+    pub fn set_origin(&self, event_id: &str, origin: &str) -> AvailResult<()> {
+        let now = Instant::now();
+        let mut events = self.events.lock().map_err(lock_error)?;
+        expire(&mut events, now);
+
+        let event = events
+            .entry(event_id.to_string())
+            .or_insert_with(|| TrackedEvent::new(now));
+        event.origin = Some(origin.to_string());
+        event.updated_at = now;
+
+        Ok(())
+    }
+
+    /// Builds the payload of a change and forgets events that reached a final state
+    fn record(
+        &self,
+        event_id: &str,
+        state: TransactionState,
+        transaction_id: Option<String>,
+        height: Option<u32>,
+        error: Option<String>,
+    ) -> AvailResult<TxStateChangePayload> {
+        let now = Instant::now();
+        let mut events = self.events.lock().map_err(lock_error)?;
+        expire(&mut events, now);
+
+        let (old_state, origin) = match is_final(&state) {
+            true => match events.remove(event_id) {
+                Some(event) => (event.state, event.origin),
+                None => (None, None),
+            },
+            false => {
+                let event = events
+                    .entry(event_id.to_string())
+                    .or_insert_with(|| TrackedEvent::new(now));
+                event.updated_at = now;
+                (event.state.replace(state.clone()), event.origin.clone())
+            }
+        };
+
+        Ok(TxStateChangePayload {
+            event_id: event_id.to_string(),
+            old_state,
+            new_state: state,
+            transaction_id,
+            height,
+            error,
+            origin,
+        })
+    }
+
+    fn dispatch(&self, payload: &TxStateChangePayload) -> AvailResult<()> {
+        let mut subscribers = self.subscribers.lock().map_err(lock_error)?;
+
+        // receivers that were dropped without unsubscribing are cleaned up here
+        subscribers.retain(|_, (subscription, sender)| {
+            !subscription.matches(payload) || sender.send(payload.clone()).is_ok()
+        });
+
+        Ok(())
+    }
+}
+
+impl Default for TxEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Publishes a transaction state change to the bus subscribers and the window.
+/// The window keeps receiving the event id as `tx_state_change` and gets the full change as `tx_state_update`.
+pub fn publish_tx_state(
+    window: Option<&Window>,
+    event_id: &str,
+    state: TransactionState,
+    transaction_id: Option<String>,
+    height: Option<u32>,
+    error: Option<String>,
+) -> AvailResult<()> {
+    let payload = TX_EVENTS.record(event_id, state, transaction_id, height, error)?;
+    TX_EVENTS.dispatch(&payload)?;
+
+    if let Some(window) = window {
+        if window.emit("tx_state_change", event_id).is_err()
+            || window.emit("tx_state_update", &payload).is_err()
+        {
+            return Err(AvailError::new(
+                AvailErrorType::Internal,
+                "Error emitting tx_state_change event".to_string(),
+                "Error emitting transaction state".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tx_events_tests {
+    use super::*;
+
+    #[test]
+    fn test_state_transitions() {
+        let bus = TxEventBus::new();
+        bus.set_origin("event", "https://app.example").unwrap();
+
+        let processing = bus
+            .record("event", TransactionState::Processing, None, None, None)
+            .unwrap();
+        assert_eq!(processing.old_state, None);
+        assert_eq!(processing.origin, Some("https://app.example".to_string()));
+
+        let pending = bus
+            .record("event", TransactionState::Pending, Some("at1".to_string()), None, None)
+            .unwrap();
+        assert_eq!(pending.old_state, Some(TransactionState::Processing));
+
+        let confirmed = bus
+            .record("event", TransactionState::Confirmed, Some("at1".to_string()), Some(7), None)
+            .unwrap();
+        assert_eq!(confirmed.old_state, Some(TransactionState::Pending));
+        assert_eq!(confirmed.origin, Some("https://app.example".to_string()));
+        assert_eq!(confirmed.height, Some(7));
+
+        // final states are forgotten
+        assert!(bus.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unfinished_events_expire() {
+        let bus = TxEventBus::new();
+        bus.set_origin("stuck", "https://app.example").unwrap();
+        bus.record("stuck", TransactionState::Pending, None, None, None)
+            .unwrap();
+
+        let mut events = bus.events.lock().unwrap();
+        expire(&mut events, Instant::now());
+        assert!(events.contains_key("stuck"));
+
+        expire(&mut events, Instant::now() + EVENT_TTL);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let bus = TxEventBus::new();
+        bus.set_origin("dapp_event", "https://app.example").unwrap();
+
+        let (_, mut by_event) = bus.subscribe(TxSubscription::Event("wallet_event".to_string())).unwrap();
+        let (_, mut by_origin) = bus
+            .subscribe(TxSubscription::Origin("https://app.example".to_string()))
+            .unwrap();
+        let (all_id, all) = bus.subscribe(TxSubscription::All).unwrap();
+        drop(all);
+
+        for event_id in ["wallet_event", "dapp_event"] {
+            let payload = bus
+                .record(event_id, TransactionState::Processing, None, None, None)
+                .unwrap();
+            bus.dispatch(&payload).unwrap();
+        }
+
+        assert_eq!(by_event.try_recv().unwrap().event_id, "wallet_event");
+        assert!(by_event.try_recv().is_err());
+        assert_eq!(by_origin.try_recv().unwrap().event_id, "dapp_event");
+        assert!(by_origin.try_recv().is_err());
+
+        // the dropped subscriber was removed
+        assert!(!bus.subscribers.lock().unwrap().contains_key(&all_id));
+    }
+}
@@ -13,7 +13,6 @@ use snarkvm::utilities::ToBits;
 use std::collections::HashMap;
 use std::ops::Sub;
 use std::str::FromStr;
-use tauri::Emitter;
 use tauri::{Manager, Window};
 
 use crate::api::{
@@ -35,8 +34,11 @@ use crate::models::wallet_connect::balance::Balance;
 
 use crate::models::wallet_connect::records::{GetRecordsRequest, RecordFilterType, RecordsFilter};
 use crate::services::local_storage::encrypted_data::get_encrypted_data_by_flavour;
+use crate::services::local_storage::token_metadata::{
+    format_amount, resolve_token_metadata, token_decimals,
+};
 use crate::services::local_storage::tokens::{
-    add_balance, get_balance, get_program_id_for_token, if_token_exists, init_token,
+    add_balance, get_balance, get_program_id_for_token, if_token_exists, init_token, parse_balance,
 };
 use crate::services::local_storage::{
     encrypted_data::{
@@ -66,6 +68,13 @@ use avail_common::{
 };
 
 use super::decrypt_transition::DecryptTransition;
+use super::mapping::fetch_mapping_value;
+use super::staking::get_staking_balance;
+use super::token_registry::{
+    get_registry_public_balance, parse_token_id, record_token_id, token_balance_name,
+    TOKEN_REGISTRY,
+};
+use super::tx_events::publish_tx_state;
 
 /// Gets all tags from a given block height to the latest block height
 pub fn get_tags<N: Network>(min_block_height: u32) -> AvailResult<Vec<String>> {
@@ -453,7 +462,7 @@ pub fn update_tokens_local_storage<N: Network>(
     let program = api_client.get_program(program_id.clone())?;
     let mut record_type = RecordTypeCommon::None;
     let record_name = match record_name {
-        Some(name) => name,
+        Some(name) => token_balance_name::<N>(&name, &program_id, &record),
         None => "".to_string(),
     };
     if if_token_exists(&record_name.clone())? {
@@ -478,6 +487,7 @@ pub fn update_tokens_local_storage<N: Network>(
         println!("///////////INITIALIZE");
         record_type = match program_id.to_string().as_str() {
             "credits.aleo" => RecordTypeCommon::AleoCredits,
+            TOKEN_REGISTRY if record_token_id::<N>(&record).is_some() => RecordTypeCommon::Tokens,
             _ => get_record_type(program.clone(), record_name.clone(), record.clone())?,
         };
         if record_type == RecordTypeCommon::Tokens || record_type == RecordTypeCommon::AleoCredits {
@@ -517,16 +527,20 @@ pub fn get_record_type_and_amount<N: Network>(
                     Some(bal) => Ok(bal),
                     None => Err(()),
                 };
-                let balance_u64 = match balance_entry.unwrap() {
-                    Entry::Private(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Public(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Constant(Plaintext::Literal(Literal::<N>::U64(amount), _)) => **amount,
-                    Entry::Private(Plaintext::Literal(Literal::<N>::U128(amount), _)) => {
-                        **amount as u64
+                // registry tokens hold u128 amounts, they are kept as u128 so they aren't truncated
+                balance = match balance_entry.unwrap() {
+                    Entry::Private(Plaintext::Literal(Literal::<N>::U64(amount), _))
+                    | Entry::Public(Plaintext::Literal(Literal::<N>::U64(amount), _))
+                    | Entry::Constant(Plaintext::Literal(Literal::<N>::U64(amount), _)) => {
+                        format!("{}u64", **amount)
                     }
-                    _ => 0u64,
+                    Entry::Private(Plaintext::Literal(Literal::<N>::U128(amount), _))
+                    | Entry::Public(Plaintext::Literal(Literal::<N>::U128(amount), _))
+                    | Entry::Constant(Plaintext::Literal(Literal::<N>::U128(amount), _)) => {
+                        format!("{}u128", **amount)
+                    }
+                    _ => "0u64".to_string(),
                 };
-                balance = format!("{}u64", balance_u64.to_string());
             }
         }
         Ok(balance)
@@ -815,6 +829,11 @@ fn parse_with_suffix(input: &str) -> Result<u64, std::num::ParseIntError> {
 
 /// Get public balance for any ARC20 token
 pub fn get_public_token_balance<N: Network>(asset_id: &str) -> AvailResult<f64> {
+    if let Some(token_id) = parse_token_id::<N>(asset_id) {
+        let pub_balance = get_registry_public_balance::<N>(&token_id)?;
+        return Ok(format_amount(pub_balance, token_decimals::<N>(asset_id)));
+    }
+
     let address = get_address_string()?;
     let record_name = format!("{}.record", asset_id);
     // let program_id = format!("{}.aleo", asset_id);
@@ -823,37 +842,40 @@ pub fn get_public_token_balance<N: Network>(asset_id: &str) -> AvailResult<f64>
         program_id = format!("{}.aleo", asset_id);
     }
 
-    let api_client = setup_client::<N>()?;
-
-    let credits_mapping = match api_client.get_mapping_value(program_id, "account", &address) {
-        Ok(credits_mapping) => credits_mapping,
-        Err(e) => match e.to_string().as_str() {
-            "Mapping not found" => return Ok(0.0),
-            _ => return Err(e.into()),
-        },
+    let credits_mapping = match fetch_mapping_value::<N>(&program_id, "account", &address)? {
+        Some(credits_mapping) => credits_mapping,
+        None => return Ok(0.0),
     };
 
-    let pub_balance = parse_with_suffix(&credits_mapping.to_string())? as f64;
+    let pub_balance = parse_with_suffix(&credits_mapping.to_string())?;
 
     println!("pub_balance: {:?}", pub_balance);
 
-    Ok(pub_balance / 1000000.0)
+    Ok(format_amount(
+        pub_balance as u128,
+        token_decimals::<N>(asset_id),
+    ))
 }
 
 /// Get private balance for any ARC20 token
 pub fn get_private_token_balance<N: Network>(asset_id: &str) -> AvailResult<f64> {
     let address = get_address_string()?;
-    let record_name = format!("{}.record", asset_id);
-    let program_id = format!("{}.aleo", asset_id);
+    // registry token balances are stored under their token id
+    let record_name = match parse_token_id::<N>(asset_id) {
+        Some(token_id) => token_id.to_string(),
+        None => format!("{}.record", asset_id),
+    };
     println!("===> Asset ID in get_private_balance() {:?}", asset_id);
 
     let vk = VIEWSESSION.get_instance::<N>()?;
     let balance = get_balance(&record_name, vk)?;
 
     println!("balance: {:?}", balance);
-    let balance_trimmed = balance.trim_end_matches("u64");
 
-    Ok(balance_trimmed.parse::<u64>()? as f64 / 1000000.0)
+    Ok(format_amount(
+        parse_balance(&balance)?,
+        token_decimals::<N>(asset_id),
+    ))
 }
 
 /// Get Arc20 Token Balance
@@ -867,7 +889,22 @@ pub fn get_token_balance<N: Network>(asset_id: &str) -> AvailResult<Balance> {
     println!("public: {:?}", public);
     println!("private: {:?}", private);
 
-    Ok(Balance::new(public, private))
+    let metadata = resolve_token_metadata::<N>(&asset_id_final).unwrap_or(None);
+
+    // staked credits are not part of the spendable balance, a failed lookup leaves them unreported
+    let staking = match asset_id_final.as_str() {
+        "credits" => get_staking_balance::<N>(&get_address::<N>()?).ok(),
+        _ => None,
+    };
+
+    Ok(Balance::new(
+        public,
+        private,
+        metadata.as_ref().map(|token| token.decimals),
+        metadata.map(|token| token.symbol),
+        staking.as_ref().map(|staking| staking.bonded),
+        staking.map(|staking| staking.unbonding),
+    ))
 }
 
 /// Handles encrypted message passing and updated transaction state
@@ -899,18 +936,14 @@ pub async fn handle_encrypted_storage_and_message<N: Network>(
         TransactionState::Pending,
     )?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &transaction_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        transaction_pointer_id,
+        TransactionState::Pending,
+        Some(transaction_id.to_string()),
+        None,
+        None,
+    )?;
 
     // search for the transaction on chain
     let (
@@ -942,18 +975,14 @@ pub async fn handle_encrypted_storage_and_message<N: Network>(
             sender_address,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &transaction_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            transaction_pointer_id,
+            TransactionState::Rejected,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction rejected by the Aleo blockchain.".to_string()),
+        )?;
 
         // Check for remainder of private fee given back as new record
         for transition in transitions {
@@ -986,18 +1015,14 @@ pub async fn handle_encrypted_storage_and_message<N: Network>(
             TransactionState::Aborted,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &transaction_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            transaction_pointer_id,
+            TransactionState::Aborted,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction aborted by the Aleo blockchain. No tokens were spent.".to_string()),
+        )?;
 
         return Ok(());
     }
@@ -1076,18 +1101,14 @@ pub async fn handle_encrypted_storage_and_message<N: Network>(
         }
     }
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &transaction_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        transaction_pointer_id,
+        TransactionState::Confirmed,
+        Some(transaction_id.to_string()),
+        Some(block_height),
+        None,
+    )?;
 
     if sender_address != recipient_address {
         let transaction_message = TransactionMessage::<N>::new(
@@ -1132,18 +1153,14 @@ pub async fn handle_transaction_update_and_encrypted_storage<N: Network>(
         TransactionState::Pending,
     )?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &transaction_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        transaction_pointer_id,
+        TransactionState::Pending,
+        Some(transaction_id.to_string()),
+        None,
+        None,
+    )?;
 
     let (
         block_height,
@@ -1171,18 +1188,14 @@ pub async fn handle_transaction_update_and_encrypted_storage<N: Network>(
             sender_address,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &transaction_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            transaction_pointer_id,
+            TransactionState::Rejected,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction rejected by the Aleo blockchain.".to_string()),
+        )?;
 
         return Ok(());
     } else if transaction_state == TransactionState::Aborted {
@@ -1207,18 +1220,14 @@ pub async fn handle_transaction_update_and_encrypted_storage<N: Network>(
             TransactionState::Aborted,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &transaction_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            transaction_pointer_id,
+            TransactionState::Aborted,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction aborted by the Aleo blockchain. No tokens were spent.".to_string()),
+        )?;
 
         return Ok(());
     }
@@ -1271,18 +1280,14 @@ pub async fn handle_transaction_update_and_encrypted_storage<N: Network>(
         }
     }
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &transaction_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        transaction_pointer_id,
+        TransactionState::Confirmed,
+        Some(transaction_id.to_string()),
+        Some(block_height),
+        None,
+    )?;
 
     Ok(())
 }
@@ -1313,18 +1318,14 @@ pub async fn handle_deployment_update_and_encrypted_storage<N: Network>(
         TransactionState::Pending,
     )?;
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &deployment_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        deployment_pointer_id,
+        TransactionState::Pending,
+        Some(transaction_id.to_string()),
+        None,
+        None,
+    )?;
 
     let (block_height, transitions, _, transaction_state, _fee_tx_id, _, fee) =
         find_confirmed_block_height::<N>(transaction_id)?;
@@ -1349,18 +1350,14 @@ pub async fn handle_deployment_update_and_encrypted_storage<N: Network>(
             sender_address,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &deployment_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            deployment_pointer_id,
+            TransactionState::Rejected,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction rejected by the Aleo blockchain.".to_string()),
+        )?;
 
         return Ok(());
     } else if transaction_state == TransactionState::Aborted {
@@ -1383,18 +1380,14 @@ pub async fn handle_deployment_update_and_encrypted_storage<N: Network>(
             TransactionState::Aborted,
         )?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &deployment_pointer_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
-        };
+        publish_tx_state(
+            window.as_ref(),
+            deployment_pointer_id,
+            TransactionState::Aborted,
+            Some(transaction_id.to_string()),
+            Some(block_height),
+            Some("Transaction aborted by the Aleo blockchain. No tokens were spent.".to_string()),
+        )?;
 
         return Ok(());
     }
@@ -1422,18 +1415,14 @@ pub async fn handle_deployment_update_and_encrypted_storage<N: Network>(
         }
     }
 
-    if let Some(window) = window.clone() {
-        match window.emit("tx_state_change", &deployment_pointer_id) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Error emitting tx_state_change event".to_string(),
-                    "Error emitting transaction state".to_string(),
-                ));
-            }
-        };
-    };
+    publish_tx_state(
+        window.as_ref(),
+        deployment_pointer_id,
+        TransactionState::Confirmed,
+        Some(transaction_id.to_string()),
+        Some(block_height),
+        None,
+    )?;
 
     Ok(())
 }
@@ -1850,6 +1839,7 @@ pub fn to_commitment<N: Network>(
 pub fn parse_inputs<N: Network>(
     inputs: Vec<String>,
     function_identifier: &str,
+    decimals: u8,
 ) -> AvailResult<(Vec<Value<N>>, Vec<String>, Option<Address<N>>, Option<f64>)> {
     // check if input is address
     let mut values: Vec<Value<N>> = vec![];
@@ -1878,10 +1868,11 @@ pub fn parse_inputs<N: Network>(
 
                     // value is constant plaintext input
                     if function_identifier.contains("transfer") {
-                        let trimmed_input = input.trim_end_matches("u64");
+                        let trimmed_input =
+                            input.trim_end_matches("u64").trim_end_matches("u128");
 
-                        if let Ok(amount_found) = trimmed_input.parse::<u64>() {
-                            amount = Some(amount_found as f64 / 1000000.0);
+                        if let Ok(amount_found) = trimmed_input.parse::<u128>() {
+                            amount = Some(format_amount(amount_found, decimals));
                         }
                     }
                 }
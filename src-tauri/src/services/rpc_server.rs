@@ -0,0 +1,1244 @@
+use futures::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Window};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot, watch, Semaphore};
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+use crate::models::event_payloads::TxStateChangePayload;
+use crate::models::wallet_connect::{
+    create_event::{BatchCreateEventRequest, CreateEventRequest},
+    explain::TransactionExplanation,
+    policy::PolicyDecision,
+    session::DappScope,
+};
+use crate::services::local_storage::{
+    dapp_sessions::{check_dapp_unpaired, grant_new_dapp_session, normalize_origin},
+    persistent_storage::{get_rpc_server_port, update_rpc_server_port},
+    rpc_clients::{get_rpc_client_by_token, get_rpc_client_origins, store_rpc_client, RpcClient},
+    spending_policy::{check_batch_spending_policy, check_spending_policy},
+};
+use crate::services::record_handling::{
+    tx_decoder::explain_for_approval,
+    tx_events::{TxSubscription, TX_EVENTS},
+};
+use crate::services::wallet_connect_api::{
+    decrypt_records, get_balance, get_event, get_events, get_mapping_keys, get_mapping_value,
+    get_records, request_create_event, request_create_events_batch, sign, sign_typed_data,
+};
+
+use avail_common::errors::{AvailError, AvailErrorType, AvailResult};
+
+/// Port the server listens on when none is configured
+pub const DEFAULT_RPC_PORT: u16 = 6310;
+
+/// How long a paired client waits for the user to approve a request in the app
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long a connection may take to send its request headers
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a connection may take to send its request body
+const BODY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Subscription to the ids of the client's events whose state changed, as the app receives them
+const TX_STATE_CHANGE_TOPIC: &str = "tx_state_change";
+
+/// Subscription to typed transaction state changes from the transaction event bus
+const TX_STATE_TOPIC: &str = "tx_state";
+
+/// Requests a websocket connection may have waiting at once
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Connections served at once, further connections are closed right away
+const MAX_CONNECTIONS: usize = 64;
+
+/// Clients that are not browsers are paired under a new origin on this host
+const LOCAL_CLIENT_HOST: &str = "rpc.localhost";
+
+/* --JSON-RPC Protocol-- */
+
+#[derive(Deserialize, Debug)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i64 = -32700;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+    const INTERNAL_ERROR: i64 = -32603;
+    const LIMIT_EXCEEDED: i64 = -32005;
+    const USER_REJECTED: i64 = 4001;
+    const UNAUTHORIZED: i64 = 4100;
+
+    fn new(code: i64, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<AvailError> for RpcError {
+    fn from(error: AvailError) -> Self {
+        let code = match error.error_type {
+            AvailErrorType::Unauthorized => Self::UNAUTHORIZED,
+            AvailErrorType::Validation | AvailErrorType::InvalidData => Self::INVALID_PARAMS,
+            _ => Self::INTERNAL_ERROR,
+        };
+
+        Self::new(code, &error.external_msg)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(RpcError::INVALID_PARAMS, &format!("Invalid params: {}", e)))
+}
+
+fn to_result<T: Serialize>(response: AvailResult<T>) -> Result<Value, RpcError> {
+    let response = response?;
+    serde_json::to_value(response).map_err(|e| AvailError::from(e).into())
+}
+
+/* --In-app Approval-- */
+
+/// The user's answer to an [`RpcApprovalRequest`]
+#[derive(Deserialize, Debug, Default)]
+pub struct RpcApproval {
+    pub approved: bool,
+    /// Scopes granted when pairing, replacing the scopes the client asked for
+    pub scopes: Option<Vec<DappScope>>,
+    /// Session lifetime in seconds when pairing
+    pub expires_in: Option<i64>,
+    /// Password entered for requests outside the spending policies
+    pub password: Option<String>,
+}
+
+/// Payload of the `rpc_approval_request` event the app answers with [`resolve_rpc_approval`]
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcApprovalRequest {
+    pub id: String,
+    pub origin: String,
+    pub name: String,
+    pub method: String,
+    pub params: Value,
+    /// Decoded executions of `request_create_event` and `request_create_events_batch`
+    pub explanations: Vec<TransactionExplanation>,
+}
+
+static PENDING_APPROVALS: Lazy<Mutex<HashMap<String, oneshot::Sender<RpcApproval>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Asks the user to approve a request in the app and waits for the answer
+async fn request_approval(
+    app: &AppHandle,
+    client: &RpcClient,
+    method: &str,
+    params: Value,
+    explanations: Vec<TransactionExplanation>,
+) -> Result<RpcApproval, RpcError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = oneshot::channel();
+
+    if let Ok(mut pending) = PENDING_APPROVALS.lock() {
+        pending.insert(id.clone(), sender);
+    }
+
+    let request = RpcApprovalRequest {
+        id: id.clone(),
+        origin: client.origin.clone(),
+        name: client.name.clone(),
+        method: method.to_string(),
+        params,
+        explanations,
+    };
+
+    if let Err(e) = app.emit("rpc_approval_request", &request) {
+        println!("Error emitting rpc approval request: {:?}", e);
+    }
+
+    let approval = tokio::time::timeout(APPROVAL_TIMEOUT, receiver).await;
+
+    if let Ok(mut pending) = PENDING_APPROVALS.lock() {
+        pending.remove(&id);
+    }
+
+    match approval {
+        Ok(Ok(approval)) if approval.approved => Ok(approval),
+        Ok(_) => Err(RpcError::new(
+            RpcError::USER_REJECTED,
+            "The request was rejected in the wallet",
+        )),
+        Err(_) => Err(RpcError::new(
+            RpcError::USER_REJECTED,
+            "The request was not approved in time",
+        )),
+    }
+}
+
+/// Answers a pending `rpc_approval_request`
+#[tauri::command(rename_all = "snake_case")]
+pub fn resolve_rpc_approval(id: String, approval: RpcApproval) -> AvailResult<()> {
+    let sender = match PENDING_APPROVALS.lock() {
+        Ok(mut pending) => pending.remove(&id),
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Internal,
+                format!("Error locking pending rpc approvals: {}", e),
+                "Error answering the request".to_string(),
+            ))
+        }
+    };
+
+    match sender {
+        Some(sender) => {
+            // the client may have disconnected meanwhile, nothing is left to answer then
+            let _ = sender.send(approval);
+            Ok(())
+        }
+        None => Err(AvailError::new(
+            AvailErrorType::NotFound,
+            format!("No pending rpc approval {}", id),
+            "The request has expired".to_string(),
+        )),
+    }
+}
+
+/* --Request Handling-- */
+
+#[derive(Deserialize, Debug)]
+struct PairParams {
+    name: String,
+    #[serde(default)]
+    scopes: Vec<DappScope>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreateEventParams {
+    request: CreateEventRequest,
+    #[serde(default)]
+    fee_private: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreateEventsBatchParams {
+    request: BatchCreateEventRequest,
+    #[serde(default)]
+    fee_private: bool,
+}
+
+/// Origin a client is paired under. Browsers send the origin header,
+/// any other client is issued a new origin, so it can't claim the origin of a dapp paired before.
+fn pairing_origin(origin_header: Option<&String>) -> Result<String, RpcError> {
+    match origin_header {
+        Some(header) => Ok(normalize_origin(header)?),
+        None => Ok(format!(
+            "http://{}.{}",
+            uuid::Uuid::new_v4().simple(),
+            LOCAL_CLIENT_HOST
+        )),
+    }
+}
+
+/// Pairs a new client after the user approves it and returns its token.
+/// Pairing never replaces the active session of an origin, nor the spending policy that comes with it.
+async fn pair(
+    app: &AppHandle,
+    origin_header: Option<&String>,
+    params: Value,
+) -> Result<(RpcClient, Value), RpcError> {
+    let params: PairParams = parse_params(params)?;
+
+    let origin = pairing_origin(origin_header)?;
+    check_dapp_unpaired(&origin)?;
+
+    let pending_client = RpcClient {
+        origin: origin.clone(),
+        name: params.name.clone(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+
+    let approval = request_approval(
+        app,
+        &pending_client,
+        "pair",
+        json!({ "scopes": params.scopes }),
+        vec![],
+    )
+    .await?;
+
+    let scopes = approval.scopes.unwrap_or(params.scopes);
+    let session = grant_new_dapp_session(origin.clone(), scopes, approval.expires_in)?;
+    let token = store_rpc_client(&origin, &params.name)?;
+
+    Ok((
+        pending_client,
+        json!({
+            "token": token,
+            "origin": origin,
+            "scopes": session.scopes(),
+            "expires_at": session.expires_at(),
+        }),
+    ))
+}
+
+/// Main window the wallet connect commands report transaction progress to
+fn main_window(app: &AppHandle) -> Result<Window, RpcError> {
+    match app.get_webview_window("main") {
+        Some(window) => Ok(window.as_ref().window()),
+        None => Err(RpcError::new(
+            RpcError::INTERNAL_ERROR,
+            "The wallet window is not open",
+        )),
+    }
+}
+
+/// Parses the params of a request the user has to approve, then asks for the approval
+async fn approved_params<T: DeserializeOwned>(
+    app: &AppHandle,
+    client: &RpcClient,
+    method: &str,
+    params: Value,
+) -> Result<T, RpcError> {
+    let request = parse_params(params.clone())?;
+    request_approval(app, client, method, params, vec![]).await?;
+
+    Ok(request)
+}
+
+/// Records, decrypted records, transaction history and signatures need approval in the app every time
+async fn dispatch(
+    app: &AppHandle,
+    client: &RpcClient,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let origin = Some(client.origin.clone());
+
+    match method {
+        "get_balance" => to_result(get_balance(parse_params(params)?, origin)),
+        "get_mapping_value" => to_result(get_mapping_value(parse_params(params)?, origin)),
+        "get_mapping_keys" => to_result(get_mapping_keys(parse_params(params)?, origin)),
+        "get_records" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(get_records(request, origin).await)
+        }
+        "decrypt_records" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(decrypt_records(request, origin))
+        }
+        "get_events" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(get_events(request, origin).await)
+        }
+        "get_event" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(get_event(request, origin))
+        }
+        "sign" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(sign(request, origin, main_window(app)?))
+        }
+        "sign_typed_data" => {
+            let request = approved_params(app, client, method, params).await?;
+            to_result(sign_typed_data(request, origin, main_window(app)?))
+        }
+        "request_create_event" => {
+            let CreateEventParams {
+                request,
+                fee_private,
+            } = parse_params(params.clone())?;
+
+            // requests within the spending policies go through without asking
+            let password = match check_spending_policy(origin.clone(), request.clone())? {
+                PolicyDecision::AutoApprove => None,
+                _ => {
+                    let explanation = explain_for_approval(&request, fee_private);
+                    request_approval(app, client, method, params, vec![explanation])
+                        .await?
+                        .password
+                }
+            };
+
+            to_result(
+                request_create_event(request, fee_private, origin, password, main_window(app)?)
+                    .await,
+            )
+        }
+        "request_create_events_batch" => {
+            let CreateEventsBatchParams {
+                request,
+                fee_private,
+            } = parse_params(params.clone())?;
+
+            let password =
+                match check_batch_spending_policy(origin.clone(), request.steps().clone())? {
+                    PolicyDecision::AutoApprove => None,
+                    _ => {
+                        let explanations = request
+                            .steps()
+                            .iter()
+                            .map(|step| explain_for_approval(step, fee_private))
+                            .collect::<Vec<_>>();
+                        request_approval(app, client, method, params, explanations)
+                            .await?
+                            .password
+                    }
+                };
+
+            to_result(
+                request_create_events_batch(
+                    request,
+                    fee_private,
+                    origin,
+                    password,
+                    main_window(app)?,
+                )
+                .await,
+            )
+        }
+        _ => Err(RpcError::new(
+            RpcError::METHOD_NOT_FOUND,
+            &format!("Unknown method {}", method),
+        )),
+    }
+}
+
+/// Resolves the client a token belongs to, a browser origin has to match the paired origin
+fn authenticate(
+    token: Option<&String>,
+    origin_header: Option<&String>,
+) -> Result<Option<RpcClient>, RpcError> {
+    let token = match token {
+        Some(token) => token,
+        None => return Ok(None),
+    };
+
+    let client = match get_rpc_client_by_token(token)? {
+        Some(client) => client,
+        None => {
+            return Err(RpcError::new(
+                RpcError::UNAUTHORIZED,
+                "Unknown token, pair with the wallet again",
+            ))
+        }
+    };
+
+    if let Some(origin) = origin_header {
+        if normalize_origin(origin)? != client.origin {
+            return Err(RpcError::new(
+                RpcError::UNAUTHORIZED,
+                "The token was issued to another origin",
+            ));
+        }
+    }
+
+    Ok(Some(client))
+}
+
+/// Handles one JSON-RPC request, a successful `pair` authenticates the connection
+async fn handle_rpc(
+    app: &AppHandle,
+    client: &mut Option<RpcClient>,
+    origin_header: Option<&String>,
+    request: RpcRequest,
+) -> RpcResponse {
+    let result = match (request.method.as_str(), client.as_ref()) {
+        ("pair", _) => match pair(app, origin_header, request.params).await {
+            Ok((paired, result)) => {
+                *client = Some(paired);
+                Ok(result)
+            }
+            Err(error) => Err(error),
+        },
+        (method, Some(client)) => dispatch(app, client, method, request.params).await,
+        (_, None) => Err(RpcError::new(
+            RpcError::UNAUTHORIZED,
+            "Pair with the wallet first",
+        )),
+    };
+
+    RpcResponse::new(request.id, result)
+}
+
+fn parse_rpc_request(body: &[u8]) -> Result<RpcRequest, RpcResponse> {
+    serde_json::from_slice::<RpcRequest>(body).map_err(|e| {
+        RpcResponse::new(
+            Value::Null,
+            Err(RpcError::new(
+                RpcError::PARSE_ERROR,
+                &format!("Invalid request: {}", e),
+            )),
+        )
+    })
+}
+
+/* --Transport-- */
+
+#[derive(Debug, Clone, PartialEq)]
+struct HttpHead {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    /// Length of the head including the blank line
+    length: usize,
+}
+
+impl HttpHead {
+    fn header(&self, name: &str) -> Option<&String> {
+        self.headers.get(name)
+    }
+
+    fn is_websocket(&self) -> bool {
+        self.header("upgrade")
+            .map(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+            .unwrap_or(false)
+    }
+
+    /// Token from the `Authorization: Bearer` header or, as browsers cannot set headers on websockets, the `token` query
+    fn token(&self) -> Option<String> {
+        if let Some(token) = self
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+        {
+            return Some(token.trim().to_string());
+        }
+
+        let url = Url::parse(&format!("http://localhost{}", self.path)).ok()?;
+        url.query_pairs()
+            .find(|(key, _)| key == "token")
+            .map(|(_, value)| value.into_owned())
+    }
+
+    fn content_length(&self) -> usize {
+        self.header("content-length")
+            .and_then(|length| length.parse::<usize>().ok())
+            .unwrap_or(0)
+    }
+}
+
+fn parse_http_head(bytes: &[u8]) -> Option<HttpHead> {
+    let length = bytes.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let text = std::str::from_utf8(&bytes[..length]).ok()?;
+
+    let mut lines = text.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let headers = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+
+    Some(HttpHead {
+        method,
+        path,
+        headers,
+        length,
+    })
+}
+
+fn connection_error(message: &str) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Network,
+        message.to_string(),
+        "Invalid rpc request".to_string(),
+    )
+}
+
+/// Reads the request head without consuming it, so websocket upgrades can still be handed to the websocket handshake
+async fn peek_http_head(stream: &TcpStream) -> AvailResult<HttpHead> {
+    let mut buffer = vec![0u8; MAX_HEAD_SIZE];
+    let started = Instant::now();
+
+    loop {
+        let read = stream.peek(&mut buffer).await?;
+        if read == 0 {
+            return Err(connection_error("Connection closed before the request head"));
+        }
+
+        if let Some(head) = parse_http_head(&buffer[..read]) {
+            return Ok(head);
+        }
+
+        if read == buffer.len() || started.elapsed() > HEAD_TIMEOUT {
+            return Err(connection_error("Request head is too large or incomplete"));
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Reads `length` bytes, a client that stops sending is dropped after `timeout`
+async fn read_exact_within<R: AsyncRead + Unpin>(
+    stream: &mut R,
+    length: usize,
+    timeout: Duration,
+) -> AvailResult<Vec<u8>> {
+    let mut bytes = vec![0u8; length];
+
+    match tokio::time::timeout(timeout, stream.read_exact(&mut bytes)).await {
+        Ok(read) => {
+            read?;
+            Ok(bytes)
+        }
+        Err(_) => Err(connection_error("Timed out reading the request")),
+    }
+}
+
+/// Browsers may only read responses of origins with a paired client, a new browser client pairs over the websocket
+fn cors_origin(origin: Option<&String>, allowed: &[String]) -> Option<String> {
+    let origin = normalize_origin(origin?).ok()?;
+
+    match allowed.contains(&origin) {
+        true => Some(origin),
+        false => None,
+    }
+}
+
+fn http_response(status: &str, origin: Option<&String>, body: &str) -> String {
+    let cors = match origin {
+        Some(origin) => format!(
+            "Access-Control-Allow-Origin: {}\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type, Authorization\r\nVary: Origin\r\n",
+            origin
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        cors,
+        body
+    )
+}
+
+async fn handle_http(mut stream: TcpStream, head: HttpHead, app: AppHandle) -> AvailResult<()> {
+    // the head was peeked already, it only has to be consumed
+    read_exact_within(&mut stream, head.length, HEAD_TIMEOUT).await?;
+
+    let origin = head.header("origin").cloned();
+    let allowed_origin = cors_origin(
+        origin.as_ref(),
+        &get_rpc_client_origins().unwrap_or_default(),
+    );
+
+    let response = match head.method.as_str() {
+        "OPTIONS" => http_response("204 No Content", allowed_origin.as_ref(), ""),
+        "POST" if head.content_length() > MAX_BODY_SIZE => {
+            http_response("413 Payload Too Large", allowed_origin.as_ref(), "")
+        }
+        "POST" => {
+            let body = read_exact_within(&mut stream, head.content_length(), BODY_TIMEOUT).await?;
+
+            let response = match parse_rpc_request(&body) {
+                Ok(request) => {
+                    let mut client = authenticate(head.token().as_ref(), origin.as_ref());
+                    match client.as_mut() {
+                        Ok(client) => handle_rpc(&app, client, origin.as_ref(), request).await,
+                        Err(error) => RpcResponse::new(request.id, Err(error.clone())),
+                    }
+                }
+                Err(response) => response,
+            };
+
+            http_response(
+                "200 OK",
+                allowed_origin.as_ref(),
+                &serde_json::to_string(&response)?,
+            )
+        }
+        _ => http_response("405 Method Not Allowed", allowed_origin.as_ref(), ""),
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// What a websocket subscription delivers, clients only see the transactions they requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Topic {
+    /// Ids of the client's events whose state changed
+    TxStateChange,
+    /// Typed state changes of the client's transactions, of one event or of all of them
+    TxState(Option<String>),
+}
+
+/// Reads the params of `subscribe` and `unsubscribe`, returning the subscription key and its topic
+fn subscription_params(params: &Value) -> Result<(String, Topic), RpcError> {
+    let event = params
+        .get("event")
+        .and_then(|event| event.as_str())
+        .unwrap_or_default();
+
+    if event == TX_STATE_TOPIC {
+        let event_id = params
+            .get("event_id")
+            .and_then(|event_id| event_id.as_str())
+            .map(|event_id| event_id.to_string());
+
+        let key = match &event_id {
+            Some(event_id) => format!("{}:{}", TX_STATE_TOPIC, event_id),
+            None => TX_STATE_TOPIC.to_string(),
+        };
+
+        return Ok((key, Topic::TxState(event_id)));
+    }
+
+    match event == TX_STATE_CHANGE_TOPIC {
+        true => Ok((event.to_string(), Topic::TxStateChange)),
+        false => Err(RpcError::new(
+            RpcError::INVALID_PARAMS,
+            &format!(
+                "Events that can be subscribed to: {}, {}",
+                TX_STATE_TOPIC, TX_STATE_CHANGE_TOPIC
+            ),
+        )),
+    }
+}
+
+fn notification(event: &str, payload: Value) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "event",
+        "params": { "event": event, "payload": payload },
+    })
+    .to_string()
+}
+
+/// Notification of a state change for a subscriber at `origin`, changes of other origins are not delivered
+fn topic_notification(
+    topic: &Topic,
+    origin: &str,
+    payload: &TxStateChangePayload,
+) -> Option<String> {
+    if payload.origin.as_deref() != Some(origin) {
+        return None;
+    }
+
+    match topic {
+        Topic::TxStateChange => Some(notification(
+            TX_STATE_CHANGE_TOPIC,
+            Value::String(payload.event_id.clone()),
+        )),
+        Topic::TxState(_) => Some(notification(
+            TX_STATE_TOPIC,
+            serde_json::to_value(payload).unwrap_or(Value::Null),
+        )),
+    }
+}
+
+/// Subscriptions of one websocket connection to the transaction event bus, notifications are queued on `outgoing`
+struct WsSubscriptions {
+    outgoing: mpsc::UnboundedSender<String>,
+    active: HashMap<String, u64>,
+}
+
+impl WsSubscriptions {
+    fn subscribe(&mut self, client: &RpcClient, params: &Value) -> Result<Value, RpcError> {
+        let (key, topic) = subscription_params(params)?;
+        if self.active.contains_key(&key) {
+            return Ok(Value::Bool(true));
+        }
+
+        let (id, mut receiver) = TX_EVENTS.subscribe(match &topic {
+            Topic::TxState(Some(event_id)) => TxSubscription::Event(event_id.clone()),
+            _ => TxSubscription::Origin(client.origin.clone()),
+        })?;
+
+        let outgoing = self.outgoing.clone();
+        let origin = client.origin.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(payload) = receiver.recv().await {
+                if let Some(message) = topic_notification(&topic, &origin, &payload) {
+                    if outgoing.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        self.active.insert(key, id);
+
+        Ok(Value::Bool(true))
+    }
+
+    fn unsubscribe(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let (key, _) = subscription_params(params)?;
+        if let Some(id) = self.active.remove(&key) {
+            let _ = TX_EVENTS.unsubscribe(id);
+        }
+
+        Ok(Value::Bool(true))
+    }
+
+    fn clear(&mut self) {
+        for (_, id) in std::mem::take(&mut self.active) {
+            let _ = TX_EVENTS.unsubscribe(id);
+        }
+    }
+}
+
+/// Serves JSON-RPC over a websocket. Requests of a paired client run concurrently, up to [`MAX_CONCURRENT_REQUESTS`],
+/// so notifications keep arriving while a request waits for approval or confirmation.
+async fn handle_websocket(stream: TcpStream, head: HttpHead, app: AppHandle) -> AvailResult<()> {
+    let origin = head.header("origin").cloned();
+    let mut client = authenticate(head.token().as_ref(), origin.as_ref()).ok().flatten();
+
+    let handshake = tokio_tungstenite::accept_async(stream);
+    let mut socket = match tokio::time::timeout(HEAD_TIMEOUT, handshake).await {
+        Ok(Ok(socket)) => socket,
+        Ok(Err(e)) => {
+            return Err(AvailError::new(
+                AvailErrorType::Network,
+                format!("Websocket handshake failed: {}", e),
+                "Invalid rpc request".to_string(),
+            ))
+        }
+        Err(_) => return Err(connection_error("Timed out during the websocket handshake")),
+    };
+
+    let (outgoing, mut outgoing_receiver) = mpsc::unbounded_channel::<String>();
+    let mut subscriptions = WsSubscriptions {
+        outgoing: outgoing.clone(),
+        active: HashMap::new(),
+    };
+    let in_flight = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+
+    loop {
+        tokio::select! {
+            message = socket.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let request = match parse_rpc_request(text.as_bytes()) {
+                    Ok(request) => request,
+                    Err(response) => {
+                        let _ = outgoing.send(serde_json::to_string(&response)?);
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let response = match (method.as_str(), client.clone()) {
+                    ("pair", _) => handle_rpc(&app, &mut client, origin.as_ref(), request).await,
+                    (_, None) => RpcResponse::new(
+                        request.id,
+                        Err(RpcError::new(RpcError::UNAUTHORIZED, "Pair with the wallet first")),
+                    ),
+                    ("subscribe", Some(paired)) => {
+                        RpcResponse::new(request.id, subscriptions.subscribe(&paired, &request.params))
+                    }
+                    ("unsubscribe", Some(_)) => {
+                        RpcResponse::new(request.id, subscriptions.unsubscribe(&request.params))
+                    }
+                    (_, Some(paired)) => match in_flight.clone().try_acquire_owned() {
+                        Ok(permit) => {
+                            let app = app.clone();
+                            let outgoing = outgoing.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let result = dispatch(&app, &paired, &request.method, request.params).await;
+                                if let Ok(response) = serde_json::to_string(&RpcResponse::new(request.id, result)) {
+                                    let _ = outgoing.send(response);
+                                }
+                                drop(permit);
+                            });
+                            continue;
+                        }
+                        Err(_) => RpcResponse::new(
+                            request.id,
+                            Err(RpcError::new(
+                                RpcError::LIMIT_EXCEEDED,
+                                "Too many requests in progress, wait for a response first",
+                            )),
+                        ),
+                    },
+                };
+
+                let _ = outgoing.send(serde_json::to_string(&response)?);
+            }
+            Some(text) = outgoing_receiver.recv() => {
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    subscriptions.clear();
+
+    Ok(())
+}
+
+async fn handle_connection(stream: TcpStream, app: AppHandle) -> AvailResult<()> {
+    let head = peek_http_head(&stream).await?;
+
+    match head.is_websocket() {
+        true => handle_websocket(stream, head, app).await,
+        false => handle_http(stream, head, app).await,
+    }
+}
+
+/* --Server Lifecycle-- */
+
+struct RpcServerHandle {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+}
+
+static RPC_SERVER: Lazy<Mutex<Option<RpcServerHandle>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RpcServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+}
+
+fn lock_error(e: impl std::fmt::Display) -> AvailError {
+    AvailError::new(
+        AvailErrorType::Internal,
+        format!("Error locking rpc server state: {}", e),
+        "Error managing the rpc server".to_string(),
+    )
+}
+
+fn stop_server() -> AvailResult<()> {
+    let handle = RPC_SERVER.lock().map_err(lock_error)?.take();
+    if let Some(handle) = handle {
+        let _ = handle.shutdown.send(true);
+    }
+
+    Ok(())
+}
+
+/// Stops the server and the requests it is serving, pending approvals are rejected.
+/// The saved port is kept, so the server starts again with the app.
+pub fn stop_rpc_tasks() -> AvailResult<()> {
+    stop_server()?;
+
+    if let Ok(mut pending) = PENDING_APPROVALS.lock() {
+        pending.clear();
+    }
+
+    Ok(())
+}
+
+/// Binds the server to localhost only, replacing a running server
+async fn start_server(app: AppHandle, port: u16) -> AvailResult<()> {
+    stop_server()?;
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            return Err(AvailError::new(
+                AvailErrorType::Network,
+                format!("Error binding rpc server to port {}: {}", port, e),
+                format!("Port {} is not available", port),
+            ))
+        }
+    };
+
+    let (shutdown, mut shutdown_receiver) = watch::channel(false);
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown_receiver.changed() => break,
+                connection = listener.accept() => {
+                    let (stream, permit) = match (connection, connections.clone().try_acquire_owned()) {
+                        (Ok((stream, _)), Ok(permit)) => (stream, permit),
+                        _ => continue,
+                    };
+
+                    let app = app.clone();
+                    let mut connection_shutdown = shutdown_receiver.clone();
+                    tauri::async_runtime::spawn(async move {
+                        // connections end with the server, a request doesn't outlive its profile
+                        tokio::select! {
+                            _ = connection_shutdown.changed() => {}
+                            result = handle_connection(stream, app) => {
+                                if let Err(e) = result {
+                                    println!("Rpc connection error: {:?}", e.internal_msg);
+                                }
+                            }
+                        }
+                        drop(permit);
+                    });
+                }
+            }
+        }
+    });
+
+    *RPC_SERVER.lock().map_err(lock_error)? = Some(RpcServerHandle { port, shutdown });
+
+    Ok(())
+}
+
+/// Starts the server on launch when the user enabled it earlier
+pub fn start_saved_rpc_server(app: AppHandle) {
+    if let Ok(Some(port)) = get_rpc_server_port() {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_server(app, port).await {
+                println!("Error starting rpc server: {:?}", e.internal_msg);
+            }
+        });
+    }
+}
+
+/// Enables the localhost rpc server, it keeps starting with the app until stopped
+#[tauri::command(rename_all = "snake_case")]
+pub async fn start_rpc_server(port: Option<u16>, app: AppHandle) -> AvailResult<RpcServerStatus> {
+    let port = port.unwrap_or(DEFAULT_RPC_PORT);
+
+    start_server(app, port).await?;
+    update_rpc_server_port(Some(port))?;
+
+    get_rpc_server_status()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn stop_rpc_server() -> AvailResult<()> {
+    stop_server()?;
+    update_rpc_server_port(None)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_rpc_server_status() -> AvailResult<RpcServerStatus> {
+    let port = RPC_SERVER
+        .lock()
+        .map_err(lock_error)?
+        .as_ref()
+        .map(|handle| handle.port);
+
+    Ok(RpcServerStatus {
+        running: port.is_some(),
+        port,
+    })
+}
+
+#[cfg(test)]
+mod rpc_server_tests {
+    use super::*;
+    use avail_common::models::encrypted_data::TransactionState;
+
+    #[test]
+    fn test_parse_http_head() {
+        let request = b"POST /?token=abc HTTP/1.1\r\nHost: 127.0.0.1:6310\r\nOrigin: https://app.example\r\nContent-Length: 12\r\n\r\n{\"id\":1}";
+        let head = parse_http_head(request).unwrap();
+
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/?token=abc");
+        assert_eq!(head.header("origin").unwrap(), "https://app.example");
+        assert_eq!(head.content_length(), 12);
+        assert_eq!(head.length, request.len() - 8);
+        assert_eq!(head.token().unwrap(), "abc");
+        assert!(!head.is_websocket());
+
+        assert!(parse_http_head(b"POST / HTTP/1.1\r\nHost: 127.0.0.1").is_none());
+    }
+
+    #[test]
+    fn test_websocket_head() {
+        let head = parse_http_head(
+            b"GET / HTTP/1.1\r\nUpgrade: WebSocket\r\nAuthorization: Bearer xyz\r\n\r\n",
+        )
+        .unwrap();
+
+        assert!(head.is_websocket());
+        assert_eq!(head.token().unwrap(), "xyz");
+    }
+
+    #[test]
+    fn test_rpc_response() {
+        let request = parse_rpc_request(br#"{"jsonrpc":"2.0","id":7,"method":"get_balance"}"#).unwrap();
+        assert_eq!(request.method, "get_balance");
+        assert_eq!(request.params, Value::Null);
+
+        let response = RpcResponse::new(
+            request.id,
+            Err(RpcError::new(RpcError::METHOD_NOT_FOUND, "Unknown method")),
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"jsonrpc":"2.0","id":7,"error":{"code":-32601,"message":"Unknown method"}})
+        );
+
+        assert!(parse_rpc_request(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_subscription_params() {
+        assert_eq!(
+            subscription_params(&json!({"event":"tx_state_change"})).unwrap(),
+            ("tx_state_change".to_string(), Topic::TxStateChange)
+        );
+        assert_eq!(
+            subscription_params(&json!({"event":"tx_state"})).unwrap(),
+            ("tx_state".to_string(), Topic::TxState(None))
+        );
+        assert_eq!(
+            subscription_params(&json!({"event":"tx_state","event_id":"abc"})).unwrap(),
+            (
+                "tx_state:abc".to_string(),
+                Topic::TxState(Some("abc".to_string()))
+            )
+        );
+        assert!(subscription_params(&json!({"event":"reauthenticate"})).is_err());
+        assert!(subscription_params(&json!({"event":"scan_progress"})).is_err());
+    }
+
+    #[test]
+    fn test_notifications_by_origin() {
+        let payload = TxStateChangePayload {
+            event_id: "event".to_string(),
+            old_state: None,
+            new_state: TransactionState::Pending,
+            transaction_id: None,
+            height: None,
+            error: None,
+            origin: Some("https://app.example".to_string()),
+        };
+
+        let notification =
+            topic_notification(&Topic::TxStateChange, "https://app.example", &payload).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&notification).unwrap()["params"]["payload"],
+            json!("event")
+        );
+        assert!(
+            topic_notification(&Topic::TxState(None), "https://app.example", &payload).is_some()
+        );
+
+        // other dapps and the wallet's own transactions are not delivered
+        assert!(
+            topic_notification(&Topic::TxStateChange, "https://other.example", &payload).is_none()
+        );
+        let wallet_payload = TxStateChangePayload {
+            origin: None,
+            ..payload
+        };
+        assert!(topic_notification(
+            &Topic::TxState(None),
+            "https://app.example",
+            &wallet_payload
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_pairing_origin() {
+        let browser = "https://App.Example/swap".to_string();
+        assert_eq!(
+            pairing_origin(Some(&browser)).unwrap(),
+            "https://app.example"
+        );
+        assert!(pairing_origin(Some(&"not a url".to_string())).is_err());
+
+        // clients that are not browsers can't choose their origin
+        let issued = pairing_origin(None).unwrap();
+        assert!(issued.ends_with(".rpc.localhost"));
+        assert_eq!(normalize_origin(&issued).unwrap(), issued);
+        assert_ne!(issued, pairing_origin(None).unwrap());
+
+        let params: PairParams = parse_params(
+            json!({"name": "bot", "origin": "https://dex.example", "scopes": [{"type": "read_balance"}]}),
+        )
+        .unwrap();
+        assert_eq!(params.scopes, vec![DappScope::ReadBalance]);
+    }
+
+    #[test]
+    fn test_cors_allowlist() {
+        let allowed = vec!["https://app.example".to_string()];
+
+        assert_eq!(
+            cors_origin(Some(&"https://app.example".to_string()), &allowed),
+            Some("https://app.example".to_string())
+        );
+        assert_eq!(
+            cors_origin(Some(&"https://evil.example".to_string()), &allowed),
+            None
+        );
+        assert_eq!(cors_origin(None, &allowed), None);
+
+        let response = http_response("200 OK", None, "{}");
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+        let response = http_response("200 OK", allowed.first(), "{}");
+        assert!(response.contains("Access-Control-Allow-Origin: https://app.example\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_all(b"{\"id\":1}").await.unwrap();
+
+        let body = read_exact_within(&mut server, 8, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert_eq!(body, b"{\"id\":1}");
+
+        // a client announcing more than it sends is dropped
+        client.write_all(b"{").await.unwrap();
+        assert!(read_exact_within(&mut server, 8, Duration::from_millis(50))
+            .await
+            .is_err());
+    }
+}
@@ -1,8 +1,13 @@
 use super::{
+    account::signer::{get_signer, Signer},
     local_storage::{
+        accounts::{in_account, request_account, with_account, with_account_async},
+        dapp_sessions::{active_dapp_session, authorize_dapp, normalize_origin, requesting_dapp},
         encrypted_data::update_encrypted_transaction_state_by_id,
-        persistent_storage::{get_address, get_address_string, get_network},
-        session::{password::PASS, view::VIEWSESSION},
+        persistent_storage::{get_address, get_network},
+        reputation::{find_blocked, request_targets},
+        session::{password::PASS, policy::POLICY_PASS, view::VIEWSESSION},
+        spending_policy::{reserve_batch, reserve_deployment, reserve_request, PolicyReservation},
         storage_api::{
             event::{
                 get_avail_event_raw, get_avail_events_raw, get_event_raw, get_events_raw,
@@ -12,11 +17,17 @@ use super::{
                 get_page_count_for_filter, get_record_pointers, update_record_spent_local,
                 update_record_spent_local_via_nonce,
             },
+            transaction::get_transaction_pointer,
         },
-        utils::{get_private_key, sign_message},
+        token_metadata::program_decimals,
     },
     record_handling::{
+        deployment::{has_deployment_balance, parse_deployment_inputs, prepare_deployment},
+        mapping::{get_mapping_keys_raw, get_mapping_value_raw},
+        program_abi::validate_request_inputs,
+        prover::execute_with_prover,
         records::find_aleo_credits_record_to_spend,
+        tx_events::{publish_tx_state, TX_EVENTS},
         utils::{
             get_token_balance, handle_deployment_update_and_encrypted_storage,
             handle_encrypted_storage_and_message, handle_transaction_update_and_encrypted_storage,
@@ -26,24 +37,35 @@ use super::{
 };
 use crate::api::aleo_client::setup_client;
 use crate::api::aleo_client::setup_local_client;
-use crate::models::event::{AvailEvent, SuccinctAvailEvent};
+use crate::models::event::{AvailEvent, Event, SuccinctAvailEvent};
 use crate::models::pointers::{deployment::DeploymentPointer, transaction::TransactionPointer};
 use crate::models::wallet_connect::{
     balance::{BalanceRequest, BalanceResponse},
-    create_event::{CreateEventRequest, CreateEventResponse},
+    create_event::{
+        BatchCreateEventRequest, BatchCreateEventResponse, CreateEventRequest, CreateEventResponse,
+    },
     decrypt::{DecryptRequest, DecryptResponse},
     get_event::{GetEventRequest, GetEventResponse, GetEventsRequest, GetEventsResponse},
-    records::{GetRecordsRequest, GetRecordsResponse, RecordWithPlaintext},
+    mapping::{MappingKeysRequest, MappingKeysResponse, MappingValueRequest, MappingValueResponse},
+    policy::PolicyDecision,
+    records::{
+        GetRecordsRequest, GetRecordsResponse, RecordFilterType, RecordWithPlaintext,
+        RecordsFilter,
+    },
+    session::DappScope,
     sign::{SignatureRequest, SignatureResponse},
+    typed_sign::{SigningDomain, TypedSignatureRequest, TYPED_DATA_PREFIX},
 };
-use chrono::Local;
+use chrono::{Local, Utc};
 use std::str::FromStr;
 use tauri::Emitter;
 
 use snarkvm::circuit::Aleo;
 use snarkvm::{
     circuit::{AleoTestnetV0, Environment},
-    prelude::{Address, Ciphertext, Field, Network, Program, Record, Signature, TestnetV0},
+    prelude::{
+        Address, Ciphertext, Field, Network, Plaintext, Record, Signature, TestnetV0, Value,
+    },
 };
 
 use tauri::{Manager, Window};
@@ -59,61 +81,226 @@ use avail_common::{
 };
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn get_balance(request: BalanceRequest) -> AvailResult<BalanceResponse> {
-    let network = get_network()?;
-    println!(
-        "===> Asset ID in Request Backend {:?}",
-        Some(request.asset_id())
-    );
-    //TODO - Read ARC20 to deduce assets id something like {program_id/record_name} seems reasonable.
-    let asset_id = match request.asset_id() {
-        Some(asset_id) => asset_id,
-        None => "credits".to_string(),
-    };
-    println!("===> Asset ID in Backend {:?}", asset_id);
-    //TODO - V2 HD wallet support
-    let _address = match request.address() {
-        Some(address) => address.to_string(),
-        None => get_address_string()?,
+pub fn get_balance(
+    request: BalanceRequest,
+    origin: Option<String>,
+) -> AvailResult<BalanceResponse> {
+    let address = request.address();
+
+    // a request for another registered account acts as it, the active account stays as it is
+    with_account(address.as_ref(), || {
+        if let Err(error) = authorize_dapp(
+            origin.as_ref(),
+            &DappScope::ReadBalance,
+            request.address().as_ref(),
+        ) {
+            return Ok(BalanceResponse::new(vec![], Some(error.external_msg)));
+        }
+
+        let network = get_network()?;
+        println!(
+            "===> Asset ID in Request Backend {:?}",
+            Some(request.asset_id())
+        );
+        //TODO - Read ARC20 to deduce assets id something like {program_id/record_name} seems reasonable.
+        let asset_id = match request.asset_id() {
+            Some(asset_id) => asset_id,
+            None => "credits".to_string(),
+        };
+        println!("===> Asset ID in Backend {:?}", asset_id);
+
+        let balance = match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => get_token_balance::<TestnetV0>(&asset_id)?,
+            _ => get_token_balance::<TestnetV0>(&asset_id)?, //SupportedNetworks::Mainnet => get_aleo_balance::<Mainnet>()?,
+        };
+
+        Ok(BalanceResponse::new(vec![balance], None))
+    })
+}
+
+/// Reads public program state, any connected dapp may read it
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_mapping_value(
+    request: MappingValueRequest,
+    origin: Option<String>,
+) -> AvailResult<MappingValueResponse> {
+    if let Err(error) = active_dapp_session(origin.as_ref(), None) {
+        return Ok(MappingValueResponse::new(None, Some(error.external_msg)));
+    }
+
+    let value = match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => get_mapping_value_raw::<TestnetV0>(&request),
+        _ => get_mapping_value_raw::<TestnetV0>(&request),
     };
 
-    let balance = match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => get_token_balance::<TestnetV0>(&asset_id)?,
-        _ => get_token_balance::<TestnetV0>(&asset_id)?, //SupportedNetworks::Mainnet => get_aleo_balance::<Mainnet>()?,
+    match value {
+        Ok(value) => Ok(MappingValueResponse::new(value, None)),
+        Err(error) => Ok(MappingValueResponse::new(None, Some(error.external_msg))),
+    }
+}
+
+/// Lists the keys of a mapping where the node supports it
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_mapping_keys(
+    request: MappingKeysRequest,
+    origin: Option<String>,
+) -> AvailResult<MappingKeysResponse> {
+    if let Err(error) = active_dapp_session(origin.as_ref(), None) {
+        return Ok(MappingKeysResponse::new(vec![], Some(error.external_msg)));
+    }
+
+    let keys = match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => get_mapping_keys_raw::<TestnetV0>(&request),
+        _ => get_mapping_keys_raw::<TestnetV0>(&request),
     };
 
-    Ok(BalanceResponse::new(vec![balance], None))
+    match keys {
+        Ok(keys) => Ok(MappingKeysResponse::new(keys, None)),
+        Err(error) => Ok(MappingKeysResponse::new(vec![], Some(error.external_msg))),
+    }
 }
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn request_create_event(
     request: CreateEventRequest,
     fee_private: bool,
+    origin: Option<String>,
+    password: Option<String>,
     window: Window,
 ) -> AvailResult<CreateEventResponse> {
-    let network = get_network()?;
-    match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => {
-            request_create_event_raw::<TestnetV0, AleoTestnetV0>(request, fee_private, Some(window))
-                .await
+    let address = request.address().cloned();
+
+    with_account_async(address.as_ref(), async move {
+        if let Err(error) = authorize_dapp(
+            origin.as_ref(),
+            &DappScope::Execute(vec![request.program_id().clone()]),
+            request.address(),
+        ) {
+            return Ok(CreateEventResponse::new(None, Some(error.external_msg)));
         }
-        _ => {
-            request_create_event_raw::<TestnetV0, AleoTestnetV0>(request, fee_private, Some(window))
+
+        let origin = requesting_dapp(origin);
+        let network = get_network()?;
+        match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => {
+                request_create_event_raw::<TestnetV0, AleoTestnetV0>(
+                    request,
+                    fee_private,
+                    origin,
+                    password,
+                    Some(window),
+                )
                 .await
-        } //SupportedNetworks::Mainnet => request_create_event_raw::<Mainnet>(request),
-    }
+            }
+            _ => {
+                request_create_event_raw::<TestnetV0, AleoTestnetV0>(
+                    request,
+                    fee_private,
+                    origin,
+                    password,
+                    Some(window),
+                )
+                .await
+            } //SupportedNetworks::Mainnet => request_create_event_raw::<Mainnet>(request),
+        }
+    })
+    .await
 }
 
 pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network = N>>(
     request: CreateEventRequest,
     fee_private: bool,
+    origin: Option<String>,
+    password: Option<String>,
     window: Option<Window>,
 ) -> AvailResult<CreateEventResponse> {
-    let api_client = setup_client::<N>()?;
-    let private_key = match get_private_key::<N>(None) {
-        Ok(private_key) => {
+    // bad inputs are reported per argument here instead of failing while proving
+    let request = match request.event_type() {
+        EventTypeCommon::Deploy => request,
+        _ => match validate_request_inputs::<N>(&request) {
+            Ok(request) => request,
+            Err(e) => return Ok(CreateEventResponse::new(None, Some(e.external_msg))),
+        },
+    };
+
+    // known phishing programs and addresses are refused before anything is signed
+    if let Some(entry) = find_blocked(&request_targets::<N>(&request))? {
+        return Ok(CreateEventResponse::blocked(entry));
+    }
+
+    // deployments are prepared first, so the spending policy is checked against their total cost
+    let deployment = match request.event_type() {
+        EventTypeCommon::Deploy => {
+            match parse_deployment_inputs::<N>(request.inputs()).and_then(
+                |(program, local_imports)| {
+                    // dapp scopes are checked against the requested program id
+                    if &program.id().to_string() != request.program_id() {
+                        return Err(AvailError::new(
+                            AvailErrorType::Validation,
+                            format!(
+                                "Program {} doesn't match the requested program id {}",
+                                program.id(),
+                                request.program_id()
+                            ),
+                            "Program doesn't match the requested program id".to_string(),
+                        ));
+                    }
+
+                    prepare_deployment::<N, A>(
+                        program,
+                        &local_imports,
+                        (request.fee() * 1000000.0) as u64,
+                    )
+                },
+            ) {
+                Ok(deployment) => Some(deployment),
+                Err(e) => return Ok(CreateEventResponse::new(None, Some(e.external_msg))),
+            }
+        }
+        _ => None,
+    };
+
+    // the spending is reserved until the request is broadcast, so concurrent requests can't pass the same limit
+    let (decision, reservation) = match &deployment {
+        Some(deployment) => reserve_deployment(
+            origin.as_ref(),
+            request.program_id(),
+            deployment.total_cost() as f64 / 1000000.0,
+        )?,
+        None => reserve_request::<N>(origin.as_ref(), &request)?,
+    };
+
+    // requests outside a spending policy are only signed with the user's password
+    let password = match decision {
+        PolicyDecision::Reauthenticate(reason) if password.is_none() => {
+            if let Some(window) = window {
+                match window.emit("reauthenticate", "create-event") {
+                    Ok(_) => {}
+                    Err(_) => {
+                        return Err(AvailError::new(
+                            AvailErrorType::Internal,
+                            "Error emitting reauthentication event".to_string(),
+                            "Error emitting reauthentication state".to_string(),
+                        ));
+                    }
+                };
+            }
+
+            return Ok(CreateEventResponse::new(
+                None,
+                Some(format!("{}, please reauthenticate.", reason)),
+            ));
+        }
+        // auto-approved requests are signed without the password session
+        PolicyDecision::AutoApprove if password.is_none() => POLICY_PASS.get_instance().ok(),
+        _ => password,
+    };
+
+    let signer = match get_signer::<N>(password).and_then(|signer| signer.unlock().map(|_| signer))
+    {
+        Ok(signer) => {
             PASS.extend_session()?;
-            private_key
+            signer
         }
         Err(e) => match e.error_type {
             AvailErrorType::Unauthorized => {
@@ -145,62 +332,63 @@ pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network
     };
 
     let address = get_address::<N>()?;
-    let fee = (request.fee() * 1000000.0) as u64;
-
-    let mut program_manager =
-        ProgramManager::<N>::new(Some(private_key), None, Some(api_client), None)?;
 
     let mut fee_record_nonce: Option<String> = None;
 
-    if request.event_type() == &EventTypeCommon::Deploy {
-        let program = match request.inputs().get(0) {
-            Some(program) => program,
-            None => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Program not found".to_string(),
-                    "Program not found".to_string(),
-                ))
-            }
-        };
+    if let Some(deployment) = deployment {
+        // deployments are built by the program manager, which needs the key itself
+        let program_manager = signer.private_key().and_then(|private_key| {
+            let api_client = setup_client::<N>()?;
+            Ok(ProgramManager::<N>::new(
+                Some(private_key),
+                None,
+                Some(api_client),
+                None,
+            )?)
+        });
 
-        let program = match Program::<N>::from_str(program) {
-            Ok(program) => program,
-            Err(_) => {
-                return Err(AvailError::new(
-                    AvailErrorType::Internal,
-                    "Program string parsing failure".to_string(),
-                    "Program string parsing failure".to_string(),
-                ))
-            }
+        let mut program_manager = match program_manager {
+            Ok(program_manager) => program_manager,
+            Err(e) => return Ok(CreateEventResponse::new(None, Some(e.external_msg))),
         };
 
-        program_manager.add_program(&program)?;
-
-        // TODO - Check if dapps take all costs into account for deployment
-        //let (minimum_deployment_cost, (_storage_cost, _namespace_cost)) =
-        // program_manager.estimate_deployment_fee::<A>(&program, &private_key)?;
+        if let Err(e) = deployment.load_into(&mut program_manager) {
+            return Ok(CreateEventResponse::new(None, Some(e.external_msg)));
+        }
 
-        //let mut fee = minimum_deployment_cost;
-        //fee += request.fee();
+        let program = deployment.program.clone();
+        let total_cost = deployment.total_cost();
+        let total_cost_credits = total_cost as f64 / 1000000.0;
 
         let (fee_record, _fee_commitment, fee_id) = match fee_private {
-            true => {
-                let (fee_record, fee_commitment, fee_id) =
-                    find_aleo_credits_record_to_spend::<N>(&fee, vec![])?;
+            true => match find_aleo_credits_record_to_spend::<N>(&total_cost, vec![]) {
+                Ok((fee_record, fee_commitment, fee_id)) => {
+                    let fee_nonce = fee_record.nonce().to_string();
+                    fee_record_nonce = Some(fee_nonce);
 
-                let fee_nonce = fee_record.nonce().to_string();
-                fee_record_nonce = Some(fee_nonce);
+                    (Some(fee_record), Some(fee_commitment), Some(fee_id))
+                }
+                Err(e) => return Ok(CreateEventResponse::new(None, Some(e.external_msg))),
+            },
+            false => {
+                if !has_deployment_balance::<N>(total_cost, false)? {
+                    return Ok(CreateEventResponse::new(
+                        None,
+                        Some(format!(
+                            "Insufficient public balance, the deployment costs {} credits",
+                            total_cost_credits
+                        )),
+                    ));
+                }
 
-                (Some(fee_record), Some(fee_commitment), Some(fee_id))
+                (None, None, None)
             }
-            false => (None, None, None),
         };
 
         let mut pending_deployment_tx = DeploymentPointer::<N>::new(
             None,
             request.program_id().clone(),
-            request.fee(),
+            total_cost_credits,
             TransactionState::Processing,
             None,
             fee_record_nonce,
@@ -211,25 +399,29 @@ pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network
 
         let pending_event_id = pending_deployment_tx.encrypt_and_store(address)?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &pending_event_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
+        if let Some(origin) = &origin {
+            TX_EVENTS.set_origin(&pending_event_id, &normalize_origin(origin)?)?;
         }
 
+        publish_tx_state(
+            window.as_ref(),
+            &pending_event_id,
+            TransactionState::Processing,
+            None,
+            None,
+            None,
+        )?;
+
         if let Some(fee_id) = fee_id.clone() {
             update_record_spent_local::<N>(&fee_id, true)?;
         }
 
-        let transaction_id = match program_manager.deploy_program(program.id(), 0, fee_record, None)
-        {
+        let transaction_id = match program_manager.deploy_program(
+            program.id(),
+            deployment.priority_fee,
+            fee_record,
+            None,
+        ) {
             Ok(tx_id) => tx_id,
             Err(_) => {
                 if let Some(fee_id) = fee_id {
@@ -250,18 +442,14 @@ pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network
                     TransactionState::Failed,
                 )?;
 
-                if let Some(window) = window.clone() {
-                    match window.emit("tx_state_change", &pending_event_id) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            return Err(AvailError::new(
-                                AvailErrorType::Internal,
-                                "Error emitting tx_state_change event".to_string(),
-                                "Error emitting transaction state".to_string(),
-                            ));
-                        }
-                    };
-                }
+                publish_tx_state(
+                    window.as_ref(),
+                    &pending_event_id,
+                    TransactionState::Failed,
+                    None,
+                    None,
+                    Some("Deployment failed, no records were spent.".to_string()),
+                )?;
 
                 return Ok(CreateEventResponse::new(
                     Some(pending_event_id),
@@ -270,6 +458,8 @@ pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network
             }
         };
 
+        reservation.commit()?;
+
         handle_deployment_update_and_encrypted_storage::<N>(
             transaction_id,
             &pending_event_id,
@@ -280,181 +470,654 @@ pub async fn request_create_event_raw<N: Network, A: Aleo + Environment<Network
 
         Ok(CreateEventResponse::new(Some(pending_event_id), None))
     } else {
-        let mut record_nonces: Vec<String> = vec![];
+        let mut reserved_nonces: Vec<String> = vec![];
+        let plan = plan_execution::<N>(&request, fee_private, &mut reserved_nonces)?;
+
+        let (pending_event_id, transaction_id, recipient_address, fee_id) =
+            match broadcast_execution::<N>(
+                &request,
+                plan,
+                reservation,
+                signer.as_ref(),
+                origin.as_ref(),
+                window.as_ref(),
+            )? {
+                Broadcast::Sent {
+                    event_id,
+                    transaction_id,
+                    recipient_address,
+                    fee_id,
+                } => (event_id, transaction_id, recipient_address, fee_id),
+                Broadcast::Failed(response) => return Ok(response),
+            };
 
-        let (input_values, input_nonces, recipient_address, amount) =
-            parse_inputs::<N>(request.inputs().clone(), &request.function_id().clone())?;
+        confirm_execution::<N>(
+            transaction_id,
+            &pending_event_id,
+            recipient_address,
+            fee_id,
+            window,
+        )
+        .await?;
 
-        let (fee_record, _fee_commitment, fee_id) = match fee_private {
-            true => {
-                let (fee_record, fee_commitment, fee_id) =
-                    find_aleo_credits_record_to_spend::<N>(&fee, input_nonces.clone())?;
+        Ok(CreateEventResponse::new(Some(pending_event_id), None))
+    }
+}
 
-                let fee_nonce = fee_record.nonce().to_string();
-                record_nonces.push(fee_nonce);
+/// Parsed inputs and chosen fee record of an execution, planned before anything is executed
+pub struct ExecutionPlan<N: Network> {
+    input_values: Vec<Value<N>>,
+    input_nonces: Vec<String>,
+    recipient_address: Option<Address<N>>,
+    amount: Option<f64>,
+    fee: u64,
+    fee_record: Option<Record<N, Plaintext<N>>>,
+    fee_id: Option<String>,
+}
 
-                (Some(fee_record), Some(fee_commitment), Some(fee_id))
+/// Parses an execution request and picks its fee record.
+/// Nonces in `reserved_nonces` are not selected again and the nonces this execution spends are added to it.
+pub fn plan_execution<N: Network>(
+    request: &CreateEventRequest,
+    fee_private: bool,
+    reserved_nonces: &mut Vec<String>,
+) -> AvailResult<ExecutionPlan<N>> {
+    let fee = (request.fee() * 1000000.0) as u64;
+
+    let (input_values, input_nonces, recipient_address, amount) = parse_inputs::<N>(
+        request.inputs().clone(),
+        request.function_id(),
+        program_decimals(request.program_id()),
+    )?;
+
+    if let Some(nonce) = input_nonces
+        .iter()
+        .find(|nonce| reserved_nonces.contains(nonce))
+    {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Record with nonce {} is spent twice", nonce),
+            "A record is used by more than one execution".to_string(),
+        ));
+    }
+    reserved_nonces.extend(input_nonces.clone());
+
+    let (fee_record, fee_id) = match fee_private {
+        true => {
+            let (fee_record, _fee_commitment, fee_id) =
+                find_aleo_credits_record_to_spend::<N>(&fee, reserved_nonces.clone())?;
+            reserved_nonces.push(fee_record.nonce().to_string());
+
+            (Some(fee_record), Some(fee_id))
+        }
+        false => (None, None),
+    };
+
+    Ok(ExecutionPlan {
+        input_values,
+        input_nonces,
+        recipient_address,
+        amount,
+        fee,
+        fee_record,
+        fee_id,
+    })
+}
+
+/// Outcome of proving and broadcasting a planned execution
+pub enum Broadcast<N: Network> {
+    Sent {
+        event_id: String,
+        transaction_id: N::TransactionID,
+        recipient_address: Option<Address<N>>,
+        fee_id: Option<String>,
+    },
+    /// Nothing was spent, the response carries the failed event and the error
+    Failed(CreateEventResponse),
+}
+
+/// Stores the pending event of a planned execution, then proves and broadcasts it
+fn broadcast_execution<N: Network>(
+    request: &CreateEventRequest,
+    plan: ExecutionPlan<N>,
+    reservation: PolicyReservation,
+    signer: &dyn Signer<N>,
+    origin: Option<&String>,
+    window: Option<&Window>,
+) -> AvailResult<Broadcast<N>> {
+    let address = get_address::<N>()?;
+
+    let mut record_nonces: Vec<String> = vec![];
+    if let Some(fee_record) = &plan.fee_record {
+        record_nonces.push(fee_record.nonce().to_string());
+    }
+    record_nonces.extend(plan.input_nonces.clone());
+
+    let mut pending_transaction = TransactionPointer::<N>::new(
+        None,
+        None,
+        TransactionState::Processing,
+        None,
+        Some(request.program_id().clone()),
+        Some(request.function_id().clone()),
+        vec![],
+        record_nonces,
+        Local::now(),
+        None,
+        None,
+        request.event_type().to_owned(),
+        plan.amount,
+        Some(request.fee()),
+        None,
+    );
+
+    let pending_event_id = pending_transaction.encrypt_and_store(address)?;
+
+    if let Some(origin) = origin {
+        TX_EVENTS.set_origin(&pending_event_id, &normalize_origin(origin)?)?;
+    }
+
+    publish_tx_state(
+        window,
+        &pending_event_id,
+        TransactionState::Processing,
+        None,
+        None,
+        None,
+    )?;
+
+    // the fee record and the inputs are marked spent until the execution fails
+    for nonce in plan.input_nonces.clone() {
+        update_record_spent_local_via_nonce::<N>(&nonce, true)?;
+    }
+
+    if let Some(fee_id) = plan.fee_id.clone() {
+        update_record_spent_local::<N>(&fee_id, true)?;
+    }
+
+    let transaction_id = match execute_with_prover::<N>(
+        signer,
+        request.program_id(),
+        request.function_id(),
+        plan.input_values,
+        plan.fee,
+        plan.fee_record,
+    ) {
+        Ok(tx_id) => tx_id,
+        Err(_) => {
+            if let Some(fee_id) = plan.fee_id {
+                update_record_spent_local::<N>(&fee_id, false)?;
             }
-            false => (None, None, None),
-        };
 
-        record_nonces.extend(input_nonces.clone());
+            for nonce in plan.input_nonces {
+                update_record_spent_local_via_nonce::<N>(&nonce, false)?;
+            }
 
-        let mut pending_transaction = TransactionPointer::<N>::new(
-            None,
-            None,
-            TransactionState::Processing,
-            None,
-            Some(request.program_id().clone()),
-            Some(request.function_id().clone()),
-            vec![],
-            record_nonces,
-            Local::now(),
-            None,
-            None,
-            request.event_type().to_owned(),
-            amount,
-            Some(request.fee()),
-            None,
-        );
+            pending_transaction.update_failed_transaction(
+                "Transaction execution failed, no records were spent.".to_string(),
+                None,
+            );
 
-        let pending_event_id = pending_transaction.encrypt_and_store(address)?;
+            let encrypted_failed_transaction = pending_transaction.to_encrypted_data(address)?;
 
-        if let Some(window) = window.clone() {
-            match window.emit("tx_state_change", &pending_event_id) {
-                Ok(_) => {}
-                Err(e) => {
-                    return Err(AvailError::new(
-                        AvailErrorType::Internal,
-                        "Error emitting tx_state_change event".to_string(),
-                        "Error emitting transaction state".to_string(),
-                    ));
-                }
-            };
+            update_encrypted_transaction_state_by_id(
+                &pending_event_id,
+                &encrypted_failed_transaction.ciphertext,
+                &encrypted_failed_transaction.nonce,
+                TransactionState::Failed,
+            )?;
+
+            publish_tx_state(
+                window,
+                &pending_event_id,
+                TransactionState::Failed,
+                None,
+                None,
+                Some("Transaction execution failed, no records were spent.".to_string()),
+            )?;
+
+            return Ok(Broadcast::Failed(CreateEventResponse::new(
+                Some(pending_event_id),
+                Some(format!(
+                    "Error executing program: '{}' function: '{}' ",
+                    request.program_id(),
+                    request.function_id()
+                )),
+            )));
         }
+    };
 
-        // TODO - Update fee to spent and input_nonces to spent
-        for nonce in input_nonces.clone() {
-            update_record_spent_local_via_nonce::<N>(&nonce, true)?;
+    reservation.commit()?;
+
+    Ok(Broadcast::Sent {
+        event_id: pending_event_id,
+        transaction_id,
+        recipient_address: plan.recipient_address,
+        fee_id: plan.fee_id,
+    })
+}
+
+/// Waits for a broadcast execution to be confirmed, rejected or aborted and updates its event
+async fn confirm_execution<N: Network>(
+    transaction_id: N::TransactionID,
+    event_id: &str,
+    recipient_address: Option<Address<N>>,
+    fee_id: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<()> {
+    match recipient_address {
+        Some(recipient_address) => {
+            handle_encrypted_storage_and_message::<N>(
+                transaction_id,
+                recipient_address,
+                event_id,
+                None,
+                fee_id,
+                true,
+                window,
+            )
+            .await
+        }
+        None => {
+            handle_transaction_update_and_encrypted_storage::<N>(
+                transaction_id,
+                event_id,
+                fee_id,
+                window,
+            )
+            .await
         }
+    }
+}
 
-        if let Some(fee_id) = fee_id.clone() {
-            update_record_spent_local::<N>(&fee_id, false)?;
+/// Limit on the steps of a batch, each step is proven on this device
+const MAX_BATCH_STEPS: usize = 16;
+
+/// Executes an ordered list of dapp requests after a single approval
+#[tauri::command(rename_all = "snake_case")]
+pub async fn request_create_events_batch(
+    request: BatchCreateEventRequest,
+    fee_private: bool,
+    origin: Option<String>,
+    password: Option<String>,
+    window: Window,
+) -> AvailResult<BatchCreateEventResponse> {
+    // a batch acts as a single account
+    let address = request
+        .steps()
+        .first()
+        .and_then(|step| step.address().cloned());
+    if request
+        .steps()
+        .iter()
+        .any(|step| step.address() != address.as_ref())
+    {
+        return Ok(BatchCreateEventResponse::new(
+            vec![],
+            Some("All steps of a batch have to be for the same account".to_string()),
+        ));
+    }
+
+    with_account_async(address.as_ref(), async move {
+        for step in request.steps() {
+            if let Err(error) = authorize_dapp(
+                origin.as_ref(),
+                &DappScope::Execute(vec![step.program_id().clone()]),
+                step.address(),
+            ) {
+                return Ok(BatchCreateEventResponse::new(
+                    vec![],
+                    Some(error.external_msg),
+                ));
+            }
         }
 
-        let request_clone = request.clone();
+        let origin = requesting_dapp(origin);
+        let network = get_network()?;
+        match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => {
+                request_create_events_batch_raw::<TestnetV0>(
+                    request,
+                    fee_private,
+                    origin,
+                    password,
+                    Some(window),
+                )
+                .await
+            }
+            _ => {
+                request_create_events_batch_raw::<TestnetV0>(
+                    request,
+                    fee_private,
+                    origin,
+                    password,
+                    Some(window),
+                )
+                .await
+            }
+        }
+    })
+    .await
+}
 
-        println!("=====> INPUTS {:?}", input_values);
-        let transaction_id = match program_manager.execute_program(
-            request.program_id().clone(),
-            request.function_id().clone(),
-            input_values.iter(),
-            0,
-            fee_record,
-            None,
-        ) {
-            Ok(tx_id) => tx_id,
-            Err(_) => {
-                if let Some(fee_id) = fee_id {
-                    update_record_spent_local::<N>(&fee_id, false)?;
-                }
+/// Plans the records of every step before anything is executed, so no record is selected twice
+/// and a batch that can't be funded fails without side effects.
+/// Steps are broadcast in order, once a step fails the remaining steps are not executed.
+pub async fn request_create_events_batch_raw<N: Network>(
+    request: BatchCreateEventRequest,
+    fee_private: bool,
+    origin: Option<String>,
+    password: Option<String>,
+    window: Option<Window>,
+) -> AvailResult<BatchCreateEventResponse> {
+    let steps = request.steps();
 
-                for nonce in input_nonces {
-                    update_record_spent_local_via_nonce::<N>(&nonce, false)?;
-                }
+    if steps.is_empty() || steps.len() > MAX_BATCH_STEPS {
+        return Ok(BatchCreateEventResponse::new(
+            vec![],
+            Some(format!(
+                "A batch has to contain between 1 and {} steps",
+                MAX_BATCH_STEPS
+            )),
+        ));
+    }
 
-                pending_transaction.update_failed_transaction(
-                    "Transaction execution failed, no records were spent.".to_string(),
-                    None,
-                );
+    if steps
+        .iter()
+        .any(|step| step.event_type() == &EventTypeCommon::Deploy)
+    {
+        return Ok(BatchCreateEventResponse::new(
+            vec![],
+            Some("Deployments can't be part of a batch".to_string()),
+        ));
+    }
 
-                let encrypted_failed_transaction =
-                    pending_transaction.to_encrypted_data(address)?;
+    let mut validated_steps = vec![];
+    for (index, step) in steps.iter().enumerate() {
+        match validate_request_inputs::<N>(step) {
+            Ok(step) => validated_steps.push(step),
+            Err(e) => {
+                return Ok(BatchCreateEventResponse::new(
+                    vec![],
+                    Some(format!("Step {}: {}", index + 1, e.external_msg)),
+                ))
+            }
+        }
+    }
+    let steps = &validated_steps;
 
-                update_encrypted_transaction_state_by_id(
-                    &pending_event_id,
-                    &encrypted_failed_transaction.ciphertext,
-                    &encrypted_failed_transaction.nonce,
-                    TransactionState::Failed,
-                )?;
+    for (index, step) in steps.iter().enumerate() {
+        if let Some(entry) = find_blocked(&request_targets::<N>(step))? {
+            return Ok(BatchCreateEventResponse::blocked(index, entry));
+        }
+    }
 
-                if let Some(window) = window.clone() {
-                    match window.emit("tx_state_change", &pending_event_id) {
-                        Ok(_) => {}
-                        Err(e) => {
+    let (decision, reservations) = reserve_batch::<N>(origin.as_ref(), steps)?;
+    let password = match decision {
+        PolicyDecision::Reauthenticate(reason) if password.is_none() => {
+            if let Some(window) = &window {
+                if window.emit("reauthenticate", "create-event").is_err() {
+                    return Err(AvailError::new(
+                        AvailErrorType::Internal,
+                        "Error emitting reauthentication event".to_string(),
+                        "Error emitting reauthentication state".to_string(),
+                    ));
+                }
+            }
+
+            return Ok(BatchCreateEventResponse::new(
+                vec![],
+                Some(format!("{}, please reauthenticate.", reason)),
+            ));
+        }
+        PolicyDecision::AutoApprove if password.is_none() => POLICY_PASS.get_instance().ok(),
+        _ => password,
+    };
+
+    let signer = match get_signer::<N>(password).and_then(|signer| signer.unlock().map(|_| signer))
+    {
+        Ok(signer) => {
+            PASS.extend_session()?;
+            signer
+        }
+        Err(e) => {
+            let error = match e.error_type {
+                AvailErrorType::Unauthorized => {
+                    if let Some(window) = &window {
+                        if window.emit("reauthenticate", "create-event").is_err() {
                             return Err(AvailError::new(
                                 AvailErrorType::Internal,
-                                "Error emitting tx_state_change event".to_string(),
-                                "Error emitting transaction state".to_string(),
+                                "Error emitting reauthentication event".to_string(),
+                                "Error emitting reauthentication state".to_string(),
                             ));
                         }
-                    };
+                    }
+
+                    "Unauthorized, please reauthenticate."
                 }
+                _ => "Error signing the event creation.",
+            };
 
-                return Ok(CreateEventResponse::new(
-                    Some(pending_event_id),
+            return Ok(BatchCreateEventResponse::new(
+                vec![],
+                Some(error.to_string()),
+            ));
+        }
+    };
+
+    let mut reserved_nonces: Vec<String> = vec![];
+    let mut plans: Vec<ExecutionPlan<N>> = vec![];
+    for (index, step) in steps.iter().enumerate() {
+        match plan_execution::<N>(step, fee_private, &mut reserved_nonces) {
+            Ok(plan) => plans.push(plan),
+            Err(e) => {
+                return Ok(BatchCreateEventResponse::new(
+                    vec![],
                     Some(format!(
-                        "Error executing program: '{}' function: '{}' ",
-                        request.program_id(),
-                        request.function_id()
+                        "Step {} can't be executed, nothing was executed: {}",
+                        index + 1,
+                        e.external_msg
                     )),
-                ));
+                ))
+            }
+        }
+    }
+
+    let mut responses: Vec<CreateEventResponse> = vec![];
+    let mut error: Option<String> = None;
+
+    for (index, ((step, plan), reservation)) in
+        steps.iter().zip(plans).zip(reservations).enumerate()
+    {
+        if error.is_some() {
+            responses.push(CreateEventResponse::new(
+                None,
+                Some("Not executed, an earlier step failed.".to_string()),
+            ));
+            continue;
+        }
+
+        // errors are reported per step, so the events of steps broadcast before are still returned
+        let (event_id, transaction_id, recipient_address, fee_id) = match broadcast_execution::<N>(
+            step,
+            plan,
+            reservation,
+            signer.as_ref(),
+            origin.as_ref(),
+            window.as_ref(),
+        ) {
+            Ok(Broadcast::Sent {
+                event_id,
+                transaction_id,
+                recipient_address,
+                fee_id,
+            }) => (event_id, transaction_id, recipient_address, fee_id),
+            Ok(Broadcast::Failed(response)) => {
+                error = Some(format!("Step {} failed", index + 1));
+                responses.push(response);
+                continue;
+            }
+            Err(e) => {
+                error = Some(format!("Step {} failed", index + 1));
+                responses.push(CreateEventResponse::new(None, Some(e.external_msg)));
+                continue;
             }
         };
 
-        match recipient_address {
-            Some(recipient_address) => {
-                handle_encrypted_storage_and_message::<N>(
+        if request.parallel() {
+            let window = window.clone();
+            let pending_event_id = event_id.clone();
+            // the follow up keeps acting as the account of the batch
+            tokio::spawn(in_account(request_account(), async move {
+                // a step that can't be followed up keeps its pending event
+                let _ = confirm_execution::<N>(
                     transaction_id,
-                    recipient_address,
                     &pending_event_id,
-                    None,
+                    recipient_address,
                     fee_id,
-                    true,
                     window,
                 )
-                .await?
+                .await;
+            }));
+
+            responses.push(CreateEventResponse::new(Some(event_id), None));
+            continue;
+        }
+
+        let confirmed = confirm_execution::<N>(
+            transaction_id,
+            &event_id,
+            recipient_address,
+            fee_id,
+            window.clone(),
+        )
+        .await
+        .and_then(|_| get_transaction_pointer::<N>(&event_id));
+
+        let pointer = match confirmed {
+            Ok(pointer) => pointer,
+            Err(e) => {
+                error = Some(format!("Step {} could not be confirmed", index + 1));
+                responses.push(CreateEventResponse::new(
+                    Some(event_id),
+                    Some(e.external_msg),
+                ));
+                continue;
             }
-            None => {
-                handle_transaction_update_and_encrypted_storage::<N>(
-                    transaction_id,
-                    &pending_event_id,
-                    fee_id,
-                    window,
-                )
-                .await?
+        };
+
+        // later steps may rely on the state this step changes, so they wait for it to be confirmed
+        match pointer.state() {
+            TransactionState::Confirmed => {
+                responses.push(CreateEventResponse::new(Some(event_id), None))
+            }
+            state => {
+                error = Some(format!("Step {} was not confirmed", index + 1));
+                responses.push(CreateEventResponse::new(
+                    Some(event_id),
+                    Some(
+                        pointer
+                            .error()
+                            .unwrap_or(format!("Transaction ended as {:?}", state)),
+                    ),
+                ));
             }
         }
-
-        Ok(CreateEventResponse::new(Some(pending_event_id), None))
     }
+
+    Ok(BatchCreateEventResponse::new(responses, error))
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_records(request: GetRecordsRequest) -> AvailResult<GetRecordsResponse> {
-    let network = get_network()?;
-    match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => match get_records_raw::<TestnetV0>(request) {
-            Ok((records, page_count)) => {
-                Ok(GetRecordsResponse::new(records, Some(page_count), None))
-            }
-            Err(error) => Ok(GetRecordsResponse::new(
-                vec![],
-                None,
-                Some(error.external_msg),
-            )),
-        },
-        _ => match get_records_raw::<TestnetV0>(request) {
-            Ok((records, page_count)) => {
-                Ok(GetRecordsResponse::new(records, Some(page_count), None))
+pub async fn get_records(
+    request: GetRecordsRequest,
+    origin: Option<String>,
+) -> AvailResult<GetRecordsResponse> {
+    let address = request.address().clone();
+
+    with_account_async(address.as_ref(), async move {
+        let request = match restrict_records_request(origin.as_ref(), request) {
+            Ok(request) => request,
+            Err(error) => {
+                return Ok(GetRecordsResponse::new(
+                    vec![],
+                    None,
+                    Some(error.external_msg),
+                ))
             }
-            Err(error) => Ok(GetRecordsResponse::new(
-                vec![],
-                None,
-                Some(error.external_msg),
-            )),
-        }, //SupportedNetworks::Mainnet => get_records_raw::<Mainnet>(request),
+        };
+
+        let network = get_network()?;
+        match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => match get_records_raw::<TestnetV0>(request) {
+                Ok((records, page_count)) => {
+                    Ok(GetRecordsResponse::new(records, Some(page_count), None))
+                }
+                Err(error) => Ok(GetRecordsResponse::new(
+                    vec![],
+                    None,
+                    Some(error.external_msg),
+                )),
+            },
+            _ => match get_records_raw::<TestnetV0>(request) {
+                Ok((records, page_count)) => {
+                    Ok(GetRecordsResponse::new(records, Some(page_count), None))
+                }
+                Err(error) => Ok(GetRecordsResponse::new(
+                    vec![],
+                    None,
+                    Some(error.external_msg),
+                )),
+            }, //SupportedNetworks::Mainnet => get_records_raw::<Mainnet>(request),
+        }
+    })
+    .await
+}
+
+/// Limits a dapp's records request to the programs it may read, requests without programs get all of them
+fn restrict_records_request(
+    origin: Option<&String>,
+    request: GetRecordsRequest,
+) -> AvailResult<GetRecordsRequest> {
+    let session = match active_dapp_session(origin, request.address().as_ref())? {
+        Some(session) => session,
+        None => return Ok(request),
+    };
+
+    let requested = match request.filter() {
+        Some(filter) => filter.program_ids().clone(),
+        None => vec![],
+    };
+
+    if !requested.is_empty() {
+        authorize_dapp(origin, &DappScope::ReadRecords(requested), None)?;
+        return Ok(request);
     }
+
+    let readable = session.readable_programs();
+    if readable.is_empty() {
+        return Err(AvailError::new(
+            AvailErrorType::Unauthorized,
+            format!("Dapp {} can not read any records", session.origin()),
+            "The dapp does not have permission for this request".to_string(),
+        ));
+    }
+
+    let filter = match request.filter() {
+        Some(filter) => RecordsFilter::new(
+            readable,
+            filter.function_id().clone(),
+            RecordFilterType::from_string(filter.record_type()).clone(),
+            filter.record_name().clone(),
+        ),
+        None => RecordsFilter::new(readable, None, RecordFilterType::All, None),
+    };
+
+    Ok(GetRecordsRequest::new(
+        request.address().clone(),
+        Some(filter),
+        *request.page(),
+    ))
 }
 
 pub fn get_records_raw<N: Network>(
@@ -477,12 +1140,67 @@ pub fn get_records_raw<N: Network>(
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn sign(request: SignatureRequest, window: Window) -> AvailResult<SignatureResponse> {
-    let network = get_network()?;
+pub fn sign(
+    request: SignatureRequest,
+    origin: Option<String>,
+    window: Window,
+) -> AvailResult<SignatureResponse> {
+    let address = request.get_address();
+
+    with_account(address.as_ref(), || {
+        if let Err(error) = authorize_dapp(
+            origin.as_ref(),
+            &DappScope::Sign,
+            request.get_address().as_ref(),
+        ) {
+            return Ok(SignatureResponse::new(None, None, Some(error.external_msg)));
+        }
 
-    match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => {
-            match sign_message::<TestnetV0>(&request.get_message(), None) {
+        // typed messages are only signed through sign_typed_data, where their domain is checked
+        if request.get_message().starts_with(TYPED_DATA_PREFIX) {
+            return Ok(SignatureResponse::new(
+                None,
+                None,
+                Some("Typed data has to be signed with sign_typed_data".to_string()),
+            ));
+        }
+
+        let network = get_network()?;
+
+        match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => {
+                match get_signer::<TestnetV0>(None)
+                    .and_then(|signer| signer.sign_message(&request.get_message()))
+                {
+                    Ok((signature, message_field)) => Ok(SignatureResponse::new(
+                        Some(signature.to_string()),
+                        Some(message_field.to_string()),
+                        None,
+                    )),
+                    Err(e) => {
+                        if e.error_type == AvailErrorType::Unauthorized {
+                            match window.emit("reauthenticate", "sign") {
+                                Ok(_) => {}
+                                Err(e) => {
+                                    return Err(AvailError::new(
+                                        AvailErrorType::Internal,
+                                        "Error emitting reauthentication event".to_string(),
+                                        "Error emitting reauthentication state".to_string(),
+                                    ));
+                                }
+                            };
+                        }
+                        Ok(SignatureResponse::new(
+                            None,
+                            None,
+                            Some("Signing Failed".to_string()),
+                        ))
+                    }
+                }
+            }
+            _ => match get_signer::<TestnetV0>(None)
+                .and_then(|signer| signer.sign_message(&request.get_message()))
+            {
                 Ok((signature, message_field)) => Ok(SignatureResponse::new(
                     Some(signature.to_string()),
                     Some(message_field.to_string()),
@@ -507,19 +1225,125 @@ pub fn sign(request: SignatureRequest, window: Window) -> AvailResult<SignatureR
                         Some("Signing Failed".to_string()),
                     ))
                 }
-            }
+            },
+            //SupportedNetworks::Mainnet => decrypt_record_raw::<Mainnet>(ciphertext),
         }
-        _ => match sign_message::<TestnetV0>(&request.get_message(), None) {
-            Ok((signature, message_field)) => Ok(SignatureResponse::new(
-                Some(signature.to_string()),
-                Some(message_field.to_string()),
-                None,
-            )),
+    })
+}
+
+/// Checks that a signing domain is for the wallet's network and chain
+fn check_domain_network<N: Network>(domain: &SigningDomain) -> AvailResult<()> {
+    if domain.network != get_network()? || domain.chain_id != N::ID {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!(
+                "Signing domain is for network {} with chain id {}",
+                domain.network, domain.chain_id
+            ),
+            "The signing domain is for another network".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a signing domain belongs to the requesting dapp and the current network, and has not expired.
+/// Typed data is only signed for a dapp, requests that don't name one are refused.
+fn check_signing_domain<N: Network>(
+    domain: &SigningDomain,
+    origin: Option<&String>,
+) -> AvailResult<()> {
+    let origin = match origin {
+        Some(origin) => origin,
+        None => {
+            return Err(AvailError::new(
+                AvailErrorType::Unauthorized,
+                format!("Signature for {} requested without a dapp", domain.origin),
+                "The signing request did not name the dapp it came from".to_string(),
+            ))
+        }
+    };
+
+    if normalize_origin(&domain.origin)? != normalize_origin(origin)? {
+        return Err(AvailError::new(
+            AvailErrorType::Unauthorized,
+            format!("Dapp {} requested a signature for {}", origin, domain.origin),
+            "The signing domain belongs to another dapp".to_string(),
+        ));
+    }
+
+    check_domain_network::<N>(domain)?;
+
+    if domain.nonce.is_empty() {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            "Signing domain has no nonce".to_string(),
+            "The signing domain needs a nonce".to_string(),
+        ));
+    }
+
+    if domain.is_expired(Utc::now().timestamp()) {
+        return Err(AvailError::new(
+            AvailErrorType::Validation,
+            format!("Signing domain expired at {}", domain.expires_at),
+            "The signing request has expired".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn sign_typed_data_raw<N: Network>(
+    request: &TypedSignatureRequest,
+    origin: Option<&String>,
+) -> AvailResult<SignatureResponse> {
+    let encoded = match check_signing_domain::<N>(&request.domain, origin)
+        .and_then(|_| request.encode::<N>())
+    {
+        Ok(encoded) => encoded,
+        Err(error) => return Ok(SignatureResponse::new(None, None, Some(error.external_msg))),
+    };
+
+    let (signature, message_field) = get_signer::<N>(None)?.sign_message(&encoded)?;
+
+    Ok(SignatureResponse::new(
+        Some(signature.to_string()),
+        Some(message_field.to_string()),
+        None,
+    ))
+}
+
+/// Signs a typed message bound to a domain, so the user signs values they can read instead of an opaque string
+#[tauri::command(rename_all = "snake_case")]
+pub fn sign_typed_data(
+    request: TypedSignatureRequest,
+    origin: Option<String>,
+    window: Window,
+) -> AvailResult<SignatureResponse> {
+    let address = request.address.clone();
+
+    with_account(address.as_ref(), || {
+        if let Err(error) =
+            authorize_dapp(origin.as_ref(), &DappScope::Sign, request.address.as_ref())
+        {
+            return Ok(SignatureResponse::new(None, None, Some(error.external_msg)));
+        }
+
+        let origin = requesting_dapp(origin);
+        let response = match SupportedNetworks::from_str(&get_network()?)? {
+            SupportedNetworks::Testnet => {
+                sign_typed_data_raw::<TestnetV0>(&request, origin.as_ref())
+            }
+            _ => sign_typed_data_raw::<TestnetV0>(&request, origin.as_ref()),
+        };
+
+        match response {
+            Ok(response) => Ok(response),
             Err(e) => {
                 if e.error_type == AvailErrorType::Unauthorized {
                     match window.emit("reauthenticate", "sign") {
                         Ok(_) => {}
-                        Err(e) => {
+                        Err(_) => {
                             return Err(AvailError::new(
                                 AvailErrorType::Internal,
                                 "Error emitting reauthentication event".to_string(),
@@ -528,17 +1352,50 @@ pub fn sign(request: SignatureRequest, window: Window) -> AvailResult<SignatureR
                         }
                     };
                 }
+
                 Ok(SignatureResponse::new(
                     None,
                     None,
                     Some("Signing Failed".to_string()),
                 ))
             }
-        },
-        //SupportedNetworks::Mainnet => decrypt_record_raw::<Mainnet>(ciphertext),
+        }
+    })
+}
+
+/// Verifies a signature from [`sign_typed_data`], signatures whose domain expired are no longer valid.
+/// Rejecting a reused nonce is up to the verifier.
+#[tauri::command(rename_all = "snake_case")]
+pub fn verify_typed_data(
+    request: TypedSignatureRequest,
+    address: &str,
+    signature: &str,
+) -> AvailResult<bool> {
+    if request.domain.is_expired(Utc::now().timestamp()) {
+        return Ok(false);
+    }
+
+    match SupportedNetworks::from_str(&get_network()?)? {
+        SupportedNetworks::Testnet => {
+            verify_typed_data_raw::<TestnetV0>(&request, address, signature)
+        }
+        _ => verify_typed_data_raw::<TestnetV0>(&request, address, signature),
     }
 }
 
+/// Signatures for a domain of another network or chain are not valid on this one
+fn verify_typed_data_raw<N: Network>(
+    request: &TypedSignatureRequest,
+    address: &str,
+    signature: &str,
+) -> AvailResult<bool> {
+    if check_domain_network::<N>(&request.domain).is_err() {
+        return Ok(false);
+    }
+
+    verify_signature::<N>(&request.encode::<N>()?, address, signature)
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub fn verify(message: &str, address: &str, signature: &str) -> AvailResult<bool> {
     let network = get_network()?;
@@ -549,7 +1406,7 @@ pub fn verify(message: &str, address: &str, signature: &str) -> AvailResult<bool
     }
 }
 
-fn verify_signature<N: Network>(
+pub fn verify_signature<N: Network>(
     message: &str,
     address: &str,
     signature: &str,
@@ -567,7 +1424,14 @@ fn verify_signature<N: Network>(
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn decrypt_records(request: DecryptRequest) -> AvailResult<DecryptResponse> {
+pub fn decrypt_records(
+    request: DecryptRequest,
+    origin: Option<String>,
+) -> AvailResult<DecryptResponse> {
+    if let Err(error) = authorize_dapp(origin.as_ref(), &DappScope::Decrypt, None) {
+        return Ok(DecryptResponse::new(vec![], Some(error.external_msg)));
+    }
+
     let network = get_network()?;
     match SupportedNetworks::from_str(&network)? {
         SupportedNetworks::Testnet => match decrypt_records_raw::<TestnetV0>(request.ciphertexts) {
@@ -605,43 +1469,89 @@ pub fn decrypt_records_raw<N: Network>(ciphertext: Vec<String>) -> AvailResult<V
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_events(request: GetEventsRequest) -> AvailResult<GetEventsResponse> {
+pub async fn get_events(
+    request: GetEventsRequest,
+    origin: Option<String>,
+) -> AvailResult<GetEventsResponse> {
     let network = get_network()?;
-    match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => match get_events_raw::<TestnetV0>(request) {
-            Ok(events) => Ok(GetEventsResponse::new(events, None, None)),
-            Err(error) => Ok(GetEventsResponse::new(
-                vec![],
-                None,
-                Some(error.external_msg),
-            )),
-        },
-        _ => match get_events_raw::<TestnetV0>(request) {
-            Ok(events) => Ok(GetEventsResponse::new(events, None, None)),
-            Err(error) => Ok(GetEventsResponse::new(
-                vec![],
-                None,
-                Some(error.external_msg),
-            )),
-        },
+    let events = match SupportedNetworks::from_str(&network)? {
+        SupportedNetworks::Testnet => get_dapp_events::<TestnetV0>(origin.as_ref(), request),
+        _ => get_dapp_events::<TestnetV0>(origin.as_ref(), request),
         //SupportedNetworks::Mainnet => get_events_raw::<Mainnet>(request),
+    };
+
+    match events {
+        Ok(events) => Ok(GetEventsResponse::new(events, None, None)),
+        Err(error) => Ok(GetEventsResponse::new(
+            vec![],
+            None,
+            Some(error.external_msg),
+        )),
+    }
+}
+
+/// Events a dapp may read, events of programs outside its session are left out
+fn get_dapp_events<N: Network>(
+    origin: Option<&String>,
+    request: GetEventsRequest,
+) -> AvailResult<Vec<Event>> {
+    let session = match active_dapp_session(origin, None)? {
+        Some(session) => session,
+        None => return get_events_raw::<N>(request),
+    };
+
+    if let Some(program_id) = request.filter.as_ref().and_then(|f| f.program_id.clone()) {
+        authorize_dapp(origin, &DappScope::ReadRecords(vec![program_id]), None)?;
     }
+
+    let readable = session.readable_programs();
+    let events = get_events_raw::<N>(request)?;
+
+    Ok(events
+        .into_iter()
+        .filter(|event| match event.program_id() {
+            Some(program_id) => readable.contains(program_id),
+            None => false,
+        })
+        .collect())
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn get_event(request: GetEventRequest) -> AvailResult<GetEventResponse> {
-    let network = get_network()?;
-    match SupportedNetworks::from_str(&network)? {
-        SupportedNetworks::Testnet => match get_event_raw::<TestnetV0>(&request.id) {
-            Ok(event) => Ok(GetEventResponse::new(Some(event), None)),
-            Err(error) => Ok(GetEventResponse::new(None, Some(error.external_msg))),
-        },
-        _ => match get_event_raw::<TestnetV0>(&request.id) {
+pub fn get_event(
+    request: GetEventRequest,
+    origin: Option<String>,
+) -> AvailResult<GetEventResponse> {
+    let address = request.address.clone();
+
+    with_account(address.as_ref(), || {
+        let network = get_network()?;
+        let event = match SupportedNetworks::from_str(&network)? {
+            SupportedNetworks::Testnet => get_dapp_event::<TestnetV0>(origin.as_ref(), &request),
+            _ => get_dapp_event::<TestnetV0>(origin.as_ref(), &request),
+            //SupportedNetworks::Mainnet => get_event_raw::<Mainnet>(request),
+        };
+
+        match event {
             Ok(event) => Ok(GetEventResponse::new(Some(event), None)),
             Err(error) => Ok(GetEventResponse::new(None, Some(error.external_msg))),
-        },
-        //SupportedNetworks::Mainnet => get_event_raw::<Mainnet>(request),
+        }
+    })
+}
+
+fn get_dapp_event<N: Network>(
+    origin: Option<&String>,
+    request: &GetEventRequest,
+) -> AvailResult<Event> {
+    active_dapp_session(origin, request.address.as_ref())?;
+
+    let event = get_event_raw::<N>(&request.id)?;
+
+    if origin.is_some() {
+        let program_id = event.program_id().clone().unwrap_or_default();
+        authorize_dapp(origin, &DappScope::ReadRecords(vec![program_id]), None)?;
     }
+
+    Ok(event)
 }
 
 /* --Avail Events-- */
@@ -721,6 +1631,12 @@ mod test {
     use snarkvm::prelude::{Address, FromStr, Identifier, PrivateKey, TestnetV0, ViewKey};
 
     use crate::services::account::generation::import_wallet;
+    use crate::services::local_storage::spending_policy::{
+        remove_spending_policy, save_spending_policy,
+    };
+
+    use crate::models::wallet_connect::policy::{AllowedCall, SpendingPolicy};
+    use std::collections::HashMap;
 
     /*
     #[tokio::test]
@@ -798,7 +1714,7 @@ mod test {
             " <<<<<<<<<<<<<< Testing get_balance() fn in Wallet Connect Rust API >>>>>>>>>>>>>>>"
         );
         let request = BalanceRequest::new(Some("credits"), None);
-        let res = get_balance(request).unwrap();
+        let res = get_balance(request, None).unwrap();
         println!("res: {:?}", res);
     }
 
@@ -914,7 +1830,7 @@ mod test {
         PASS.set_pass_session(STRONG_PASSWORD).unwrap();
 
         let result_create_event =
-            request_create_event_raw::<TestnetV0, AleoTestnetV0>(request, false, None)
+            request_create_event_raw::<TestnetV0, AleoTestnetV0>(request, false, None, None, None)
                 .await
                 .unwrap();
         println!("res: {:?}", result_create_event);
@@ -945,7 +1861,7 @@ mod test {
         }
 
         let request = DecryptRequest::new(ciphertexts);
-        let res = decrypt_records(request).unwrap();
+        let res = decrypt_records(request, None).unwrap();
 
         println!("Result: {:?}", res);
     }
@@ -967,7 +1883,7 @@ mod test {
             page: None,
         };
 
-        let res = get_events(request).await.unwrap();
+        let res = get_events(request, None).await.unwrap();
 
         println!("Result: {:?}", res);
     }
@@ -988,7 +1904,7 @@ mod test {
             address: None,
         };
 
-        let res = get_event(request).unwrap();
+        let res = get_event(request, None).unwrap();
 
         println!("Result: {:?}", res);
     }
@@ -1008,6 +1924,59 @@ mod test {
         assert_eq!(res, true);
     }
 
+    #[test]
+    fn test_verify_typed_data() {
+        let pk = PrivateKey::<TestnetV0>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let address = Address::<TestnetV0>::try_from(&pk).unwrap();
+
+        let request: TypedSignatureRequest = serde_json::from_value(serde_json::json!({
+            "domain": {
+                "origin": "https://dex.example",
+                "network": get_network().unwrap(),
+                "chainId": TestnetV0::ID,
+                "nonce": "1",
+                "expiresAt": Utc::now().timestamp() + 600
+            },
+            "types": {"Login": [{"name": "account", "type": "address"}]},
+            "primaryType": "Login",
+            "message": {"account": address.to_string()},
+            "address": null
+        }))
+        .unwrap();
+
+        let encoded = request.encode::<TestnetV0>().unwrap();
+        let (signature, _) = sign_message_w_key::<TestnetV0>(&encoded, &pk).unwrap();
+
+        assert!(
+            verify_typed_data(request.clone(), &address.to_string(), &signature.to_string())
+                .unwrap()
+        );
+
+        let mut replayed = request.clone();
+        replayed.domain.nonce = "2".to_string();
+        assert!(
+            !verify_typed_data(replayed, &address.to_string(), &signature.to_string()).unwrap()
+        );
+
+        // a signature for another chain is not valid here, even when it verifies
+        let mut other_chain = request.clone();
+        other_chain.domain.chain_id = TestnetV0::ID + 1;
+        let encoded = other_chain.encode::<TestnetV0>().unwrap();
+        let (signature, _) = sign_message_w_key::<TestnetV0>(&encoded, &pk).unwrap();
+        assert!(
+            !verify_typed_data(other_chain, &address.to_string(), &signature.to_string()).unwrap()
+        );
+
+        let error = check_signing_domain::<TestnetV0>(&request.domain, None).unwrap_err();
+        assert!(error.error_type == AvailErrorType::Unauthorized);
+
+        let dex = "https://dex.example".to_string();
+        assert!(check_signing_domain::<TestnetV0>(&request.domain, Some(&dex)).is_ok());
+
+        let other = "https://other.example".to_string();
+        assert!(check_signing_domain::<TestnetV0>(&request.domain, Some(&other)).is_err());
+    }
+
     #[test]
     fn test_fee_f64() {
         let fee = 0.3;
@@ -1019,4 +1988,50 @@ mod test {
         let fee_x = (fee_x as f64) / 1000000.0;
         println!("fee_x: {:?}", fee_x);
     }
+
+    #[tokio::test]
+    async fn test_create_event_follows_dapp_policy() {
+        test_setup_prerequisites();
+
+        let origin = "https://policy.example".to_string();
+        let recipient = "aleo1dg722m22fzpz6xjdrvl9tzu5t68zmypj5p74khlqcac0gvednygqxaax0j";
+
+        save_spending_policy(SpendingPolicy::new(
+            Some(origin.clone()),
+            Some(1.0),
+            HashMap::from([("credits.aleo".to_string(), 10.0)]),
+            vec![AllowedCall {
+                program_id: "credits.aleo".to_string(),
+                function_id: Some("transfer_public".to_string()),
+            }],
+        ))
+        .unwrap();
+
+        let request = CreateEventRequest::new(
+            None,
+            EventTypeCommon::Execute,
+            "credits.aleo".to_string(),
+            "transfer_public".to_string(),
+            0.1,
+            vec![recipient.to_string(), "5000000u64".to_string()],
+        );
+
+        // the dapp's policy decides the request, a request without a dapp would prompt instead
+        let response = request_create_event_raw::<TestnetV0, AleoTestnetV0>(
+            request,
+            false,
+            requesting_dapp(Some(origin.clone())),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        remove_spending_policy(Some(origin)).unwrap();
+
+        assert_eq!(
+            response.error().map(|error| error.as_str()),
+            Some("Amount 5 is above the limit of 1 per transaction, please reauthenticate.")
+        );
+    }
 }
@@ -33,7 +33,7 @@ export async function register_seed_phrase(setErrorAlert: React.Dispatch<React.S
 	console.log('Registering seed phrase wallet');
 
 	return (invoke<string>('create_seed_phrase_wallet', {
-		username, password, access_type: authType, backup: false, language, length,
+		username, password, access_type: authType, backup: false, language, length, passphrase: null, store_passphrase: false, mnemonic_language: null,
 	}).then(response => response).catch((error: AvailError) => {
 		// TODO - Sentry should log AvailError
 		setMessage('Problem when creating account, apologies. Please try again.');
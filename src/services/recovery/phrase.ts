@@ -7,7 +7,7 @@ export async function recover(phrase: string, password: string, authType: boolea
 	return delete_local_for_recovery(password).then(() => {
 		localStorage.clear();
 		invoke<string>('recover_wallet_from_seed_phrase', {
-			seed_phrase: phrase, password, access_type: authType, language,
+			seed_phrase: phrase, password, access_type: authType, language, passphrase: null, store_passphrase: false, mnemonic_language: null,
 		}).then(response => {
 			setMessage('Wallet recovered successfully.');
 			setSuccessAlert(true);
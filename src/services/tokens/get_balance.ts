@@ -1,12 +1,14 @@
 import {invoke} from '@tauri-apps/api/core';
-import {Balance, type GetBalancesRequest, type GetBalancesResponse} from '../wallet-connect/WCTypes';
+import {
+	Balance, type GetBalancesRequest, type GetBalancesResponse, walletOrigin,
+} from '../wallet-connect/WCTypes';
 
 export async function get_balance(request: GetBalancesRequest) {
-	const res: GetBalancesResponse = await invoke('get_balance', {request});
+	const res: GetBalancesResponse = await invoke('get_balance', {request, origin: walletOrigin});
 	return res;
 }
 
 export async function get_total_balance() {
-	const res: GetBalancesResponse = await invoke('get_balance', {request: {assetId: ''}});
+	const res: GetBalancesResponse = await invoke('get_balance', {request: {assetId: ''}, origin: walletOrigin});
 	return res;
 }
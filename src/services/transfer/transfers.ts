@@ -1,7 +1,15 @@
 import {invoke} from '@tauri-apps/api/core';
 import {AvailError} from 'src/types/errors';
-import {type TransferRequest} from 'src/types/transfer_props/tokens';
+import {type TransferRequest, type TransferResponse} from 'src/types/transfer_props/tokens';
 
 export async function transfer(request: TransferRequest, setAlert: React.Dispatch<React.SetStateAction<boolean>>, setMessage: React.Dispatch<React.SetStateAction<string>>) {
-	return invoke('transfer', {request});
+	const response = await invoke<TransferResponse>('transfer', {request});
+
+	// blocked recipients and tokens are refused without raising an error
+	if (response.blocked && response.error) {
+		setMessage(response.error);
+		setAlert(true);
+	}
+
+	return response;
 }
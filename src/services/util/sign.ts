@@ -1,12 +1,12 @@
 import {invoke} from '@tauri-apps/api/core';
-import {type SignatureRequest, type SignatureResponse} from '../wallet-connect/WCTypes';
+import {type SignatureRequest, type SignatureResponse, walletOrigin} from '../wallet-connect/WCTypes';
 
 export async function sign(message: string) {
 	const request: SignatureRequest = {
 		message,
 	};
 
-	return invoke<SignatureResponse>('sign', {request});
+	return invoke<SignatureResponse>('sign', {request, origin: walletOrigin});
 }
 
 export async function verify(message: string, signature: string, address: string) {
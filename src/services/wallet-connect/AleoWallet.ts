@@ -37,6 +37,8 @@ import {
 	type GetRecordsResponse,
 	type GetBackendRecordsResponse,
 	convertGetRecordsResponse,
+	type DappScope,
+	type AvailEvent,
 } from './WCTypes';
 
 function checkWindow(reference: string) {
@@ -106,6 +108,15 @@ function getDappMetadata(session_topic: string): DAppSession | undefined {
 	}
 }
 
+/**
+ * Add a scope the user approved to the wallet session of the DApp
+ * @param dappSession The DApp session metadata
+ * @param scope The approved scope
+ */
+async function grantScope(dappSession: DAppSession | undefined, scope: DappScope) {
+	await invoke('extend_dapp_session', {origin: dappSession?.origin, scope});
+}
+
 function storeSession(unique_request_id: string) {
 	const expiry = new Date();
 	expiry.setHours(expiry.getHours() + 1);
@@ -311,7 +322,7 @@ export class AleoWallet {
 
 		if (checkNotExpired(requestIdentifier)) {
 			return new Promise((resolve, reject) => {
-				invoke<GetBalancesResponse>('get_balance', {request})
+				invoke<GetBalancesResponse>('get_balance', {request, origin: metadata?.origin})
 					.then(response => {
 						resolve(formatJsonRpcResult(requestEvent.id, response));
 					})
@@ -346,6 +357,7 @@ export class AleoWallet {
 				async onApprove() {
 					const response = await invoke<GetBalancesResponse>('get_balance', {
 						request,
+						origin: metadata?.origin,
 					});
 					return formatJsonRpcResult(requestEvent.id, response);
 				},
@@ -391,7 +403,7 @@ export class AleoWallet {
 
 		async function action(): Promise<JsonRpcResult | JsonRpcError> {
 			return new Promise((resolve, reject) => {
-				invoke<DecryptResponse>('decrypt_records', {request})
+				invoke<DecryptResponse>('decrypt_records', {request, origin: metadata?.origin})
 					.then(response => {
 						resolve(formatJsonRpcResult(requestEvent.id, response));
 					})
@@ -420,7 +432,10 @@ export class AleoWallet {
 
 		return createWalletConnectDialog(
 			{
-				onApprove: action,
+				async onApprove() {
+					await grantScope(metadata, {type: 'decrypt'});
+					return action();
+				},
 				onReject: async () =>
 					formatJsonRpcError(requestEvent.id, 'User rejected decryption'),
 				approveEventString: 'decrypt-approved',
@@ -453,7 +468,7 @@ export class AleoWallet {
 
 		async function action(): Promise<JsonRpcResult | JsonRpcError> {
 			return new Promise((resolve, reject) => {
-				invoke<SignatureResponse>('sign', {request})
+				invoke<SignatureResponse>('sign', {request, origin: metadata?.origin})
 					.then(response => {
 						resolve(formatJsonRpcResult(requestEvent.id, response));
 					})
@@ -465,7 +480,10 @@ export class AleoWallet {
 
 		return createWalletConnectDialog(
 			{
-				onApprove: action,
+				async onApprove() {
+					await grantScope(metadata, {type: 'sign'});
+					return action();
+				},
 				onReject: async () =>
 					formatJsonRpcError(requestEvent.id, 'User rejected signature'),
 				approveEventString: 'sign-approved',
@@ -515,6 +533,7 @@ export class AleoWallet {
 			{
 				async onApprove(response, webview) {
 					await webview.destroy();
+					await grantScope(metadata, {type: 'execute', programs: [request.programId]});
 					await emit('transaction_start', 'Transaction started');
 					return new Promise((resolve, reject) => {
 						sessionStorage.setItem('transfer_on', 'true');
@@ -524,6 +543,7 @@ export class AleoWallet {
 
 						invoke<CreateEventResponse>('request_create_event', {
 							request,
+							origin: metadata?.origin,
 							fee_private: feeOption,
 						})
 							.then(async response => {
@@ -567,7 +587,7 @@ export class AleoWallet {
 
 		if (!checkExpired(request_identifier)) {
 			return new Promise((resolve, reject) => {
-				invoke<GetEventResponse>('get_event', {request})
+				invoke<GetEventResponse>('get_event', {request, origin: metadata?.origin})
 					.then(response => {
 						resolve(formatJsonRpcResult(requestEvent.id, response));
 					})
@@ -632,7 +652,13 @@ export class AleoWallet {
 					storeSession(request_identifier);
 
 					try {
-						invoke<GetEventResponse>('get_event', {request})
+						// The event's program is only known once it is read
+						const event = await invoke<AvailEvent>('get_avail_event', {id: request.id});
+						if (event.programId) {
+							await grantScope(metadata, {type: 'read_records', programs: [event.programId]});
+						}
+
+						invoke<GetEventResponse>('get_event', {request, origin: metadata?.origin})
 							.then(response => {
 								resolve(formatJsonRpcResult(requestEvent.id, response));
 							})
@@ -675,7 +701,7 @@ export class AleoWallet {
 
 		if (!checkExpired(request_identifier)) {
 			return new Promise((resolve, reject) => {
-				invoke<GetEventsResponse>('get_events', {request})
+				invoke<GetEventsResponse>('get_events', {request, origin: metadata?.origin})
 					.then(response => {
 						resolve(formatJsonRpcResult(requestEvent.id, response));
 					})
@@ -744,7 +770,11 @@ export class AleoWallet {
 					storeSession(request_identifier);
 
 					try {
-						invoke<GetEventsResponse>('get_events', {request})
+						if (request.filter?.programId) {
+							await grantScope(metadata, {type: 'read_records', programs: [request.filter.programId]});
+						}
+
+						invoke<GetEventsResponse>('get_events', {request, origin: metadata?.origin})
 							.then(response => {
 								resolve(formatJsonRpcResult(requestEvent.id, response));
 							})
@@ -801,7 +831,7 @@ export class AleoWallet {
 
 		if (!checkExpired(request_identifier)) {
 			return new Promise((resolve, reject) => {
-				invoke<GetBackendRecordsResponse>('get_records', {request})
+				invoke<GetBackendRecordsResponse>('get_records', {request, origin: metadata?.origin})
 					.then(response => {
 						const res = convertGetRecordsResponse(response);
 						resolve(formatJsonRpcResult(requestEvent.id, res));
@@ -873,8 +903,13 @@ export class AleoWallet {
 
 					try {
 						console.log('===================> INSIDE GETRECORDS');
+						if (request.filter?.programIds?.length) {
+							await grantScope(metadata, {type: 'read_records', programs: request.filter.programIds});
+						}
+
 						invoke<GetBackendRecordsResponse>('get_records', {
 							request,
+							origin: metadata?.origin,
 						})
 							.then(response => {
 								const res = convertGetRecordsResponse(response);
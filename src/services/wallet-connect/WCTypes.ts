@@ -1,5 +1,9 @@
 export const aleoChain = 'aleo:1';
 
+// Origin of the wallet's own screens, requests relayed from a dapp carry the dapp's origin instead.
+// The backend refuses requests without an origin
+export const walletOrigin = 'avail:wallet';
+
 export enum AleoMethod {
 	ALEO_GETBALANCE = 'getBalance',
 	ALEO_DISCONNECT = 'disconnect',
@@ -57,12 +61,13 @@ export type WalletConnectRequest = {
 	message?: string;
 };
 
-// Stores dapp metadata to display to user
+// Stores dapp metadata to display to user, origin is the one its wallet session is granted to
 export type DAppSession = {
 	name: string;
 	description: string;
 	url: string;
 	img: string;
+	origin: string;
 };
 
 export const dappSession = (
@@ -70,13 +75,32 @@ export const dappSession = (
 	description: string,
 	url: string,
 	img: string,
+	origin: string,
 ): DAppSession => ({
 	name,
 	description,
 	url,
 	img,
+	origin,
 });
 
+// Permission granted to a dapp, program scopes only cover the listed programs
+export type DappScope =
+	| {type: 'read_balance'}
+	| {type: 'read_records'; programs: string[]}
+	| {type: 'decrypt'}
+	| {type: 'sign'}
+	| {type: 'execute'; programs: string[]};
+
+export type DappSessionGrant = {
+	origin: string;
+	address: string;
+	scopes: DappScope[];
+	created_at: number;
+	expires_at?: number;
+	revoked: boolean;
+};
+
 export type RequestSession = {
 	method: string;
 	request: string;
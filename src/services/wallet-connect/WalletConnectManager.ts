@@ -1,4 +1,5 @@
 
+import {invoke} from '@tauri-apps/api/core';
 import {emit, once, emitTo} from '@tauri-apps/api/event';
 import {WebviewWindow} from '@tauri-apps/api/webviewWindow';
 import { getAll } from '@tauri-apps/api/window';
@@ -10,10 +11,32 @@ import {Web3Wallet, type IWeb3Wallet, type Web3WalletTypes} from '@walletconnect
 import {get_address} from '../storage/persistent';
 import {AleoWallet} from './AleoWallet';
 import {SessionInfo} from './SessionInfo';
-import {dappSession, type WalletConnectRequest} from './WCTypes';
+import {
+	type DAppSession, dappSession, type DappSessionGrant, type WalletConnectRequest,
+} from './WCTypes';
 
 type PingEventData = Omit<SignClientTypes.BaseEventArgs, 'params'>;
 
+/**
+ * Revoke the wallet session of the DApp connected under a wallet connect session topic
+ * @param session_topic The wallet connect session topic
+ */
+async function revokeDappSession(session_topic: string) {
+	const dappSessionString = sessionStorage.getItem(session_topic);
+	if (!dappSessionString) {
+		return;
+	}
+
+	const {origin} = JSON.parse(dappSessionString) as DAppSession;
+	sessionStorage.removeItem(session_topic);
+
+	try {
+		await invoke('revoke_dapp_session', {origin});
+	} catch (error) {
+		console.error('Failed to revoke dapp session', error);
+	}
+}
+
 export class WalletConnectManager {
 	theWallet?: IWeb3Wallet;
 	projectId: string;
@@ -91,6 +114,7 @@ export class WalletConnectManager {
 		} */
 		if (this.sessionTopic) {
 			console.log('Closing pairing...');
+			await revokeDappSession(this.sessionTopic);
 			await this.theWallet?.disconnectSession({ topic: this.sessionTopic, reason: getSdkError('USER_DISCONNECTED') });
 			// Await this.theWallet?.core.history.delete(this.sessionTopic);
 		}
@@ -199,6 +223,20 @@ export class WalletConnectManager {
 				});
 				console.log('Approved session', session);
 
+				// Requests are checked against the verified origin of the dapp, the metadata url is self reported
+				let grant: DappSessionGrant;
+				try {
+					grant = await invoke<DappSessionGrant>('grant_dapp_session', {
+						origin: proposal.verifyContext?.verified?.origin || metadata.url,
+						scopes: [{type: 'read_balance'}],
+						expires_in: session.expiry - Math.floor(Date.now() / 1000),
+					});
+				} catch (error) {
+					console.error('Failed to grant dapp session', error);
+					await theWallet.disconnectSession({topic: session.topic, reason: getSdkError('USER_DISCONNECTED')});
+					return;
+				}
+
 				await emit('connected', session);
 
 				this.currentRequestVerifyContext = proposal.verifyContext;
@@ -212,7 +250,7 @@ export class WalletConnectManager {
 				this.sessionTopic = session.topic;
 				console.log('Session topic', this.sessionTopic);
 
-				const dappSess = dappSession(metadata.name, metadata.description, metadata.url, metadata.icons[0]);
+				const dappSess = dappSession(metadata.name, metadata.description, metadata.url, metadata.icons[0], grant.origin);
 
 				console.log('Storing dapp session', dappSess);
 				sessionStorage.setItem(session.topic, JSON.stringify(dappSess));
@@ -300,6 +338,7 @@ export class WalletConnectManager {
 		console.log('Event: session_delete received');
 		console.log(data);
 		//await this.close();
+		await revokeDappSession(data.topic);
 		await emit('disconnected', 'disconnected');
 	}
 
@@ -12,4 +12,7 @@ export type SuccinctAvailEvent = {
 	created: Date;
 	programId?: string;
 	functionId?: string;
+	staking?: StakingEventType;
 };
+
+export type StakingEventType = 'bond' | 'bond_validator' | 'unbond' | 'claim_unbond';
@@ -28,3 +28,16 @@ export type TransferRequest = {
 	fee: number;
 	asset_id: string;
 };
+
+export type ReputationEntry = {
+	target: string;
+	status: 'blocked' | 'verified';
+	reason: string | undefined;
+	source: 'list' | 'user';
+};
+
+export type TransferResponse = {
+	message: string | undefined;
+	error: string | undefined;
+	blocked: ReputationEntry | undefined;
+};